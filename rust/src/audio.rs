@@ -0,0 +1,540 @@
+use core::fmt;
+
+use godot::{
+    classes::{
+        AudioEffectLowPassFilter, AudioServer, AudioStream, AudioStreamPlayer, AudioStreamWav,
+        Engine, INode, ResourceLoader, Tween, audio_stream_wav::Format, node::ProcessMode,
+    },
+    global::linear_to_db,
+    prelude::*,
+};
+
+use crate::{
+    localization::{TextKey, tr},
+    settings::{MUSIC_BUS, SFX_BUS, ensure_bus},
+};
+
+const DEFAULT_MUSIC: &str = "res://assets/musics/House In a Forest Loop.ogg";
+const DEFAULT_GAME_OVER_SOUND: &str = "res://assets/musics/gameover.wav";
+
+/// Volume used for a silent music player, low enough to be inaudible without muting it.
+const SILENCE_DB: f32 = -80f32;
+/// Cutoff of the music low-pass filter when it is not filtering anything audible.
+const OPEN_CUTOFF_HZ: f32 = 20500f32;
+
+enum AudioChild {
+    MusicA,
+    MusicB,
+}
+
+impl fmt::Display for AudioChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioChild::MusicA => write!(f, "MusicA"),
+            AudioChild::MusicB => write!(f, "MusicB"),
+        }
+    }
+}
+
+/// Non-positional music and sound effects. Music alternates between two players on the
+/// `Music` bus so tracks can crossfade, and can be ducked under effects; effects go through a
+/// pool of players on the `SFX` bus so overlapping sounds don't cut each other off.
+#[derive(GodotClass)]
+#[class(base=Node, tool)]
+pub struct AudioManager {
+    #[base]
+    base: Base<Node>,
+    /// Track played during a run. Defaults to the bundled forest loop.
+    #[export]
+    music: Option<Gd<AudioStream>>,
+    /// Effect played on game over. Defaults to the bundled jingle.
+    #[export]
+    game_over_sound: Option<Gd<AudioStream>>,
+    /// Seconds a music change or stop takes to fade.
+    #[export]
+    crossfade_duration: f64,
+    /// Music volume offset, in dB, while ducked.
+    #[export]
+    duck_volume_db: f32,
+    /// Seconds to move in or out of the ducked volume.
+    #[export]
+    duck_duration: f64,
+    /// Number of effects that can play at once before the oldest one is cut.
+    #[export]
+    sfx_pool_size: i32,
+    /// Restarts the current track when it ends.
+    #[export]
+    loop_music: bool,
+    /// How tense the music is, from 0 (calm) to 1 (peak). Driven by `MainScene` from the
+    /// difficulty of the run; every adaptive effect below is derived from it.
+    #[export]
+    #[var(get, set = set_intensity)]
+    intensity: f32,
+    /// Music pitch and tempo at zero intensity.
+    #[export]
+    calm_pitch: f32,
+    /// Music pitch and tempo at full intensity.
+    #[export]
+    intense_pitch: f32,
+    /// Low-pass cutoff the music drops to on a near miss. It drops further the higher the
+    /// intensity, down to half this value.
+    #[export]
+    near_miss_cutoff_hz: f32,
+    /// Seconds for the music to open up again after a near miss.
+    #[export]
+    near_miss_recovery: f64,
+    /// Played on milestones. Defaults to a short generated arpeggio.
+    #[export]
+    stinger: Option<Gd<AudioStream>>,
+    /// Share of the music on the active player; the other one gets the rest.
+    crossfade: f32,
+    /// Overall music level, faded in when music starts and out when it stops.
+    level: f32,
+    /// How far the music is ducked, from 0 (not at all) to 1 (`duck_volume_db`).
+    duck: f32,
+    active: usize,
+    music_tween: Option<Gd<Tween>>,
+    duck_tween: Option<Gd<Tween>>,
+    sfx_players: Vec<Gd<AudioStreamPlayer>>,
+    low_pass: Option<Gd<AudioEffectLowPassFilter>>,
+    low_pass_tween: Option<Gd<Tween>>,
+    music_a: OnReady<Gd<AudioStreamPlayer>>,
+    music_b: OnReady<Gd<AudioStreamPlayer>>,
+}
+
+#[godot_api]
+impl INode for AudioManager {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            music: None,
+            game_over_sound: None,
+            crossfade_duration: 1f64,
+            duck_volume_db: -12f32,
+            duck_duration: 0.4f64,
+            sfx_pool_size: 8,
+            loop_music: true,
+            intensity: 0f32,
+            calm_pitch: 1f32,
+            intense_pitch: 1.12f32,
+            near_miss_cutoff_hz: 900f32,
+            near_miss_recovery: 0.6f64,
+            stinger: None,
+            crossfade: 1f32,
+            level: 0f32,
+            duck: 0f32,
+            active: 0,
+            music_tween: None,
+            duck_tween: None,
+            sfx_players: Vec::new(),
+            low_pass: None,
+            low_pass_tween: None,
+            music_a: OnReady::from_node(&AudioChild::MusicA.to_string()),
+            music_b: OnReady::from_node(&AudioChild::MusicB.to_string()),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        for child in [AudioChild::MusicA, AudioChild::MusicB] {
+            self.base()
+                .try_get_node_as::<AudioStreamPlayer>(&child.to_string())
+                .unwrap_or_else(|| {
+                    let mut player = AudioStreamPlayer::new_alloc();
+                    player.set_name(&child.to_string());
+                    player.set_bus(MUSIC_BUS);
+                    self.base_mut().add_child(&player);
+                    player.set_owner(self.base().to_godot());
+                    player
+                });
+        }
+    }
+
+    fn ready(&mut self) {
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        // Music keeps fading and effects keep playing while the game is paused.
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+
+        if self.music.is_none() {
+            self.music = load_stream(DEFAULT_MUSIC);
+        }
+
+        if self.game_over_sound.is_none() {
+            self.game_over_sound = load_stream(DEFAULT_GAME_OVER_SOUND);
+        }
+
+        if self.stinger.is_none() {
+            self.stinger = Some(stinger_stream().upcast());
+        }
+
+        self.low_pass = Some(music_low_pass());
+
+        let intensity = self.intensity;
+        self.set_intensity(intensity);
+
+        for mut player in self.music_players() {
+            player.set_bus(MUSIC_BUS);
+            player.set_volume_db(SILENCE_DB);
+        }
+
+        self.music_a
+            .signals()
+            .finished()
+            .connect_other(self, Self::on_music_a_finished);
+        self.music_b
+            .signals()
+            .finished()
+            .connect_other(self, Self::on_music_b_finished);
+
+        for _ in 0..self.sfx_pool_size.max(1) {
+            let mut player = AudioStreamPlayer::new_alloc();
+            player.set_bus(SFX_BUS);
+            self.base_mut().add_child(&player);
+            self.sfx_players.push(player);
+        }
+    }
+}
+
+#[godot_api]
+impl AudioManager {
+    /// Plays `stream` as music, crossfading from the current track over `crossfade_duration`.
+    /// Fades in from silence when nothing is playing, and keeps the track going when `stream`
+    /// is already on the active player.
+    #[func]
+    pub fn play_music(&mut self, stream: Gd<AudioStream>) {
+        let mut current = self.music_players()[self.active].clone();
+        let playing = current.is_playing() && self.level > 0f32;
+
+        if playing && current.get_stream().as_ref() == Some(&stream) {
+            self.fade_music(self.crossfade, 1f32, false);
+            return;
+        }
+
+        if !playing {
+            for mut player in self.music_players() {
+                player.stop();
+            }
+
+            current.set_stream(&stream);
+            current.set_stream_paused(false);
+            current.play();
+            self.crossfade = 1f32;
+            self.fade_music(1f32, 1f32, false);
+            return;
+        }
+
+        self.active = 1 - self.active;
+        let mut next = self.music_players()[self.active].clone();
+        next.set_stream(&stream);
+        next.set_stream_paused(false);
+        next.play();
+
+        // The new active player starts silent and takes over the whole mix.
+        self.crossfade = 0f32;
+        self.apply_music_volume();
+        self.fade_music(1f32, 1f32, true);
+    }
+
+    /// Plays the exported `music` track.
+    #[func]
+    pub fn play_default_music(&mut self) {
+        if let Some(stream) = self.music.clone() {
+            self.play_music(stream);
+        }
+    }
+
+    /// Fades the music out over `crossfade_duration` and stops it.
+    #[func]
+    pub fn stop_music(&mut self) {
+        self.fade_music(self.crossfade, 0f32, false);
+    }
+
+    #[func]
+    pub fn set_music_paused(&mut self, paused: bool) {
+        for mut player in self.music_players() {
+            player.set_stream_paused(paused);
+        }
+    }
+
+    /// Lowers the music by `duck_volume_db` when `ducked`, or brings it back otherwise.
+    #[func]
+    pub fn duck_music(&mut self, ducked: bool) {
+        if let Some(mut tween) = self.duck_tween.take() {
+            tween.kill();
+        }
+
+        let target = if ducked { 1f32 } else { 0f32 };
+        let callable = self.base().callable("set_duck");
+        let from = self.duck;
+        let duration = self.duck_duration.max(0f64);
+
+        let tween = self.base_mut().create_tween();
+        if let Some(mut tween) = tween {
+            tween.tween_method(
+                &callable,
+                &from.to_variant(),
+                &target.to_variant(),
+                duration,
+            );
+            self.duck_tween = Some(tween);
+        }
+    }
+
+    /// Plays `stream` on a free effect player, cutting the oldest effect when all are busy.
+    #[func]
+    pub fn play_sfx(&mut self, stream: Gd<AudioStream>, pitch_scale: f32) {
+        let Some(mut player) = self
+            .sfx_players
+            .iter()
+            .find(|player| !player.is_playing())
+            .or_else(|| {
+                self.sfx_players.iter().max_by(|a, b| {
+                    let (mut a, mut b) = ((*a).clone(), (*b).clone());
+                    a.get_playback_position()
+                        .total_cmp(&b.get_playback_position())
+                })
+            })
+            .cloned()
+        else {
+            return;
+        };
+
+        player.set_stream(&stream);
+        player.set_pitch_scale(pitch_scale);
+        player.play();
+    }
+
+    #[func]
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity.clamp(0f32, 1f32);
+
+        if !self.base().is_node_ready() {
+            return;
+        }
+
+        let pitch = self.calm_pitch + (self.intense_pitch - self.calm_pitch) * self.intensity;
+        for mut player in self.music_players() {
+            player.set_pitch_scale(pitch.max(0.01f32));
+        }
+    }
+
+    /// Muffles the music for a moment, as if the player held their breath, then lets it open
+    /// back up over `near_miss_recovery` seconds.
+    #[func]
+    pub fn near_miss(&mut self) {
+        let Some(mut filter) = self.low_pass.clone() else {
+            return;
+        };
+
+        if let Some(mut tween) = self.low_pass_tween.take() {
+            tween.kill();
+        }
+
+        let cutoff = self.near_miss_cutoff_hz * (1f32 - self.intensity / 2f32);
+        filter.set_cutoff(cutoff);
+
+        let duration = self.near_miss_recovery.max(0f64);
+        let tween = self.base_mut().create_tween();
+        if let Some(mut tween) = tween {
+            tween.tween_property(&filter, "cutoff_hz", &OPEN_CUTOFF_HZ.to_variant(), duration);
+            self.low_pass_tween = Some(tween);
+        }
+    }
+
+    /// Plays the `stinger` over the music, pitched with the intensity so later milestones
+    /// sound higher.
+    #[func]
+    pub fn play_stinger(&mut self) {
+        if let Some(stream) = self.stinger.clone() {
+            let pitch = 1f32 + self.intensity / 2f32;
+            self.play_sfx(stream, pitch);
+        }
+    }
+
+    /// Plays the exported `game_over_sound` and ducks the music under it.
+    #[func]
+    pub fn play_game_over(&mut self) {
+        self.duck_music(true);
+
+        if let Some(stream) = self.game_over_sound.clone() {
+            self.play_sfx(stream, 1f32);
+        }
+    }
+
+    #[func]
+    fn set_crossfade(&mut self, crossfade: f32) {
+        self.crossfade = crossfade;
+        self.apply_music_volume();
+    }
+
+    #[func]
+    fn set_level(&mut self, level: f32) {
+        self.level = level;
+        self.apply_music_volume();
+    }
+
+    #[func]
+    fn set_duck(&mut self, duck: f32) {
+        self.duck = duck;
+        self.apply_music_volume();
+    }
+
+    /// Stops every music player that is no longer heard once a fade has finished.
+    #[func]
+    fn on_music_fade_finished(&mut self) {
+        let silent_active = self.level <= 0f32;
+        let active = self.active;
+
+        for (index, mut player) in self.music_players().into_iter().enumerate() {
+            if silent_active || index != active {
+                player.stop();
+            }
+        }
+    }
+
+    #[func]
+    fn on_music_a_finished(&mut self) {
+        self.restart_if_active(0);
+    }
+
+    #[func]
+    fn on_music_b_finished(&mut self) {
+        self.restart_if_active(1);
+    }
+
+    fn restart_if_active(&mut self, index: usize) {
+        if self.loop_music && index == self.active && self.level > 0f32 {
+            self.music_players()[index].play();
+        }
+    }
+
+    /// Tweens the crossfade and the overall level to the given values. When `stop_others` is
+    /// set, or the level reaches zero, the unheard players are stopped at the end.
+    fn fade_music(&mut self, crossfade: f32, level: f32, stop_others: bool) {
+        if let Some(mut tween) = self.music_tween.take() {
+            tween.kill();
+        }
+
+        let duration = self.crossfade_duration.max(0f64);
+        let gd = self.to_gd();
+        let (from_crossfade, from_level) = (self.crossfade, self.level);
+
+        let tween = self.base_mut().create_tween();
+        let Some(mut tween) = tween else {
+            return;
+        };
+
+        if let Some(mut parallel) = tween.set_parallel() {
+            parallel.tween_method(
+                &gd.callable("set_crossfade"),
+                &from_crossfade.to_variant(),
+                &crossfade.to_variant(),
+                duration,
+            );
+            parallel.tween_method(
+                &gd.callable("set_level"),
+                &from_level.to_variant(),
+                &level.to_variant(),
+                duration,
+            );
+        }
+
+        if stop_others || level <= 0f32 {
+            tween.chain();
+            tween.tween_callback(&gd.callable("on_music_fade_finished"));
+        }
+
+        self.music_tween = Some(tween);
+    }
+
+    fn apply_music_volume(&mut self) {
+        let duck_db = self.duck_volume_db * self.duck;
+        let active = self.active;
+
+        for (index, mut player) in self.music_players().into_iter().enumerate() {
+            let weight = if index == active {
+                self.crossfade
+            } else {
+                1f32 - self.crossfade
+            };
+
+            let linear = (weight * self.level).clamp(0f32, 1f32);
+            let volume = if linear <= 0f32 {
+                SILENCE_DB
+            } else {
+                (linear_to_db(linear as f64) as f32).max(SILENCE_DB)
+            };
+
+            player.set_volume_db(volume + duck_db);
+        }
+    }
+
+    fn music_players(&self) -> [Gd<AudioStreamPlayer>; 2] {
+        [self.music_a.clone(), self.music_b.clone()]
+    }
+}
+
+/// Low-pass filter on the `Music` bus used for near misses, added once and left wide open.
+fn music_low_pass() -> Gd<AudioEffectLowPassFilter> {
+    let bus = ensure_bus(MUSIC_BUS);
+    let mut server = AudioServer::singleton();
+
+    let existing = (0..server.get_bus_effect_count(bus)).find_map(|index| {
+        server
+            .get_bus_effect(bus, index)
+            .and_then(|effect| effect.try_cast::<AudioEffectLowPassFilter>().ok())
+    });
+
+    existing.unwrap_or_else(|| {
+        let mut filter = AudioEffectLowPassFilter::new_gd();
+        filter.set_cutoff(OPEN_CUTOFF_HZ);
+        server.add_bus_effect(bus, &filter);
+        filter
+    })
+}
+
+/// Rising three-note arpeggio used as the milestone stinger when none is assigned.
+fn stinger_stream() -> Gd<AudioStreamWav> {
+    tone_stream(&[523.25, 659.25, 783.99], 0.09, 0.4)
+}
+
+/// Generated 16-bit sound playing each of `notes` (in Hz) in turn for `note_duration` seconds,
+/// each fading out linearly from `gain`.
+pub fn tone_stream(notes: &[f32], note_duration: f32, gain: f32) -> Gd<AudioStreamWav> {
+    const MIX_RATE: i32 = 22050;
+
+    let note_samples = (MIX_RATE as f32 * note_duration) as usize;
+    let mut data = PackedByteArray::new();
+
+    for frequency in notes {
+        for index in 0..note_samples {
+            let time = index as f32 / MIX_RATE as f32;
+            let envelope = 1f32 - index as f32 / note_samples as f32;
+            let sample = (time * frequency * std::f32::consts::TAU).sin() * envelope * gain;
+
+            for byte in ((sample * i16::MAX as f32) as i16).to_le_bytes() {
+                data.push(byte);
+            }
+        }
+    }
+
+    let mut stream = AudioStreamWav::new_gd();
+    stream.set_format(Format::FORMAT_16_BITS);
+    stream.set_mix_rate(MIX_RATE);
+    stream.set_data(&data);
+    stream
+}
+
+fn load_stream(path: &str) -> Option<Gd<AudioStream>> {
+    let stream = ResourceLoader::singleton()
+        .load(path)
+        .and_then(|resource| resource.try_cast::<AudioStream>().ok());
+
+    if stream.is_none() {
+        godot_warn!("{}: {path}", tr(TextKey::ErrorLoadAudio));
+    }
+
+    stream
+}
@@ -0,0 +1,409 @@
+use core::fmt;
+use std::f64::consts::TAU;
+
+use godot::{
+    classes::{AnimatableBody2D, AnimatedSprite2D, CollisionShape2D, Engine, IAnimatableBody2D},
+    prelude::*,
+};
+
+use crate::{enemy::EnemyBase, spawn_pattern::SpawnPatterns};
+
+/// Pixels above the spawn bounds the boss enters from and leaves to.
+const OFFSCREEN_MARGIN: f32 = 160f32;
+
+/// When bosses come in a run and how their fights go.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct BossEncounter {
+    #[base]
+    base: Base<Resource>,
+    /// Scores at which a boss comes, lowest first.
+    #[export]
+    pub milestones: PackedInt64Array,
+    /// Seconds the warning is shown, with the spawner already paused, before the boss enters.
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub warning_time: f64,
+    /// Seconds from the boss entering to it leaving.
+    #[export(range = (1.0, 120.0, or_greater))]
+    pub duration: f64,
+    /// Seconds between two attacks.
+    #[export(range = (0.1, 10.0, or_greater))]
+    pub attack_interval: f64,
+    /// Points awarded when the boss leaves.
+    #[export(range = (0.0, 1000.0, or_greater))]
+    pub bonus: i64,
+    /// Share of the spawn ramp the difficulty jumps ahead by after each boss.
+    #[export(range = (0.0, 1.0))]
+    pub difficulty_step: f64,
+    /// Attacks of the boss, used in turn in the order wall, spiral, pincer, burst. Spirals
+    /// and bursts are fired from the boss itself; chances and cooldown are not used.
+    #[export]
+    pub attacks: Option<Gd<SpawnPatterns>>,
+}
+
+#[godot_api]
+impl IResource for BossEncounter {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            milestones: PackedInt64Array::from(&[50, 150, 300]),
+            warning_time: 3f64,
+            duration: 20f64,
+            attack_interval: 2.5f64,
+            bonus: 100,
+            difficulty_step: 0.15f64,
+            attacks: Some(SpawnPatterns::new_gd()),
+        }
+    }
+}
+
+/// Something a `BossFight` calls for as it moves on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BossCue {
+    Enter,
+    /// Attack number `n` of the fight, counted from 0.
+    Attack(usize),
+    Leave,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum BossStage {
+    Warning,
+    Fighting,
+    Over,
+}
+
+/// Timeline of one boss fight: the warning, the boss's attacks, and its leaving.
+pub struct BossFight {
+    stage: BossStage,
+    elapsed: f64,
+    attacks: usize,
+    warning_time: f64,
+    duration: f64,
+    attack_interval: f64,
+}
+
+impl BossFight {
+    pub fn new(encounter: &BossEncounter) -> Self {
+        Self::with_timing(
+            encounter.warning_time,
+            encounter.duration,
+            encounter.attack_interval,
+        )
+    }
+
+    /// A fight with a warning of `warning_time` seconds, lasting `duration` seconds from the
+    /// boss entering, with an attack every `attack_interval` seconds.
+    pub fn with_timing(warning_time: f64, duration: f64, attack_interval: f64) -> Self {
+        Self {
+            stage: BossStage::Warning,
+            elapsed: 0f64,
+            attacks: 0,
+            warning_time: warning_time.max(0f64),
+            duration: duration.max(0f64),
+            attack_interval: attack_interval.max(0.1f64),
+        }
+    }
+
+    /// Whether the boss has been told to leave.
+    pub fn is_over(&self) -> bool {
+        self.stage == BossStage::Over
+    }
+
+    /// Moves `delta` seconds on and returns the cues due by then. The first attack comes one
+    /// interval after the boss enters, giving it time to get into place.
+    pub fn advance(&mut self, delta: f64) -> Vec<BossCue> {
+        let mut cues = Vec::new();
+        self.elapsed += delta;
+
+        if self.stage == BossStage::Warning {
+            if self.elapsed < self.warning_time {
+                return cues;
+            }
+
+            self.elapsed -= self.warning_time;
+            self.stage = BossStage::Fighting;
+            cues.push(BossCue::Enter);
+        }
+
+        if self.stage != BossStage::Fighting {
+            return cues;
+        }
+
+        loop {
+            let next = self.attack_interval * (self.attacks + 1) as f64;
+            if next > self.elapsed || next >= self.duration {
+                break;
+            }
+
+            cues.push(BossCue::Attack(self.attacks));
+            self.attacks += 1;
+        }
+
+        if self.elapsed >= self.duration {
+            self.stage = BossStage::Over;
+            cues.push(BossCue::Leave);
+        }
+
+        cues
+    }
+}
+
+enum BossChild {
+    AnimatedSprite2D,
+    CollisionShape2D,
+}
+
+impl fmt::Display for BossChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BossChild::AnimatedSprite2D => write!(f, "AnimatedSprite2D"),
+            BossChild::CollisionShape2D => write!(f, "CollisionShape2D"),
+        }
+    }
+}
+
+/// A large enemy that comes down from the top, sways across the screen while its fight
+/// lasts, and goes back up. It looks like an enemy of the run, scaled up.
+#[derive(GodotClass)]
+#[class(base=AnimatableBody2D, tool)]
+pub struct BossBase {
+    #[base]
+    base: Base<AnimatableBody2D>,
+    /// How many times larger than a regular enemy the boss is.
+    #[export(range = (1.0, 8.0, or_greater))]
+    pub size_scale: f32,
+    /// Pixels per second when entering and leaving.
+    #[export(range = (0.0, 1000.0, or_greater))]
+    pub speed: f32,
+    /// Pixels the boss sways to each side while it attacks, at most a third of the screen.
+    #[export(range = (0.0, 512.0, or_greater))]
+    pub sway: f32,
+    /// Seconds for one sway from side to side and back.
+    #[export(range = (0.5, 20.0, or_greater))]
+    pub sway_period: f64,
+    hover: Vector2,
+    exit: Vector2,
+    sway_width: f32,
+    arrived: bool,
+    leaving: bool,
+    clock: f64,
+    animated_sprite: OnReady<Gd<AnimatedSprite2D>>,
+    collision_shape: OnReady<Gd<CollisionShape2D>>,
+}
+
+#[godot_api]
+impl IAnimatableBody2D for BossBase {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            size_scale: 3f32,
+            speed: 180f32,
+            sway: 200f32,
+            sway_period: 6f64,
+            hover: Vector2::ZERO,
+            exit: Vector2::ZERO,
+            sway_width: 0f32,
+            arrived: false,
+            leaving: false,
+            clock: 0f64,
+            animated_sprite: OnReady::from_node(&BossChild::AnimatedSprite2D.to_string()),
+            collision_shape: OnReady::from_node(&BossChild::CollisionShape2D.to_string()),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        self.base()
+            .try_get_node_as::<AnimatedSprite2D>(&BossChild::AnimatedSprite2D.to_string())
+            .unwrap_or_else(|| {
+                let mut sprite = AnimatedSprite2D::new_alloc();
+                sprite.set_name(&BossChild::AnimatedSprite2D.to_string());
+                self.base_mut().add_child(&sprite);
+                sprite.set_owner(self.base().to_godot());
+                sprite
+            });
+
+        self.base()
+            .try_get_node_as::<CollisionShape2D>(&BossChild::CollisionShape2D.to_string())
+            .unwrap_or_else(|| {
+                let mut collision_shape = CollisionShape2D::new_alloc();
+                collision_shape.set_name(&BossChild::CollisionShape2D.to_string());
+                self.base_mut().add_child(&collision_shape);
+                collision_shape.set_owner(self.base().to_godot());
+                collision_shape
+            });
+    }
+
+    fn ready(&mut self) {
+        // The boss only has to be felt by the player's hurtbox and graze ring.
+        self.base_mut().set_collision_mask(0);
+        self.base_mut().set_sync_to_physics(false);
+        self.stop();
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        if Engine::singleton().is_editor_hint() {
+            self.base_mut().set_physics_process(false);
+            return;
+        }
+
+        if !self.base().is_visible() {
+            return;
+        }
+
+        let position = self.base().get_position();
+        let step = self.speed * delta as f32;
+
+        let next = if self.leaving {
+            position.move_toward(self.exit, step)
+        } else if !self.arrived {
+            self.arrived = position.distance_to(self.hover) <= step;
+            position.move_toward(self.hover, step)
+        } else {
+            self.clock += delta;
+            let sway = (self.clock * TAU / self.sway_period.max(0.5f64)).sin() as f32;
+            Vector2::new(self.hover.x + self.sway_width * sway, self.hover.y)
+        };
+
+        self.base_mut().set_position(next);
+
+        if self.leaving && position.distance_to(self.exit) <= step {
+            self.stop();
+        }
+    }
+}
+
+#[godot_api]
+impl BossBase {
+    /// Takes the frames and shape of `enemy`, scaled up by `size_scale`.
+    pub fn mimic(&mut self, enemy: &Gd<EnemyBase>) {
+        let frames = enemy.bind().get_sprite_frames();
+        let shape = enemy.bind().get_shape();
+        let scale = Vector2::ONE * self.size_scale;
+
+        self.animated_sprite.set_scale(scale);
+        if let Some(frames) = frames {
+            self.animated_sprite.set_sprite_frames(&frames);
+        }
+
+        self.collision_shape.set_scale(scale);
+        if let Some(shape) = shape {
+            self.collision_shape.set_shape(&shape);
+        }
+    }
+
+    /// Brings the boss down from above `bounds` to hover near their top, looking like
+    /// `archetype`.
+    pub fn enter(&mut self, bounds: Rect2, archetype: StringName) {
+        let top = Vector2::new(bounds.center().x, bounds.position.y);
+        self.exit = top + Vector2::UP * OFFSCREEN_MARGIN;
+        self.hover = top + Vector2::DOWN * bounds.size.y * 0.2f32;
+        self.sway_width = self.sway.min(bounds.size.x / 3f32);
+        self.arrived = false;
+        self.leaving = false;
+        self.clock = 0f64;
+
+        self.animated_sprite.set_animation(&archetype);
+        self.animated_sprite.play();
+        self.collision_shape
+            .set_deferred("disabled", &false.to_variant());
+
+        let exit = self.exit;
+        self.base_mut().set_position(exit);
+        self.base_mut().show();
+    }
+
+    /// Sends the boss back up where it came from; it hides once off screen.
+    pub fn leave(&mut self) {
+        self.leaving = true;
+    }
+
+    /// Hides the boss at once and disables its collision.
+    pub fn stop(&mut self) {
+        self.leaving = false;
+        self.base_mut().hide();
+        self.animated_sprite.stop();
+        self.collision_shape
+            .set_deferred("disabled", &true.to_variant());
+    }
+
+    /// Whether the boss is on screen, including on its way in or out.
+    #[func]
+    pub fn is_active(&self) -> bool {
+        self.base().is_visible()
+    }
+
+    /// Archetype the boss looks like, shared by the enemies it sends.
+    #[func]
+    pub fn get_archetype(&self) -> StringName {
+        self.animated_sprite.get_animation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boss_enters_after_the_warning() {
+        let mut fight = BossFight::with_timing(3f64, 20f64, 2.5f64);
+
+        assert!(fight.advance(2.9f64).is_empty());
+        assert_eq!(fight.advance(0.1f64), [BossCue::Enter]);
+        assert!(fight.advance(2f64).is_empty());
+        assert_eq!(fight.advance(0.5f64), [BossCue::Attack(0)]);
+        assert!(!fight.is_over());
+    }
+
+    #[test]
+    fn large_delta_catches_up_on_every_cue() {
+        let mut fight = BossFight::with_timing(1f64, 10f64, 2f64);
+
+        assert_eq!(
+            fight.advance(8f64),
+            [
+                BossCue::Enter,
+                BossCue::Attack(0),
+                BossCue::Attack(1),
+                BossCue::Attack(2),
+            ]
+        );
+        assert_eq!(fight.advance(100f64), [BossCue::Attack(3), BossCue::Leave]);
+        assert!(fight.is_over());
+        assert!(fight.advance(100f64).is_empty());
+    }
+
+    #[test]
+    fn no_attack_at_or_past_the_duration() {
+        // The fourth attack would come just as the boss leaves.
+        let mut fight = BossFight::with_timing(0f64, 8f64, 2f64);
+
+        let cues = fight.advance(8f64);
+
+        assert_eq!(cues.last(), Some(&BossCue::Leave));
+        assert!(!cues.contains(&BossCue::Attack(3)));
+        assert_eq!(
+            cues.iter()
+                .filter(|cue| matches!(cue, BossCue::Attack(_)))
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn timing_is_clamped() {
+        let mut fight = BossFight::with_timing(-1f64, -1f64, 0f64);
+
+        assert_eq!(fight.advance(0f64), [BossCue::Enter, BossCue::Leave]);
+        assert!(fight.is_over());
+
+        // A tiny interval still attacks at most ten times a second.
+        let mut fight = BossFight::with_timing(0f64, 1f64, 0.001f64);
+        let attacks = fight
+            .advance(1f64)
+            .into_iter()
+            .filter(|cue| matches!(cue, BossCue::Attack(_)))
+            .count();
+        assert!(attacks <= 10, "{attacks}");
+    }
+}
@@ -0,0 +1,407 @@
+use core::fmt;
+
+use godot::{
+    builtin::Side,
+    classes::{
+        Button, CanvasLayer, Control, Engine, GridContainer, ICanvasLayer, InputEvent, Label,
+        PanelContainer, Time, VBoxContainer,
+        box_container::AlignmentMode,
+        control::{LayoutPreset, SizeFlags},
+        node::{AutoTranslateMode, ProcessMode},
+        text_server::AutowrapMode,
+    },
+    global::HorizontalAlignment,
+    prelude::*,
+};
+
+use crate::{
+    hud_theme::{SMALL_LABEL, hud_theme, place},
+    input_bindings::{self, GameAction, binding_for, cancels_capture},
+    localization::{TextKey, tr},
+};
+
+/// Drawn above the settings panel it is opened from.
+const CONTROLS_LAYER: i32 = 3;
+/// Time in milliseconds within which a second Escape or Back press binds it instead of
+/// calling off the capture.
+const CANCEL_WINDOW_MSEC: u64 = 500;
+
+enum ControlsPanelChild {
+    Panel,
+    Content,
+    Grid,
+    Status,
+    CancelButton,
+    ResetButton,
+    BackButton,
+}
+
+impl fmt::Display for ControlsPanelChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlsPanelChild::Panel => write!(f, "Panel"),
+            ControlsPanelChild::Content => write!(f, "Panel/Content"),
+            ControlsPanelChild::Grid => write!(f, "Panel/Content/Grid"),
+            ControlsPanelChild::Status => write!(f, "Panel/Content/Status"),
+            ControlsPanelChild::CancelButton => write!(f, "Panel/Content/CancelButton"),
+            ControlsPanelChild::ResetButton => write!(f, "Panel/Content/ResetButton"),
+            ControlsPanelChild::BackButton => write!(f, "Panel/Content/BackButton"),
+        }
+    }
+}
+
+impl ControlsPanelChild {
+    /// Name of the node itself, without the path to it.
+    fn name(&self) -> String {
+        let path = self.to_string();
+        path.rsplit('/').next().unwrap_or_default().to_string()
+    }
+}
+
+/// Rebinding screen for every `GameAction`. Pressing an action's button captures the next
+/// key, gamepad button or stick direction; it replaces the action's bindings for that
+/// device, is taken away from any other action it was bound to, and is saved right away.
+/// A capture is called off with its Cancel button, or with Escape or the gamepad's Back
+/// button; pressing either twice in quick succession binds it instead.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer, tool)]
+pub struct ControlsPanel {
+    #[base]
+    base: Base<CanvasLayer>,
+    capturing: Option<GameAction>,
+    /// Escape or Back pressed during the capture, and when, waiting for a second press.
+    pending_cancel: Option<(Gd<InputEvent>, u64)>,
+    return_focus: Option<Gd<Control>>,
+    bindings: Vec<(GameAction, Gd<Button>)>,
+    grid: OnReady<Gd<GridContainer>>,
+    status: OnReady<Gd<Label>>,
+    cancel_button: OnReady<Gd<Button>>,
+    reset_button: OnReady<Gd<Button>>,
+    back_button: OnReady<Gd<Button>>,
+}
+
+#[godot_api]
+impl ICanvasLayer for ControlsPanel {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            capturing: None,
+            pending_cancel: None,
+            return_focus: None,
+            bindings: Vec::new(),
+            grid: OnReady::from_node(&ControlsPanelChild::Grid.to_string()),
+            status: OnReady::from_node(&ControlsPanelChild::Status.to_string()),
+            cancel_button: OnReady::from_node(&ControlsPanelChild::CancelButton.to_string()),
+            reset_button: OnReady::from_node(&ControlsPanelChild::ResetButton.to_string()),
+            back_button: OnReady::from_node(&ControlsPanelChild::BackButton.to_string()),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        let owner = self.base().clone().upcast::<Node>();
+
+        let mut panel = self
+            .base()
+            .try_get_node_as::<PanelContainer>(&ControlsPanelChild::Panel.to_string())
+            .unwrap_or_else(|| {
+                let mut panel = PanelContainer::new_alloc();
+                panel.set_name(&ControlsPanelChild::Panel.to_string());
+                panel.set_theme(&hud_theme());
+                place(
+                    &mut panel,
+                    LayoutPreset::CENTER,
+                    [-230f32, -280f32, 230f32, 280f32],
+                );
+                self.base_mut().add_child(&panel);
+                panel.set_owner(&owner);
+                panel
+            });
+
+        let mut content = panel
+            .try_get_node_as::<VBoxContainer>(&ControlsPanelChild::Content.name())
+            .unwrap_or_else(|| {
+                let mut content = VBoxContainer::new_alloc();
+                content.set_name(&ControlsPanelChild::Content.name());
+                content.set_alignment(AlignmentMode::CENTER);
+                panel.add_child(&content);
+                content.set_owner(&owner);
+                content
+            });
+
+        let mut grid = content
+            .try_get_node_as::<GridContainer>(&ControlsPanelChild::Grid.name())
+            .unwrap_or_else(|| {
+                let mut grid = GridContainer::new_alloc();
+                grid.set_name(&ControlsPanelChild::Grid.name());
+                grid.set_columns(2);
+                content.add_child(&grid);
+                grid.set_owner(&owner);
+                grid
+            });
+
+        for action in GameAction::ALL {
+            let name = action.to_string();
+
+            if grid.has_node(&name) {
+                continue;
+            }
+
+            let mut label = Label::new_alloc();
+            label.set_name(&format!("{name}Label"));
+            label.set_theme_type_variation(SMALL_LABEL);
+            label.set_text(&action.text_key().to_string());
+            grid.add_child(&label);
+            label.set_owner(&owner);
+
+            let mut button = Button::new_alloc();
+            button.set_name(&name);
+            button.set_h_size_flags(SizeFlags::EXPAND_FILL);
+            button.set_custom_minimum_size(Vector2::new(200f32, 0f32));
+            button.set_clip_text(true);
+            button.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+            grid.add_child(&button);
+            button.set_owner(&owner);
+        }
+
+        if !content.has_node(&ControlsPanelChild::Status.name()) {
+            let mut label = Label::new_alloc();
+            label.set_name(&ControlsPanelChild::Status.name());
+            label.set_theme_type_variation(SMALL_LABEL);
+            label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+            label.set_autowrap_mode(AutowrapMode::WORD_SMART);
+            label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+            content.add_child(&label);
+            label.set_owner(&owner);
+        }
+
+        for (child, key) in [
+            (ControlsPanelChild::CancelButton, TextKey::ControlsCancel),
+            (ControlsPanelChild::ResetButton, TextKey::ControlsReset),
+            (ControlsPanelChild::BackButton, TextKey::MenuBack),
+        ] {
+            if content.has_node(&child.name()) {
+                continue;
+            }
+
+            let mut button = Button::new_alloc();
+            button.set_name(&child.name());
+            button.set_text(&key.to_string());
+            button.set_h_size_flags(SizeFlags::SHRINK_CENTER);
+            content.add_child(&button);
+            button.set_owner(&owner);
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_layer(CONTROLS_LAYER);
+
+        for action in GameAction::ALL {
+            let Some(button) = self.grid.try_get_node_as::<Button>(&action.to_string()) else {
+                continue;
+            };
+
+            let panel = self.to_gd();
+            button.signals().pressed().connect(move || {
+                panel.clone().bind_mut().begin_capture(action);
+            });
+
+            self.bindings.push((action, button));
+        }
+
+        self.cancel_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_cancel_pressed);
+        self.reset_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_reset_pressed);
+        self.back_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::close);
+
+        self.setup_focus_wrap();
+
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        // Reachable from the pause screen through the settings panel.
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+        self.base_mut().hide();
+        self.cancel_button.hide();
+    }
+
+    fn process(&mut self, _delta: f64) {
+        let Some((_, pressed_at)) = self.pending_cancel else {
+            return;
+        };
+
+        if Time::singleton().get_ticks_msec() - pressed_at > CANCEL_WINDOW_MSEC {
+            self.end_capture(String::new());
+        }
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        let Some(action) = self.capturing else {
+            return;
+        };
+
+        let Some(binding) = binding_for(&event) else {
+            return;
+        };
+
+        let pending = self.pending_cancel.take();
+
+        if cancels_capture(&binding)
+            && !pending.is_some_and(|(pressed, _)| pressed.is_match(&binding))
+        {
+            self.pending_cancel = Some((binding, Time::singleton().get_ticks_msec()));
+
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        let status = match action.rebind(&binding) {
+            Some(conflict) => format!(
+                "{} {}",
+                tr(TextKey::ControlsConflict),
+                tr(conflict.text_key())
+            ),
+            None => String::new(),
+        };
+
+        input_bindings::save();
+        self.end_capture(status);
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        if !self.base().is_visible() || !event.is_action_pressed("ui_cancel") {
+            return;
+        }
+
+        self.close();
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+    }
+}
+
+#[godot_api]
+impl ControlsPanel {
+    #[signal]
+    pub fn closed();
+
+    /// Shows the panel and remembers the focused control, which gets the focus back on
+    /// `close`.
+    #[func]
+    pub fn open(&mut self) {
+        self.capturing = None;
+        self.pending_cancel = None;
+        self.cancel_button.hide();
+        self.status.set_text("");
+        self.refresh_bindings();
+
+        self.return_focus = self
+            .base()
+            .get_viewport()
+            .and_then(|viewport| viewport.gui_get_focus_owner());
+
+        self.base_mut().show();
+
+        if let Some((_, button)) = self.bindings.first_mut() {
+            button.grab_focus();
+        }
+    }
+
+    #[func]
+    pub fn close(&mut self) {
+        if !self.base().is_visible() {
+            return;
+        }
+
+        self.capturing = None;
+        self.pending_cancel = None;
+        self.base_mut().hide();
+
+        if let Some(mut control) = self.return_focus.take()
+            && control.is_instance_valid()
+            && control.is_visible_in_tree()
+        {
+            control.grab_focus();
+        }
+
+        self.signals().closed().emit();
+    }
+
+    #[func]
+    fn on_cancel_pressed(&mut self) {
+        self.end_capture(String::new());
+    }
+
+    #[func]
+    fn on_reset_pressed(&mut self) {
+        input_bindings::reset_to_defaults();
+        self.capturing = None;
+        self.pending_cancel = None;
+        self.cancel_button.hide();
+        self.status.set_text("");
+        self.refresh_bindings();
+    }
+
+    fn begin_capture(&mut self, action: GameAction) {
+        self.capturing = Some(action);
+        self.status.set_text(&tr(TextKey::ControlsPrompt));
+        self.cancel_button.show();
+
+        if let Some((_, button)) = self.bindings.iter_mut().find(|(bound, _)| *bound == action) {
+            button.set_text("...");
+        }
+    }
+
+    fn end_capture(&mut self, status: String) {
+        let action = self.capturing.take();
+        self.pending_cancel = None;
+        self.status.set_text(&status);
+        self.cancel_button.hide();
+        self.refresh_bindings();
+
+        if let Some((_, button)) = self
+            .bindings
+            .iter_mut()
+            .find(|(bound, _)| Some(*bound) == action)
+        {
+            button.grab_focus();
+        }
+    }
+
+    fn refresh_bindings(&mut self) {
+        for (action, button) in self.bindings.iter_mut() {
+            button.set_text(&action.describe());
+        }
+    }
+
+    /// Wraps focus between the first binding and the back button.
+    fn setup_focus_wrap(&mut self) {
+        let Some((_, first)) = self.bindings.first() else {
+            return;
+        };
+
+        let mut first = first.clone().upcast::<Control>();
+        let mut last = self.back_button.clone().upcast::<Control>();
+
+        let to_last = first.get_path_to(&last);
+        let to_first = last.get_path_to(&first);
+
+        first.set_focus_neighbor(Side::TOP, &to_last);
+        last.set_focus_neighbor(Side::BOTTOM, &to_first);
+    }
+}
@@ -0,0 +1,323 @@
+use core::fmt;
+use std::collections::BTreeMap;
+
+use godot::{
+    classes::{ConfigFile, RandomNumberGenerator, Time},
+    global::Error,
+    prelude::*,
+};
+
+use crate::localization::{TextKey, tr};
+
+const DAILY_RESULTS_PATH: &str = "user://daily.cfg";
+
+/// A calendar day, in the local time of the system.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today() -> Self {
+        let date = Time::singleton().get_date_dict_from_system();
+        let read = |key: &str| date.get(key).and_then(|value| value.try_to::<i64>().ok());
+
+        Self {
+            year: read("year").unwrap_or(1970) as i32,
+            month: read("month").unwrap_or(1) as u32,
+            day: read("day").unwrap_or(1) as u32,
+        }
+    }
+
+    /// Parses the `YYYY-MM-DD` form `Display` writes.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.splitn(3, '-');
+        let date = Self {
+            year: parts.next()?.parse().ok()?,
+            month: parts.next()?.parse().ok()?,
+            day: parts.next()?.parse().ok()?,
+        };
+
+        let valid = (1..=12).contains(&date.month)
+            && (1..=days_in_month(date.year, date.month)).contains(&date.day);
+        valid.then_some(date)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Day of the week of `day`, from 0 for Sunday to 6 for Saturday.
+pub fn weekday(year: i32, month: u32, day: u32) -> u32 {
+    const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+
+    let year = if month < 3 { year - 1 } else { year };
+    let days = year + year / 4 - year / 100 + year / 400 + OFFSETS[month as usize - 1] + day as i32;
+    days.rem_euclid(7) as u32
+}
+
+/// The rules of a day's challenge: everyone playing on the same date gets the same seed,
+/// enemy speed and archetype mix.
+#[derive(Clone, Debug)]
+pub struct DailyChallenge {
+    pub date: Date,
+    pub seed: i64,
+    /// Factor applied to the speed of every enemy, from 0.8 to 1.4.
+    pub speed_scale: f32,
+    /// Index, wrapped to the available archetypes, of the one the day favours.
+    featured: u32,
+    /// Share of the spawns taking the featured archetype, from 0.4 to 0.8.
+    featured_share: f32,
+}
+
+impl DailyChallenge {
+    pub fn for_date(date: Date) -> Self {
+        let seed = (fnv1a(date.to_string().as_bytes()) & i64::MAX as u64) as i64;
+
+        // Modifiers come from their own generator so they don't shift the spawn sequence.
+        let mut rng = RandomNumberGenerator::new_gd();
+        rng.set_seed(seed as u64 ^ 0x9e37_79b9_7f4a_7c15);
+
+        Self {
+            date,
+            seed,
+            speed_scale: 0.8f32 + rng.randi_range(0, 12) as f32 * 0.05f32,
+            featured: rng.randi(),
+            featured_share: 0.4f32 + rng.randi_range(0, 4) as f32 * 0.1f32,
+        }
+    }
+
+    pub fn today() -> Self {
+        Self::for_date(Date::today())
+    }
+
+    /// Picks an archetype among `archetypes` with the day's mix, drawing from `rng`.
+    pub fn pick_archetype(
+        &self,
+        rng: &mut Gd<RandomNumberGenerator>,
+        archetypes: &PackedStringArray,
+    ) -> Option<GString> {
+        if archetypes.is_empty() {
+            return None;
+        }
+
+        let index = if rng.randf() < self.featured_share {
+            self.featured as usize % archetypes.len()
+        } else {
+            rng.randi_range(0, archetypes.len() as i32 - 1) as usize
+        };
+
+        archetypes.get(index)
+    }
+}
+
+/// 64-bit FNV-1a, stable across platforms and releases, unlike `std`'s hasher.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The scored attempt of a day.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DailyResult {
+    pub score: i64,
+    pub time: f64,
+}
+
+/// Scored attempt of every day played, persisted in `user://daily.cfg` with one section per
+/// date.
+#[derive(Clone, Default)]
+pub struct DailyResults {
+    results: BTreeMap<Date, DailyResult>,
+}
+
+impl DailyResults {
+    pub fn load() -> Self {
+        let mut config = ConfigFile::new_gd();
+
+        if config.load(DAILY_RESULTS_PATH) != Error::OK {
+            return Self::default();
+        }
+
+        let results = config
+            .get_sections()
+            .as_slice()
+            .iter()
+            .filter_map(|section| {
+                let date = Date::parse(&section.to_string())?;
+                let read = |key: &str| {
+                    config
+                        .get_value_ex(section, key)
+                        .default(&0.to_variant())
+                        .done()
+                };
+
+                let result = DailyResult {
+                    score: read("score").try_to().unwrap_or_default(),
+                    time: read("time").try_to().unwrap_or_default(),
+                };
+                Some((date, result))
+            })
+            .collect();
+
+        Self { results }
+    }
+
+    pub fn save(&self) {
+        let mut config = ConfigFile::new_gd();
+
+        for (date, result) in &self.results {
+            let section = date.to_string();
+            config.set_value(&section, "score", &result.score.to_variant());
+            config.set_value(&section, "time", &result.time.to_variant());
+        }
+
+        if config.save(DAILY_RESULTS_PATH) != Error::OK {
+            godot_warn!("{}", tr(TextKey::ErrorSaveDaily));
+        }
+    }
+
+    pub fn get(&self, date: Date) -> Option<DailyResult> {
+        self.results.get(&date).copied()
+    }
+
+    /// Keeps `result` as the attempt of `date`, unless that day already has one. Returns
+    /// whether it was kept.
+    pub fn record(&mut self, date: Date, result: DailyResult) -> bool {
+        if self.results.contains_key(&date) {
+            return false;
+        }
+
+        self.results.insert(date, result);
+        true
+    }
+
+    /// Replaces the attempt of `date` already kept by `record` with `result`.
+    pub fn update(&mut self, date: Date, result: DailyResult) {
+        if let Some(kept) = self.results.get_mut(&date) {
+            *kept = result;
+        }
+    }
+
+    /// The day with the highest score, the earliest on a tie.
+    pub fn best(&self) -> Option<(Date, DailyResult)> {
+        self.results
+            .iter()
+            .rev()
+            .max_by_key(|(_, result)| result.score)
+            .map(|(date, result)| (*date, *result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    fn result(score: i64) -> DailyResult {
+        DailyResult { score, time: 0f64 }
+    }
+
+    #[test]
+    fn parse_reads_what_display_writes() {
+        let day = date(2024, 2, 29);
+        assert_eq!(Date::parse(&day.to_string()), Some(day));
+        assert_eq!(Date::parse("0987-01-05"), Some(date(987, 1, 5)));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_text() {
+        assert_eq!(Date::parse(""), None);
+        assert_eq!(Date::parse("2024-03"), None);
+        assert_eq!(Date::parse("2024/03/01"), None);
+        assert_eq!(Date::parse("2024-03-x"), None);
+        assert_eq!(Date::parse("2024--3-01"), None);
+    }
+
+    #[test]
+    fn parse_rejects_days_out_of_range() {
+        assert_eq!(Date::parse("2024-00-10"), None);
+        assert_eq!(Date::parse("2024-13-10"), None);
+        assert_eq!(Date::parse("2024-04-00"), None);
+        assert_eq!(Date::parse("2024-04-31"), None);
+        assert_eq!(Date::parse("2023-02-29"), None);
+        assert_eq!(Date::parse("1900-02-29"), None);
+    }
+
+    #[test]
+    fn february_follows_the_leap_year_rules() {
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2023, 2), 28);
+        assert_eq!(days_in_month(2024, 4), 30);
+        assert_eq!(days_in_month(2024, 12), 31);
+    }
+
+    #[test]
+    fn weekday_of_known_dates() {
+        assert_eq!(weekday(1970, 1, 1), 4);
+        assert_eq!(weekday(2000, 2, 29), 2);
+        assert_eq!(weekday(2000, 3, 1), 3);
+        assert_eq!(weekday(2023, 12, 31), 0);
+        assert_eq!(weekday(2024, 1, 1), 1);
+        assert_eq!(weekday(2024, 6, 15), 6);
+    }
+
+    #[test]
+    fn record_keeps_one_attempt_per_day() {
+        let mut results = DailyResults::default();
+        let day = date(2024, 5, 1);
+
+        assert!(results.record(day, result(10)));
+        assert!(!results.record(day, result(50)));
+        assert_eq!(results.get(day).map(|kept| kept.score), Some(10));
+        assert!(results.get(date(2024, 5, 2)).is_none());
+    }
+
+    #[test]
+    fn update_only_replaces_a_recorded_attempt() {
+        let mut results = DailyResults::default();
+        let day = date(2024, 5, 1);
+
+        results.update(day, result(20));
+        assert!(results.get(day).is_none());
+
+        results.record(day, result(10));
+        results.update(day, result(30));
+        assert_eq!(results.get(day).map(|kept| kept.score), Some(30));
+    }
+
+    #[test]
+    fn best_is_the_earliest_of_the_highest_scores() {
+        let mut results = DailyResults::default();
+        assert!(results.best().is_none());
+
+        results.record(date(2024, 5, 3), result(40));
+        results.record(date(2024, 5, 1), result(10));
+        results.record(date(2024, 4, 30), result(40));
+        results.record(date(2024, 5, 2), result(25));
+
+        let (day, best) = results.best().unwrap();
+        assert_eq!(day, date(2024, 4, 30));
+        assert_eq!(best.score, 40);
+    }
+}
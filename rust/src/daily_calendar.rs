@@ -0,0 +1,394 @@
+use core::fmt;
+
+use godot::{
+    builtin::Side,
+    classes::{
+        Button, CanvasLayer, Control, Engine, GridContainer, HBoxContainer, ICanvasLayer,
+        InputEvent, Label, PanelContainer, VBoxContainer,
+        box_container::AlignmentMode,
+        control::{LayoutPreset, SizeFlags},
+        node::AutoTranslateMode,
+    },
+    global::{HorizontalAlignment, VerticalAlignment},
+    prelude::*,
+};
+
+use crate::{
+    daily::{DailyChallenge, DailyResults, Date, days_in_month, weekday},
+    hud_theme::{SMALL_LABEL, hud_theme, place},
+    localization::{TextKey, tr},
+};
+
+/// Drawn above the HUD and the menu, like the settings panel.
+const CALENDAR_LAYER: i32 = 2;
+
+/// Tint of the day cells that hold a result.
+const PLAYED_COLOR: Color = Color::from_rgb(0.5f32, 1f32, 0.5f32);
+
+/// Tint of today's cell.
+const TODAY_COLOR: Color = Color::from_rgb(1f32, 0.85f32, 0.3f32);
+
+/// Tint of days without a result.
+const EMPTY_COLOR: Color = Color::from_rgb(0.6f32, 0.6f32, 0.6f32);
+
+enum DailyCalendarChild {
+    Panel,
+    Content,
+    Header,
+    PreviousButton,
+    MonthLabel,
+    NextButton,
+    Grid,
+    Today,
+    Best,
+    BackButton,
+}
+
+impl fmt::Display for DailyCalendarChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DailyCalendarChild::Panel => write!(f, "Panel"),
+            DailyCalendarChild::Content => write!(f, "Panel/Content"),
+            DailyCalendarChild::Header => write!(f, "Panel/Content/Header"),
+            DailyCalendarChild::PreviousButton => write!(f, "Panel/Content/Header/PreviousButton"),
+            DailyCalendarChild::MonthLabel => write!(f, "Panel/Content/Header/MonthLabel"),
+            DailyCalendarChild::NextButton => write!(f, "Panel/Content/Header/NextButton"),
+            DailyCalendarChild::Grid => write!(f, "Panel/Content/Grid"),
+            DailyCalendarChild::Today => write!(f, "Panel/Content/Today"),
+            DailyCalendarChild::Best => write!(f, "Panel/Content/Best"),
+            DailyCalendarChild::BackButton => write!(f, "Panel/Content/BackButton"),
+        }
+    }
+}
+
+impl DailyCalendarChild {
+    /// Name of the node itself, without the path to it.
+    fn name(&self) -> String {
+        let path = self.to_string();
+        path.rsplit('/').next().unwrap_or_default().to_string()
+    }
+}
+
+/// Month view of the daily challenge results, opened from the main menu. Every played day
+/// shows its score; below are today's modifiers and the best day.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer, tool)]
+pub struct DailyCalendar {
+    #[base]
+    base: Base<CanvasLayer>,
+    results: DailyResults,
+    /// Year and month on display.
+    month: (i32, u32),
+    return_focus: Option<Gd<Control>>,
+    previous_button: OnReady<Gd<Button>>,
+    month_label: OnReady<Gd<Label>>,
+    next_button: OnReady<Gd<Button>>,
+    grid: OnReady<Gd<GridContainer>>,
+    today: OnReady<Gd<Label>>,
+    best: OnReady<Gd<Label>>,
+    back_button: OnReady<Gd<Button>>,
+}
+
+#[godot_api]
+impl ICanvasLayer for DailyCalendar {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            results: DailyResults::default(),
+            month: (1970, 1),
+            return_focus: None,
+            previous_button: OnReady::from_node(&DailyCalendarChild::PreviousButton.to_string()),
+            month_label: OnReady::from_node(&DailyCalendarChild::MonthLabel.to_string()),
+            next_button: OnReady::from_node(&DailyCalendarChild::NextButton.to_string()),
+            grid: OnReady::from_node(&DailyCalendarChild::Grid.to_string()),
+            today: OnReady::from_node(&DailyCalendarChild::Today.to_string()),
+            best: OnReady::from_node(&DailyCalendarChild::Best.to_string()),
+            back_button: OnReady::from_node(&DailyCalendarChild::BackButton.to_string()),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        let owner = self.base().clone().upcast::<Node>();
+
+        let mut panel = self
+            .base()
+            .try_get_node_as::<PanelContainer>(&DailyCalendarChild::Panel.to_string())
+            .unwrap_or_else(|| {
+                let mut panel = PanelContainer::new_alloc();
+                panel.set_name(&DailyCalendarChild::Panel.to_string());
+                panel.set_theme(&hud_theme());
+                place(
+                    &mut panel,
+                    LayoutPreset::CENTER,
+                    [-230f32, -270f32, 230f32, 270f32],
+                );
+                self.base_mut().add_child(&panel);
+                panel.set_owner(&owner);
+                panel
+            });
+
+        let mut content = panel
+            .try_get_node_as::<VBoxContainer>(&DailyCalendarChild::Content.name())
+            .unwrap_or_else(|| {
+                let mut content = VBoxContainer::new_alloc();
+                content.set_name(&DailyCalendarChild::Content.name());
+                content.set_alignment(AlignmentMode::CENTER);
+                panel.add_child(&content);
+                content.set_owner(&owner);
+                content
+            });
+
+        let mut header = content
+            .try_get_node_as::<HBoxContainer>(&DailyCalendarChild::Header.name())
+            .unwrap_or_else(|| {
+                let mut header = HBoxContainer::new_alloc();
+                header.set_name(&DailyCalendarChild::Header.name());
+                header.set_alignment(AlignmentMode::CENTER);
+                content.add_child(&header);
+                header.set_owner(&owner);
+                header
+            });
+
+        for (child, text) in [
+            (DailyCalendarChild::PreviousButton, "<"),
+            (DailyCalendarChild::MonthLabel, ""),
+            (DailyCalendarChild::NextButton, ">"),
+        ] {
+            if header.has_node(&child.name()) {
+                continue;
+            }
+
+            let mut control = match child {
+                DailyCalendarChild::MonthLabel => {
+                    let mut label = Label::new_alloc();
+                    label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+                    label.set_custom_minimum_size(Vector2::new(160f32, 0f32));
+                    label.upcast::<Control>()
+                }
+                _ => {
+                    let mut button = Button::new_alloc();
+                    button.set_text(text);
+                    button.upcast::<Control>()
+                }
+            };
+            control.set_name(&child.name());
+            control.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+            header.add_child(&control);
+            control.set_owner(&owner);
+        }
+
+        if !content.has_node(&DailyCalendarChild::Grid.name()) {
+            let mut grid = GridContainer::new_alloc();
+            grid.set_name(&DailyCalendarChild::Grid.name());
+            grid.set_columns(7);
+            grid.set_v_size_flags(SizeFlags::EXPAND_FILL);
+            content.add_child(&grid);
+            grid.set_owner(&owner);
+        }
+
+        for child in [DailyCalendarChild::Today, DailyCalendarChild::Best] {
+            if content.has_node(&child.name()) {
+                continue;
+            }
+
+            let mut label = Label::new_alloc();
+            label.set_name(&child.name());
+            label.set_theme_type_variation(SMALL_LABEL);
+            label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+            label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+            content.add_child(&label);
+            label.set_owner(&owner);
+        }
+
+        if !content.has_node(&DailyCalendarChild::BackButton.name()) {
+            let mut button = Button::new_alloc();
+            button.set_name(&DailyCalendarChild::BackButton.name());
+            button.set_text(&TextKey::MenuBack.to_string());
+            button.set_h_size_flags(SizeFlags::SHRINK_CENTER);
+            content.add_child(&button);
+            button.set_owner(&owner);
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_layer(CALENDAR_LAYER);
+
+        self.previous_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_previous_pressed);
+        self.next_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_next_pressed);
+        self.back_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::close);
+
+        self.setup_focus_wrap();
+
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        self.base_mut().hide();
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        if !self.base().is_visible() || !event.is_action_pressed("ui_cancel") {
+            return;
+        }
+
+        self.close();
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+    }
+}
+
+#[godot_api]
+impl DailyCalendar {
+    #[signal]
+    pub fn closed();
+
+    /// Shows the current month with the saved results and remembers the focused control,
+    /// which gets the focus back on `close`.
+    #[func]
+    pub fn open(&mut self) {
+        let today = Date::today();
+        self.results = DailyResults::load();
+        self.month = (today.year, today.month);
+        self.refresh();
+
+        self.return_focus = self
+            .base()
+            .get_viewport()
+            .and_then(|viewport| viewport.gui_get_focus_owner());
+
+        self.base_mut().show();
+        self.back_button.grab_focus();
+    }
+
+    #[func]
+    pub fn close(&mut self) {
+        if !self.base().is_visible() {
+            return;
+        }
+
+        self.base_mut().hide();
+
+        if let Some(mut control) = self.return_focus.take()
+            && control.is_instance_valid()
+            && control.is_visible_in_tree()
+        {
+            control.grab_focus();
+        }
+
+        self.signals().closed().emit();
+    }
+
+    #[func]
+    fn on_previous_pressed(&mut self) {
+        let (year, month) = self.month;
+        self.month = if month == 1 {
+            (year - 1, 12)
+        } else {
+            (year, month - 1)
+        };
+        self.refresh();
+    }
+
+    #[func]
+    fn on_next_pressed(&mut self) {
+        let (year, month) = self.month;
+        self.month = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let (year, month) = self.month;
+        let today = Date::today();
+
+        self.month_label.set_text(&format!("{year:04}-{month:02}"));
+
+        for mut child in self.grid.get_children().iter_shared() {
+            self.grid.remove_child(&child);
+            child.queue_free();
+        }
+
+        for name in tr(TextKey::CalendarWeekdays).split(',') {
+            self.add_cell(name, Color::WHITE);
+        }
+
+        for _ in 0..weekday(year, month, 1) {
+            self.add_cell("", Color::WHITE);
+        }
+
+        for day in 1..=days_in_month(year, month) {
+            let date = Date { year, month, day };
+            let result = self.results.get(date);
+
+            let text = match result {
+                Some(result) => format!("{day}\n{}", result.score),
+                None => format!("{day}\n"),
+            };
+            let tint = match result {
+                _ if date == today => TODAY_COLOR,
+                Some(_) => PLAYED_COLOR,
+                None => EMPTY_COLOR,
+            };
+
+            self.add_cell(&text, tint);
+        }
+
+        let challenge = DailyChallenge::for_date(today);
+        let mut today_text = format!(
+            "{} {today}: {} x{:.2}",
+            tr(TextKey::DailyToday),
+            tr(TextKey::DailySpeed),
+            challenge.speed_scale
+        );
+        if let Some(result) = self.results.get(today) {
+            today_text.push_str(&format!(" - {}", result.score));
+        }
+        self.today.set_text(&today_text);
+
+        let best_text = self
+            .results
+            .best()
+            .map(|(date, result)| format!("{} {date}: {}", tr(TextKey::DailyBest), result.score))
+            .unwrap_or_default();
+        self.best.set_text(&best_text);
+    }
+
+    /// Adds an already translated cell to the month grid.
+    fn add_cell(&mut self, text: &str, tint: Color) {
+        let mut label = Label::new_alloc();
+        label.set_theme_type_variation(SMALL_LABEL);
+        label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+        label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+        label.set_vertical_alignment(VerticalAlignment::CENTER);
+        label.set_h_size_flags(SizeFlags::EXPAND_FILL);
+        label.set_modulate(tint);
+        label.set_text(text);
+        self.grid.add_child(&label);
+    }
+
+    /// Wraps focus between the month buttons and the back button.
+    fn setup_focus_wrap(&mut self) {
+        let mut first = self.previous_button.clone().upcast::<Control>();
+        let mut last = self.back_button.clone().upcast::<Control>();
+
+        let to_last = first.get_path_to(&last);
+        let to_first = last.get_path_to(&first);
+
+        first.set_focus_neighbor(Side::TOP, &to_last);
+        last.set_focus_neighbor(Side::BOTTOM, &to_first);
+    }
+}
@@ -2,13 +2,15 @@ use core::fmt;
 
 use godot::{
     classes::{
-        AnimatedSprite2D, CollisionShape2D, Engine, IRigidBody2D, RigidBody2D,
-        VisibleOnScreenNotifier2D,
+        AnimatedSprite2D, CollisionShape2D, Engine, IRigidBody2D, RigidBody2D, Shape2D,
+        SpriteFrames, VisibleOnScreenNotifier2D,
     },
     global::randi,
     prelude::*,
 };
 
+use crate::localization::{TextKey, tr};
+
 enum EnemyChild {
     AnimatedSprite2D,
     CollisionShape2D,
@@ -98,9 +100,7 @@ impl IRigidBody2D for EnemyBase {
                 .set_animation(type_enemies[randi() as usize % type_enemies.len()].arg());
             self.animated_sprite.play();
         } else if !Engine::singleton().is_editor_hint() {
-            godot_error!(
-                "Erro ao buscar os frames do AnimatedSprite2D! Não foi possível acessar a propriedade 'sprite_frames'"
-            )
+            godot_error!("{}", tr(TextKey::ErrorSpriteFrames))
         }
 
         self.visible_notifier
@@ -112,15 +112,59 @@ impl IRigidBody2D for EnemyBase {
     fn process(&mut self, _delta: f64) {
         if Engine::singleton().is_editor_hint() {
             self.base_mut().set_process(false);
-            return;
         }
     }
 }
 
 #[godot_api]
 impl EnemyBase {
+    /// Emitted when the enemy leaves the screen, right before it is freed.
+    #[signal]
+    pub fn dodged();
+
+    /// Name of the animation picked for this enemy, which doubles as its archetype.
+    #[func]
+    pub fn get_archetype(&self) -> StringName {
+        self.animated_sprite.get_animation()
+    }
+
+    /// Every archetype the enemy can take, one per animation of its sprite. Also works before
+    /// the enemy enters the tree, so spawners can pick one up front.
+    #[func]
+    pub fn get_archetypes(&self) -> PackedStringArray {
+        self.get_sprite_frames()
+            .map(|frames| frames.get_animation_names())
+            .unwrap_or_default()
+    }
+
+    /// Frames of the enemy's sprite, one animation per archetype. Also works before the enemy
+    /// enters the tree.
+    #[func]
+    pub fn get_sprite_frames(&self) -> Option<Gd<SpriteFrames>> {
+        self.base()
+            .try_get_node_as::<AnimatedSprite2D>(&EnemyChild::AnimatedSprite2D.to_string())
+            .and_then(|sprite| sprite.get_sprite_frames())
+    }
+
+    /// Shape the enemy collides with. Also works before the enemy enters the tree.
+    #[func]
+    pub fn get_shape(&self) -> Option<Gd<Shape2D>> {
+        self.base()
+            .try_get_node_as::<CollisionShape2D>(&EnemyChild::CollisionShape2D.to_string())
+            .and_then(|collision_shape| collision_shape.get_shape())
+    }
+
+    /// Replaces the archetype picked at random on ready, e.g. to follow a seeded run or a
+    /// network host.
+    #[func]
+    pub fn set_archetype(&mut self, archetype: StringName) {
+        self.animated_sprite.set_animation(&archetype);
+        self.animated_sprite.play();
+    }
+
     #[func]
     fn on_screen_exited(&mut self) {
+        self.signals().dodged().emit();
         self.base_mut().queue_free();
     }
 }
@@ -0,0 +1,219 @@
+use core::fmt;
+
+use godot::{
+    classes::{
+        Camera2D, CpuParticles2D, Engine, INode, cpu_particles_2d::Parameter, node::ProcessMode,
+    },
+    global::randf_range,
+    prelude::*,
+};
+
+enum FeedbackChild {
+    Camera,
+    DeathParticles,
+}
+
+impl fmt::Display for FeedbackChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeedbackChild::Camera => write!(f, "Camera"),
+            FeedbackChild::DeathParticles => write!(f, "DeathParticles"),
+        }
+    }
+}
+
+/// Game-feel effects played by `MainScene`: a particle burst, a hit-stop and a trauma-based
+/// camera shake. Every effect scales with its own intensity and `reduced_motion` turns off
+/// the ones that move the whole screen.
+#[derive(GodotClass)]
+#[class(base=Node, tool)]
+pub struct FeedbackBase {
+    #[base]
+    base: Base<Node>,
+    /// Scales the number and speed of the death particles. 0 disables them.
+    #[export]
+    particle_intensity: f32,
+    /// Length of the hit-stop in real seconds. 0 disables it.
+    #[export]
+    hit_stop_intensity: f64,
+    /// Largest camera offset in pixels at full trauma. 0 disables the shake.
+    #[export]
+    shake_intensity: f32,
+    /// Trauma lost per second.
+    #[export]
+    trauma_decay: f32,
+    /// Accessibility: skips hit-stop and screen shake and halves the particle burst.
+    #[export]
+    #[var(get, set = set_reduced_motion)]
+    reduced_motion: bool,
+    trauma: f32,
+    camera: OnReady<Gd<Camera2D>>,
+    death_particles: OnReady<Gd<CpuParticles2D>>,
+}
+
+#[godot_api]
+impl INode for FeedbackBase {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            particle_intensity: 1f32,
+            hit_stop_intensity: 0.12f64,
+            shake_intensity: 18f32,
+            trauma_decay: 1.5f32,
+            reduced_motion: false,
+            trauma: 0f32,
+            camera: OnReady::from_node(&FeedbackChild::Camera.to_string()),
+            death_particles: OnReady::from_node(&FeedbackChild::DeathParticles.to_string()),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        self.base()
+            .try_get_node_as::<Camera2D>(&FeedbackChild::Camera.to_string())
+            .unwrap_or_else(|| {
+                let mut camera = Camera2D::new_alloc();
+                camera.set_name(&FeedbackChild::Camera.to_string());
+                self.base_mut().add_child(&camera);
+                camera.set_owner(self.base().to_godot());
+                camera
+            });
+
+        self.base()
+            .try_get_node_as::<CpuParticles2D>(&FeedbackChild::DeathParticles.to_string())
+            .unwrap_or_else(|| {
+                let mut particles = CpuParticles2D::new_alloc();
+                particles.set_name(&FeedbackChild::DeathParticles.to_string());
+                particles.set_emitting(false);
+                particles.set_one_shot(true);
+                particles.set_explosiveness_ratio(1f32);
+                particles.set_lifetime(0.6f64);
+                particles.set_spread(180f32);
+                particles.set_gravity(Vector2::ZERO);
+                particles.set_param_min(Parameter::SCALE, 4f32);
+                particles.set_param_max(Parameter::SCALE, 8f32);
+                particles.set_color(Color::from_rgb(1f32, 0.55f32, 0.2f32));
+                self.base_mut().add_child(&particles);
+                particles.set_owner(self.base().to_godot());
+                particles
+            });
+    }
+
+    fn ready(&mut self) {
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        // The camera keeps settling while the game is paused so a shake never freezes mid-air.
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+
+        let center = self
+            .base()
+            .get_viewport()
+            .map_or(Vector2::ZERO, |viewport| {
+                viewport.get_visible_rect().size / 2f32
+            });
+        self.camera.set_position(center);
+        self.camera.make_current();
+    }
+
+    fn process(&mut self, delta: f64) {
+        if Engine::singleton().is_editor_hint() {
+            self.base_mut().set_process(false);
+            return;
+        }
+
+        self.trauma = (self.trauma - self.trauma_decay * delta as f32).max(0f32);
+
+        let shake = self.trauma * self.trauma * self.shake_intensity;
+        let offset = Vector2::new(
+            randf_range(-1f64, 1f64) as f32,
+            randf_range(-1f64, 1f64) as f32,
+        ) * shake;
+
+        self.camera.set_offset(offset);
+    }
+}
+
+#[godot_api]
+impl FeedbackBase {
+    /// Plays every death effect at `position`.
+    #[func]
+    pub fn play_death(&mut self, position: Vector2) {
+        self.burst(position);
+        self.hit_stop();
+        self.add_trauma(1f32);
+    }
+
+    /// One-shot particle burst at `position`.
+    #[func]
+    pub fn burst(&mut self, position: Vector2) {
+        let mut intensity = self.particle_intensity.max(0f32);
+
+        if self.reduced_motion {
+            intensity /= 2f32;
+        }
+
+        let amount = (32f32 * intensity).round() as i32;
+        if amount <= 0 {
+            return;
+        }
+
+        self.death_particles.set_position(position);
+        self.death_particles.set_amount(amount);
+        self.death_particles
+            .set_param_min(Parameter::INITIAL_LINEAR_VELOCITY, 120f32 * intensity);
+        self.death_particles
+            .set_param_max(Parameter::INITIAL_LINEAR_VELOCITY, 320f32 * intensity);
+        self.death_particles.restart();
+    }
+
+    /// Slows the game almost to a stop for `hit_stop_intensity` real seconds.
+    #[func]
+    pub fn hit_stop(&mut self) {
+        if self.reduced_motion || self.hit_stop_intensity <= 0f64 {
+            return;
+        }
+
+        let Some(mut tree) = self.base().get_tree() else {
+            return;
+        };
+
+        let Some(timer) = tree
+            .create_timer_ex(self.hit_stop_intensity)
+            .process_always(true)
+            .ignore_time_scale(true)
+            .done()
+        else {
+            return;
+        };
+
+        Engine::singleton().set_time_scale(0.05f64);
+        timer
+            .signals()
+            .timeout()
+            .connect(|| Engine::singleton().set_time_scale(1f64));
+    }
+
+    /// Adds screen-shake trauma, clamped to 1. The shake grows with the square of the trauma.
+    #[func]
+    pub fn add_trauma(&mut self, amount: f32) {
+        if self.reduced_motion {
+            return;
+        }
+
+        self.trauma = (self.trauma + amount).clamp(0f32, 1f32);
+    }
+
+    #[func]
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+
+        if reduced_motion {
+            self.trauma = 0f32;
+
+            if self.base().is_node_ready() {
+                self.camera.set_offset(Vector2::ZERO);
+            }
+        }
+    }
+}
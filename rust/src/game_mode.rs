@@ -0,0 +1,121 @@
+use godot::prelude::*;
+
+/// Seconds a time attack run lasts.
+pub const TIME_ATTACK_LENGTH: f64 = 60f64;
+
+/// Score a sprint run has to reach.
+pub const SPRINT_TARGET: i64 = 250;
+
+/// Rule set a run is started with, passed to `MainScene::start_game`. `MainScene` asks it
+/// how spawns ramp up, what scores and when a run is over.
+#[derive(GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[godot(via = i64)]
+pub enum GameMode {
+    #[default]
+    Endless,
+    /// Two players on one screen; the run lasts while either is alive.
+    Coop,
+    /// Two to four players, last one standing wins the round; best of N rounds.
+    Versus,
+    /// Co-op over the local network; the host's lobby decides who plays.
+    Network,
+    /// Endless on the seed and modifiers of the day; one scored attempt per day.
+    Daily,
+    /// Survive `TIME_ATTACK_LENGTH` seconds; only grazes score.
+    TimeAttack,
+    /// Hits don't end the run, for practice. It lasts until it is left from the pause menu.
+    Zen,
+    /// Reach `SPRINT_TARGET` as fast as possible.
+    Sprint,
+}
+
+impl GameMode {
+    /// Every mode, in the order the menu lists them.
+    pub const ALL: [GameMode; 8] = [
+        GameMode::Endless,
+        GameMode::TimeAttack,
+        GameMode::Sprint,
+        GameMode::Zen,
+        GameMode::Daily,
+        GameMode::Coop,
+        GameMode::Versus,
+        GameMode::Network,
+    ];
+
+    /// How many players take part in a run of the mode. Versus starts from this and lets
+    /// `MainScene::versus_players` raise it up to four, and network runs seat one player per
+    /// connected peer.
+    pub fn player_count(self) -> usize {
+        match self {
+            GameMode::Coop | GameMode::Versus => 2,
+            _ => 1,
+        }
+    }
+
+    /// Name the mode is saved under, e.g. in the sections of the personal bests file.
+    pub fn key(self) -> &'static str {
+        match self {
+            GameMode::Endless => "endless",
+            GameMode::Coop => "coop",
+            GameMode::Versus => "versus",
+            GameMode::Network => "network",
+            GameMode::Daily => "daily",
+            GameMode::TimeAttack => "time_attack",
+            GameMode::Zen => "zen",
+            GameMode::Sprint => "sprint",
+        }
+    }
+
+    /// Whether the mode keeps a personal bests table. Versus, network runs and daily
+    /// challenges have their own results instead.
+    pub fn has_high_scores(self) -> bool {
+        !matches!(self, GameMode::Versus | GameMode::Network | GameMode::Daily)
+    }
+
+    /// Whether bosses come at score milestones. Versus rounds and network runs have none,
+    /// and zen runs stay calm.
+    pub fn has_bosses(self) -> bool {
+        !matches!(self, GameMode::Versus | GameMode::Network | GameMode::Zen)
+    }
+
+    /// Seconds after which a run is over, if the mode has a time limit.
+    pub fn time_limit(self) -> Option<f64> {
+        (self == GameMode::TimeAttack).then_some(TIME_ATTACK_LENGTH)
+    }
+
+    /// Score that ends a run once reached, if the mode has one.
+    pub fn target_score(self) -> Option<i64> {
+        (self == GameMode::Sprint).then_some(SPRINT_TARGET)
+    }
+
+    /// Whether being hit takes the player out of the run.
+    pub fn is_lethal(self) -> bool {
+        self != GameMode::Zen
+    }
+
+    /// Whether seconds survived count towards the score, on top of the bonus points.
+    pub fn scores_time(self) -> bool {
+        self != GameMode::TimeAttack
+    }
+
+    /// Whether the best time of the mode is the shortest one rather than the longest.
+    pub fn fastest_time_wins(self) -> bool {
+        self.target_score().is_some()
+    }
+
+    /// Seconds for the spawn rate to reach its peak, given the scene's `ramp_time`. A time
+    /// attack peaks as it ends and zen runs climb at half the pace.
+    pub fn spawn_ramp_time(self, ramp_time: f64) -> f64 {
+        match self {
+            GameMode::TimeAttack => TIME_ATTACK_LENGTH,
+            GameMode::Zen => ramp_time * 2f64,
+            _ => ramp_time,
+        }
+    }
+
+    /// Whether a run at `time` seconds with `score` points has reached the mode's goal.
+    pub fn is_cleared(self, time: f64, score: i64) -> bool {
+        self.time_limit().is_some_and(|limit| time >= limit)
+            || self.target_score().is_some_and(|target| score >= target)
+    }
+}
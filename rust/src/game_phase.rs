@@ -0,0 +1,37 @@
+use godot::prelude::*;
+
+/// Phase of a run, owned by `MainScene` and broadcast through its `phase_changed` signal.
+#[derive(GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[godot(via = i64)]
+pub enum GamePhase {
+    #[default]
+    Title,
+    Countdown,
+    Playing,
+    Paused,
+    /// Between two rounds of a versus match, showing the standings.
+    RoundOver,
+    GameOver,
+}
+
+impl GamePhase {
+    /// Whether the run may move from `self` to `next`. Anything not listed here is ignored by
+    /// `MainScene`, e.g. pressing start during a countdown or a second hit after game over.
+    pub fn can_transition_to(self, next: GamePhase) -> bool {
+        matches!(
+            (self, next),
+            (GamePhase::Title | GamePhase::GameOver, GamePhase::Countdown)
+                | (GamePhase::Countdown | GamePhase::Paused, GamePhase::Playing)
+                | (GamePhase::Playing, GamePhase::Paused)
+                | (GamePhase::RoundOver, GamePhase::Countdown)
+                | (
+                    GamePhase::Countdown | GamePhase::Playing,
+                    GamePhase::RoundOver | GamePhase::GameOver
+                )
+                | (
+                    GamePhase::GameOver | GamePhase::Paused | GamePhase::RoundOver,
+                    GamePhase::Title
+                )
+        )
+    }
+}
@@ -0,0 +1,285 @@
+use godot::{
+    classes::{AnimatedSprite2D, ConfigFile, IAnimatedSprite2D},
+    global::Error,
+    prelude::*,
+};
+
+use crate::localization::{TextKey, tr};
+
+const GHOST_PATH: &str = "user://ghost.cfg";
+const GHOST_SECTION: &str = "ghost";
+
+/// Seconds between two recorded positions; playback blends between them.
+const SAMPLE_INTERVAL: f64 = 0.1f64;
+
+/// Position and score track of a run, recorded every `SAMPLE_INTERVAL` seconds of play
+/// together with the seed its spawns were drawn from. The best one is persisted in
+/// `user://ghost.cfg`.
+#[derive(Clone, Default)]
+pub struct GhostRun {
+    pub seed: i64,
+    /// Seconds the run lasted.
+    pub time: f64,
+    /// Score the run ended with.
+    pub score: i64,
+    positions: Vec<Vector2>,
+    scores: Vec<i64>,
+}
+
+impl GhostRun {
+    pub fn new(seed: i64) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    /// The saved best run, if there is one.
+    pub fn load() -> Option<Self> {
+        let mut config = ConfigFile::new_gd();
+
+        if config.load(GHOST_PATH) != Error::OK {
+            return None;
+        }
+
+        let read = |key: &str| config.get_value_ex(GHOST_SECTION, key).done();
+
+        let positions = read("positions")
+            .try_to::<PackedVector2Array>()
+            .ok()?
+            .as_slice()
+            .to_vec();
+
+        let scores = read("scores")
+            .try_to::<PackedInt64Array>()
+            .ok()?
+            .as_slice()
+            .to_vec();
+
+        Some(Self {
+            seed: read("seed").try_to().ok()?,
+            time: read("time").try_to().ok()?,
+            score: read("score").try_to().ok()?,
+            positions,
+            scores,
+        })
+        .filter(|run| !run.positions.is_empty() && run.scores.len() == run.positions.len())
+    }
+
+    pub fn save(&self) {
+        let positions = self
+            .positions
+            .iter()
+            .copied()
+            .collect::<PackedVector2Array>();
+        let scores = self.scores.iter().copied().collect::<PackedInt64Array>();
+
+        let mut config = ConfigFile::new_gd();
+        config.set_value(GHOST_SECTION, "seed", &self.seed.to_variant());
+        config.set_value(GHOST_SECTION, "time", &self.time.to_variant());
+        config.set_value(GHOST_SECTION, "score", &self.score.to_variant());
+        config.set_value(GHOST_SECTION, "positions", &positions.to_variant());
+        config.set_value(GHOST_SECTION, "scores", &scores.to_variant());
+
+        if config.save(GHOST_PATH) != Error::OK {
+            godot_warn!("{}", tr(TextKey::ErrorSaveGhost));
+        }
+    }
+
+    /// Records `position` and `score` at `time` seconds into the run, once per
+    /// `SAMPLE_INTERVAL`. The latest score is kept as the one the run ended with.
+    pub fn record(&mut self, time: f64, position: Vector2, score: i64) {
+        if time >= self.positions.len() as f64 * SAMPLE_INTERVAL {
+            self.positions.push(position);
+            self.scores.push(score);
+        }
+
+        self.time = self.time.max(time);
+        self.score = score;
+    }
+
+    /// Where the run was `time` seconds in, or `None` once it had ended.
+    pub fn position_at(&self, time: f64) -> Option<Vector2> {
+        if time > self.time {
+            return None;
+        }
+
+        let step = time.max(0f64) / SAMPLE_INTERVAL;
+        let index = step.floor() as usize;
+        let from = *self.positions.get(index).or(self.positions.last())?;
+        let to = self.positions.get(index + 1).copied().unwrap_or(from);
+
+        Some(from.lerp(to, step.fract() as f32))
+    }
+
+    /// The score the run had `time` seconds in; its final score once it had ended.
+    pub fn score_at(&self, time: f64) -> i64 {
+        if time >= self.time {
+            return self.score;
+        }
+
+        let index = (time.max(0f64) / SAMPLE_INTERVAL).floor() as usize;
+        self.scores
+            .get(index)
+            .or(self.scores.last())
+            .copied()
+            .unwrap_or(self.score)
+    }
+}
+
+/// Translucent replay of a `GhostRun`, following it as the live run's clock advances.
+#[derive(GodotClass)]
+#[class(base=AnimatedSprite2D, tool)]
+pub struct Ghost {
+    #[base]
+    base: Base<AnimatedSprite2D>,
+    /// Alpha the ghost is drawn with.
+    #[export]
+    opacity: f32,
+    run: Option<GhostRun>,
+}
+
+#[godot_api]
+impl IAnimatedSprite2D for Ghost {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            opacity: 0.35f32,
+            run: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let opacity = self.opacity;
+        self.base_mut()
+            .set_modulate(Color::from_rgba(1f32, 1f32, 1f32, opacity));
+        self.base_mut().hide();
+    }
+}
+
+#[godot_api]
+impl Ghost {
+    /// Takes the frames and scale of `sprite`, so the ghost looks like the player it replays.
+    pub fn mimic(&mut self, sprite: &Gd<AnimatedSprite2D>) {
+        let frames = sprite.get_sprite_frames();
+        let scale = sprite.get_scale();
+
+        let mut base = self.base_mut();
+        base.set_scale(scale);
+
+        if let Some(frames) = frames {
+            base.set_sprite_frames(&frames);
+        }
+    }
+
+    /// Replays `run` from its start; `None` hides the ghost.
+    pub fn set_run(&mut self, run: Option<GhostRun>) {
+        self.run = run;
+        self.follow(0f64);
+    }
+
+    /// Moves the ghost to where its run was `time` seconds in, animating it like the player.
+    /// It disappears where the run ended.
+    pub fn follow(&mut self, time: f64) {
+        let position = self.run.as_ref().and_then(|run| run.position_at(time));
+
+        let Some(position) = position else {
+            self.base_mut().hide();
+            return;
+        };
+
+        let velocity = position - self.base().get_position();
+        let mut base = self.base_mut();
+
+        if velocity.x != 0f32 {
+            base.set_animation("right");
+            base.set_flip_h(velocity.x < 0f32);
+        } else if velocity.y != 0f32 {
+            base.set_animation("up");
+        }
+
+        if velocity.y != 0f32 {
+            base.set_flip_v(velocity.y > 0f32);
+        }
+
+        if velocity.length() > 0f32 {
+            base.play();
+        } else {
+            base.stop();
+        }
+
+        base.set_position(position);
+        base.show();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A run moving one pixel right and scoring ten points per second, recorded every frame
+    /// at 60 frames per second for `seconds`.
+    fn recorded(seconds: f64) -> GhostRun {
+        let mut run = GhostRun::new(7);
+        let frames = (seconds * 60f64).round() as usize;
+
+        for frame in 0..=frames {
+            let time = frame as f64 / 60f64;
+            run.record(
+                time,
+                Vector2::new(time as f32, 0f32),
+                (time * 10f64).floor() as i64,
+            );
+        }
+
+        run
+    }
+
+    #[test]
+    fn record_keeps_one_sample_per_interval() {
+        let run = recorded(1f64);
+
+        assert_eq!(run.positions.len(), 11);
+        assert_eq!(run.scores.len(), run.positions.len());
+        assert_eq!(run.time, 1f64);
+        assert_eq!(run.score, 10);
+        assert_eq!(run.seed, 7);
+    }
+
+    #[test]
+    fn position_at_blends_between_samples() {
+        let run = recorded(1f64);
+        let position = run.position_at(0.25f64).expect("inside the run");
+
+        assert!((position.x - 0.25f32).abs() < 0.02f32, "{position:?}");
+        assert_eq!(position.y, 0f32);
+    }
+
+    #[test]
+    fn position_at_starts_at_the_first_sample() {
+        let run = recorded(1f64);
+
+        assert_eq!(run.position_at(0f64), Some(Vector2::ZERO));
+        assert_eq!(run.position_at(-1f64), Some(Vector2::ZERO));
+    }
+
+    #[test]
+    fn position_at_ends_with_the_run() {
+        let run = recorded(1f64);
+
+        assert!(run.position_at(1f64).is_some());
+        assert_eq!(run.position_at(1.01f64), None);
+        assert_eq!(GhostRun::new(0).position_at(0f64), None);
+    }
+
+    #[test]
+    fn score_at_follows_the_run_then_keeps_its_final_score() {
+        let run = recorded(2f64);
+
+        assert_eq!(run.score_at(0f64), 0);
+        assert_eq!(run.score_at(0.55f64), 5);
+        assert_eq!(run.score_at(1.5f64), 15);
+        assert_eq!(run.score_at(2f64), 20);
+        assert_eq!(run.score_at(30f64), 20);
+    }
+}
@@ -1,42 +1,183 @@
 use core::fmt;
 
 use godot::{
+    builtin::{Color, GString, Vector2},
     classes::{
-        Button, CanvasLayer, ICanvasLayer, InputEventAction, Label, Shortcut, Timer,
-        class_macros::private::virtuals::Os::array,
+        AudioStreamPlayer, AudioStreamWav, Button, CanvasLayer, Engine, GridContainer,
+        ICanvasLayer, InputEventAction, Label, PanelContainer, Shortcut, Theme, Timer,
+        VBoxContainer, class_macros::private::virtuals::Os::array, control::LayoutPreset,
+        node::AutoTranslateMode, notify::NodeNotification, text_server::AutowrapMode,
     },
+    global::{HorizontalAlignment, VerticalAlignment},
     meta::ToGodot,
-    obj::{Base, Gd, NewAlloc, NewGd, OnReady, WithBaseField, WithUserSignals},
+    obj::{Base, Gd, NewAlloc, NewGd, OnReady, Singleton, WithBaseField, WithUserSignals},
     prelude::{GodotClass, godot_api},
 };
 
+use crate::{
+    audio::tone_stream,
+    daily::Date,
+    game_mode::GameMode,
+    game_phase::GamePhase,
+    hud_theme::{LARGE_LABEL, POPUP_LABEL, SMALL_LABEL, hud_theme, place},
+    input_bindings::{self, GameAction},
+    localization::{TextKey, tr},
+    message_queue::{MessagePriority, MessageQueue, QueuedMessage},
+    player::PlayerStatus,
+    run_stats::{PersonalBests, RunStats},
+    score_popup::ScorePopup,
+    settings::SFX_BUS,
+};
+
+const GAME_OVER_MESSAGE_DURATION: f64 = 2f64;
+const BOSS_BONUS_MESSAGE_DURATION: f64 = 2f64;
+const COUNTDOWN_STEP_DURATION: f64 = 1f64;
+const MIN_RESUMED_DURATION: f64 = 0.5f64;
+/// Tint of the ghost delta while the run scores ahead of the best one.
+const AHEAD_COLOR: Color = Color::from_rgb(0.5f32, 1f32, 0.5f32);
+
 enum HudChild {
     ScoreLabel,
+    TimeLabel,
+    MultiplierLabel,
+    GhostDelta,
+    ModeInfo,
     MessageLabel,
     MessageTimer,
     StartButton,
+    MainMenuButton,
+    SettingsButton,
+    SummaryPanel,
+    SummaryGrid,
+    PlayerStatus,
+    CountdownTick,
 }
 
 impl fmt::Display for HudChild {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             HudChild::ScoreLabel => write!(f, "ScoreLabel"),
+            HudChild::TimeLabel => write!(f, "TimeLabel"),
+            HudChild::MultiplierLabel => write!(f, "MultiplierLabel"),
+            HudChild::GhostDelta => write!(f, "GhostDelta"),
+            HudChild::ModeInfo => write!(f, "ModeInfo"),
             HudChild::MessageLabel => write!(f, "MessageLabel"),
             HudChild::MessageTimer => write!(f, "MessageTimer"),
             HudChild::StartButton => write!(f, "StartButton"),
+            HudChild::MainMenuButton => write!(f, "MainMenuButton"),
+            HudChild::SettingsButton => write!(f, "SettingsButton"),
+            HudChild::SummaryPanel => write!(f, "SummaryPanel"),
+            HudChild::SummaryGrid => write!(f, "SummaryPanel/SummaryGrid"),
+            HudChild::PlayerStatus => write!(f, "PlayerStatus"),
+            HudChild::CountdownTick => write!(f, "CountdownTick"),
+        }
+    }
+}
+
+impl HudChild {
+    /// Name of the node itself, without the path to it.
+    fn name(&self) -> String {
+        let path = self.to_string();
+        path.rsplit('/').next().unwrap_or_default().to_string()
+    }
+}
+
+/// Details about the rules of the run shown under the score. Kept as values rather than
+/// text, so they can be shown again after a language change.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ModeInfo {
+    #[default]
+    None,
+    TimeAttack,
+    Sprint {
+        target: i64,
+    },
+    Zen {
+        hits: i64,
+    },
+    /// A daily challenge; `practice` once the day's scored attempt has been used.
+    Daily {
+        date: Date,
+        speed_scale: f32,
+        practice: bool,
+    },
+}
+
+impl ModeInfo {
+    fn text(self) -> String {
+        match self {
+            ModeInfo::None => String::new(),
+            ModeInfo::TimeAttack => tr(TextKey::TimeAttackInfo),
+            ModeInfo::Sprint { target } => format!("{} {target}", tr(TextKey::SprintInfo)),
+            ModeInfo::Zen { hits } => format!("{} {hits}", tr(TextKey::ZenInfo)),
+            ModeInfo::Daily {
+                date,
+                speed_scale,
+                practice,
+            } => {
+                let mut text = format!(
+                    "{} {date}: {} x{speed_scale:.2}",
+                    tr(TextKey::DailyTitle),
+                    tr(TextKey::DailySpeed)
+                );
+                if practice {
+                    text.push_str(&format!(" ({})", tr(TextKey::DailyPractice)));
+                }
+                text
+            }
         }
     }
 }
 
+/// Versus standings last shown in the summary panel.
+struct Standings {
+    round: i64,
+    wins: Vec<i64>,
+    tints: Vec<Color>,
+    winner: Option<usize>,
+    match_over: bool,
+}
+
 #[derive(GodotClass)]
 #[class(base=CanvasLayer, tool)]
 pub struct HUDBase {
     #[base]
     base: Base<CanvasLayer>,
     score_label: OnReady<Gd<Label>>,
+    time_label: OnReady<Gd<Label>>,
+    multiplier_label: OnReady<Gd<Label>>,
+    ghost_delta: OnReady<Gd<Label>>,
+    mode_info: OnReady<Gd<Label>>,
     message_label: OnReady<Gd<Label>>,
     message_timer: OnReady<Gd<Timer>>,
     start_button: OnReady<Gd<Button>>,
+    main_menu_button: OnReady<Gd<Button>>,
+    settings_button: OnReady<Gd<Button>>,
+    summary_panel: OnReady<Gd<PanelContainer>>,
+    summary_grid: OnReady<Gd<GridContainer>>,
+    player_status: OnReady<Gd<VBoxContainer>>,
+    countdown_tick: OnReady<Gd<AudioStreamPlayer>>,
+    countdown_timer: Option<Gd<Timer>>,
+    message_queue: MessageQueue,
+    /// Shown when the game-over flow starts; a finished versus match names its winner.
+    game_over_text: String,
+    /// Shown during `GamePhase::RoundOver`.
+    round_result: String,
+    /// Whether the game-over flow offers the start button; network clients wait for the host.
+    restart_enabled: bool,
+    current_message: Option<QueuedMessage>,
+    /// Rules of the run, which decide how the score and time labels read.
+    mode: GameMode,
+    // Values behind the texts built with `tr`, which are rebuilt when the language changes.
+    ghost_points: Option<i64>,
+    mode_info_value: ModeInfo,
+    player_statuses: Vec<PlayerStatus>,
+    standings: Option<Standings>,
+    /// Theme shared by the score popups, created with the first one.
+    popup_theme: Option<Gd<Theme>>,
+    phase: GamePhase,
+    counting_down: bool,
+    countdown_step: i64,
 }
 
 #[godot_api]
@@ -45,18 +186,138 @@ impl ICanvasLayer for HUDBase {
         Self {
             base,
             score_label: OnReady::from_node(&HudChild::ScoreLabel.to_string()),
+            time_label: OnReady::from_node(&HudChild::TimeLabel.to_string()),
+            multiplier_label: OnReady::from_node(&HudChild::MultiplierLabel.to_string()),
+            ghost_delta: OnReady::from_node(&HudChild::GhostDelta.to_string()),
+            mode_info: OnReady::from_node(&HudChild::ModeInfo.to_string()),
             message_label: OnReady::from_node(&HudChild::MessageLabel.to_string()),
             message_timer: OnReady::from_node(&HudChild::MessageTimer.to_string()),
             start_button: OnReady::from_node(&HudChild::StartButton.to_string()),
+            main_menu_button: OnReady::from_node(&HudChild::MainMenuButton.to_string()),
+            settings_button: OnReady::from_node(&HudChild::SettingsButton.to_string()),
+            summary_panel: OnReady::from_node(&HudChild::SummaryPanel.to_string()),
+            summary_grid: OnReady::from_node(&HudChild::SummaryGrid.to_string()),
+            player_status: OnReady::from_node(&HudChild::PlayerStatus.to_string()),
+            countdown_tick: OnReady::from_node(&HudChild::CountdownTick.to_string()),
+            countdown_timer: None,
+            message_queue: MessageQueue::default(),
+            game_over_text: TextKey::GameOver.to_string(),
+            round_result: String::new(),
+            restart_enabled: true,
+            current_message: None,
+            mode: GameMode::Endless,
+            ghost_points: None,
+            mode_info_value: ModeInfo::None,
+            player_statuses: Vec::new(),
+            standings: None,
+            popup_theme: None,
+            phase: GamePhase::Title,
+            counting_down: false,
+            countdown_step: 0,
         }
     }
 
     fn enter_tree(&mut self) {
+        // Controls under a CanvasLayer don't inherit a theme, so every control created here
+        // gets the generated one. It is only built when something is actually missing.
+        let mut theme = None;
+
         self.base()
             .try_get_node_as::<Label>(&HudChild::ScoreLabel.to_string())
             .unwrap_or_else(|| {
                 let mut label = Label::new_alloc();
                 label.set_name(&HudChild::ScoreLabel.to_string());
+                label.set_theme(&*theme.get_or_insert_with(hud_theme));
+                label.set_theme_type_variation(LARGE_LABEL);
+                place(
+                    &mut label,
+                    LayoutPreset::TOP_WIDE,
+                    [0f32, 30f32, 0f32, 108f32],
+                );
+                label.set_text("0");
+                label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+                label.set_vertical_alignment(VerticalAlignment::CENTER);
+                self.base_mut().add_child(&label);
+                label.set_owner(self.base().to_godot());
+                label
+            });
+
+        self.base()
+            .try_get_node_as::<Label>(&HudChild::TimeLabel.to_string())
+            .unwrap_or_else(|| {
+                let mut label = Label::new_alloc();
+                label.set_name(&HudChild::TimeLabel.to_string());
+                label.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut label,
+                    LayoutPreset::TOP_WIDE,
+                    [0f32, 108f32, 0f32, 148f32],
+                );
+                label.set_text(&format_run_time(0f64));
+                label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+                label.set_vertical_alignment(VerticalAlignment::CENTER);
+                label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+                self.base_mut().add_child(&label);
+                label.set_owner(self.base().to_godot());
+                label
+            });
+
+        self.base()
+            .try_get_node_as::<Label>(&HudChild::MultiplierLabel.to_string())
+            .unwrap_or_else(|| {
+                let mut label = Label::new_alloc();
+                label.set_name(&HudChild::MultiplierLabel.to_string());
+                label.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut label,
+                    LayoutPreset::TOP_WIDE,
+                    [0f32, 148f32, 0f32, 188f32],
+                );
+                label.set_text("x1.00");
+                label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+                label.set_vertical_alignment(VerticalAlignment::CENTER);
+                label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+                label.hide();
+                self.base_mut().add_child(&label);
+                label.set_owner(self.base().to_godot());
+                label
+            });
+
+        self.base()
+            .try_get_node_as::<Label>(&HudChild::GhostDelta.to_string())
+            .unwrap_or_else(|| {
+                let mut label = Label::new_alloc();
+                label.set_name(&HudChild::GhostDelta.to_string());
+                label.set_theme(&*theme.get_or_insert_with(hud_theme));
+                label.set_theme_type_variation(SMALL_LABEL);
+                place(
+                    &mut label,
+                    LayoutPreset::TOP_RIGHT,
+                    [-248f32, 8f32, -8f32, 40f32],
+                );
+                label.set_horizontal_alignment(HorizontalAlignment::RIGHT);
+                label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+                label.hide();
+                self.base_mut().add_child(&label);
+                label.set_owner(self.base().to_godot());
+                label
+            });
+
+        self.base()
+            .try_get_node_as::<Label>(&HudChild::ModeInfo.to_string())
+            .unwrap_or_else(|| {
+                let mut label = Label::new_alloc();
+                label.set_name(&HudChild::ModeInfo.to_string());
+                label.set_theme(&*theme.get_or_insert_with(hud_theme));
+                label.set_theme_type_variation(SMALL_LABEL);
+                place(
+                    &mut label,
+                    LayoutPreset::TOP_RIGHT,
+                    [-408f32, 40f32, -8f32, 72f32],
+                );
+                label.set_horizontal_alignment(HorizontalAlignment::RIGHT);
+                label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+                label.hide();
                 self.base_mut().add_child(&label);
                 label.set_owner(self.base().to_godot());
                 label
@@ -67,6 +328,17 @@ impl ICanvasLayer for HUDBase {
             .unwrap_or_else(|| {
                 let mut label = Label::new_alloc();
                 label.set_name(&HudChild::MessageLabel.to_string());
+                label.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut label,
+                    LayoutPreset::CENTER,
+                    [-240f32, -68.5f32, 240f32, 68.5f32],
+                );
+                label.set_text(&TextKey::Title.to_string());
+                label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+                label.set_vertical_alignment(VerticalAlignment::CENTER);
+                label.set_autowrap_mode(AutowrapMode::WORD_SMART);
+                label.hide();
                 self.base_mut().add_child(&label);
                 label.set_owner(self.base().to_godot());
                 label
@@ -77,6 +349,8 @@ impl ICanvasLayer for HUDBase {
             .unwrap_or_else(|| {
                 let mut timer = Timer::new_alloc();
                 timer.set_name(&HudChild::MessageTimer.to_string());
+                timer.set_wait_time(GAME_OVER_MESSAGE_DURATION);
+                timer.set_one_shot(true);
                 self.base_mut().add_child(&timer);
                 timer.set_owner(self.base().to_godot());
                 timer
@@ -87,13 +361,116 @@ impl ICanvasLayer for HUDBase {
             .unwrap_or_else(|| {
                 let mut button = Button::new_alloc();
                 button.set_name(&HudChild::StartButton.to_string());
+                button.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut button,
+                    LayoutPreset::CENTER_BOTTOM,
+                    [-66f32, -120f32, 66f32, -69f32],
+                );
+                button.set_text(&TextKey::Start.to_string());
+                self.base_mut().add_child(&button);
+                button.set_owner(self.base().to_godot());
+                button
+            });
+
+        self.base()
+            .try_get_node_as::<Button>(&HudChild::MainMenuButton.to_string())
+            .unwrap_or_else(|| {
+                let mut button = Button::new_alloc();
+                button.set_name(&HudChild::MainMenuButton.to_string());
+                button.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut button,
+                    LayoutPreset::CENTER_BOTTOM,
+                    [-66f32, -60f32, 66f32, -16f32],
+                );
+                button.set_text(&TextKey::MainMenu.to_string());
+                button.hide();
+                self.base_mut().add_child(&button);
+                button.set_owner(self.base().to_godot());
+                button
+            });
+
+        self.base()
+            .try_get_node_as::<Button>(&HudChild::SettingsButton.to_string())
+            .unwrap_or_else(|| {
+                let mut button = Button::new_alloc();
+                button.set_name(&HudChild::SettingsButton.to_string());
+                button.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut button,
+                    LayoutPreset::CENTER_BOTTOM,
+                    [-110f32, -116f32, 110f32, -72f32],
+                );
+                button.set_text(&TextKey::MenuSettings.to_string());
+                button.hide();
                 self.base_mut().add_child(&button);
                 button.set_owner(self.base().to_godot());
                 button
             });
+
+        let mut summary_panel = self
+            .base()
+            .try_get_node_as::<PanelContainer>(&HudChild::SummaryPanel.to_string())
+            .unwrap_or_else(|| {
+                let mut panel = PanelContainer::new_alloc();
+                panel.set_name(&HudChild::SummaryPanel.to_string());
+                panel.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut panel,
+                    LayoutPreset::CENTER,
+                    [-200f32, -140f32, 200f32, 140f32],
+                );
+                panel.hide();
+                self.base_mut().add_child(&panel);
+                panel.set_owner(self.base().to_godot());
+                panel
+            });
+
+        if summary_panel
+            .try_get_node_as::<GridContainer>(&HudChild::SummaryGrid.name())
+            .is_none()
+        {
+            let mut grid = GridContainer::new_alloc();
+            grid.set_name(&HudChild::SummaryGrid.name());
+            grid.set_columns(3);
+            summary_panel.add_child(&grid);
+            grid.set_owner(self.base().to_godot());
+        }
+
+        self.base()
+            .try_get_node_as::<VBoxContainer>(&HudChild::PlayerStatus.to_string())
+            .unwrap_or_else(|| {
+                let mut status = VBoxContainer::new_alloc();
+                status.set_name(&HudChild::PlayerStatus.to_string());
+                status.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut status,
+                    LayoutPreset::TOP_LEFT,
+                    [8f32, 8f32, 200f32, 120f32],
+                );
+                status.hide();
+                self.base_mut().add_child(&status);
+                status.set_owner(self.base().to_godot());
+                status
+            });
+
+        self.base()
+            .try_get_node_as::<AudioStreamPlayer>(&HudChild::CountdownTick.to_string())
+            .unwrap_or_else(|| {
+                let mut audio = AudioStreamPlayer::new_alloc();
+                audio.set_name(&HudChild::CountdownTick.to_string());
+                self.base_mut().add_child(&audio);
+                audio.set_owner(self.base().to_godot());
+                audio
+            });
     }
 
     fn ready(&mut self) {
+        if !Engine::singleton().is_editor_hint() {
+            input_bindings::ensure_registered();
+        }
+
         self.start_button
             .signals()
             .pressed()
@@ -102,7 +479,7 @@ impl ICanvasLayer for HUDBase {
         let input_start = {
             let input_action = {
                 let mut input = InputEventAction::new_gd();
-                input.set_action("ui_select");
+                input.set_action(&GameAction::Start.name());
                 input
             };
 
@@ -113,10 +490,63 @@ impl ICanvasLayer for HUDBase {
 
         self.start_button.set_shortcut(input_start.to_godot());
 
+        self.main_menu_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_main_menu_button_pressed);
+
+        self.settings_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_settings_button_pressed);
+
         self.message_timer
             .signals()
             .timeout()
             .connect_other(self, Self::on_message_timer_timeout);
+
+        if !Engine::singleton().is_editor_hint() {
+            self.countdown_tick.set_bus(SFX_BUS);
+        }
+
+        if self.countdown_tick.get_stream().is_none() {
+            self.countdown_tick.set_stream(&tick_stream());
+        }
+    }
+
+    fn on_notification(&mut self, what: NodeNotification) {
+        if what == NodeNotification::TRANSLATION_CHANGED
+            && self.base().is_node_ready()
+            && !Engine::singleton().is_editor_hint()
+        {
+            self.retranslate();
+        }
+    }
+
+    fn process(&mut self, _delta: f64) {
+        if Engine::singleton().is_editor_hint() {
+            self.base_mut().set_process(false);
+            return;
+        }
+
+        if !self.counting_down {
+            return;
+        }
+
+        let Some(remaining) = self
+            .countdown_timer
+            .as_ref()
+            .filter(|timer| !timer.is_stopped())
+            .map(|timer| timer.get_time_left())
+        else {
+            return;
+        };
+
+        let step = remaining.ceil() as i64;
+        if step >= 1 && step != self.countdown_step {
+            self.countdown_step = step;
+            self.show_countdown_step(&step.to_string(), 1f32);
+        }
     }
 }
 
@@ -125,48 +555,593 @@ impl HUDBase {
     #[signal]
     pub fn start_game();
 
-    pub async fn game_over(mut hud: Gd<Self>) {
-        hud.bind_mut().show_message("Fim de Jogo!");
+    /// Emitted when the player asks to leave the run for the main menu.
+    #[signal]
+    pub fn main_menu_requested();
 
-        let timer = hud.bind().message_timer.signals().timeout().to_future();
-        timer.await;
+    /// Emitted when the settings button on the pause screen is pressed.
+    #[signal]
+    pub fn settings_requested();
+
+    /// Emitted when the last queued message leaves the screen.
+    #[signal]
+    pub fn messages_drained();
 
+    pub async fn game_over(mut hud: Gd<Self>) {
+        let drained = hud.signals().messages_drained().to_future();
+
+        let text = hud.bind().game_over_text.clone();
+        hud.bind_mut().flush_messages();
         hud.bind_mut()
-            .message_label
-            .set_text("Desvie e sobreviva aos monstros!");
-        hud.bind_mut().message_label.show();
+            .push_message(&text, GAME_OVER_MESSAGE_DURATION, MessagePriority::High);
+        drained.await;
+
+        // The run may have been left or restarted while the game-over flow was waiting.
+        if hud.bind().phase != GamePhase::GameOver {
+            return;
+        }
+
+        hud.bind_mut().summary_panel.show();
         hud.bind()
             .base()
             .get_tree()
-            .expect("Erro ao buscar a cena!")
+            .unwrap_or_else(|| panic!("{}", tr(TextKey::ErrorSceneTree)))
             .create_timer(1f64)
-            .expect("Erro ao criar o timer de reinício!")
+            .unwrap_or_else(|| panic!("{}", tr(TextKey::ErrorRestartTimer)))
             .signals()
             .timeout()
             .to_future()
             .await;
 
-        hud.bind_mut().start_button.show();
+        if hud.bind().phase != GamePhase::GameOver {
+            return;
+        }
+
+        let mut hud = hud.bind_mut();
+        hud.main_menu_button.show();
+
+        if hud.restart_enabled {
+            hud.start_button.show();
+            hud.start_button.grab_focus();
+        } else {
+            hud.main_menu_button.grab_focus();
+        }
+    }
+
+    /// Queues `text` to be shown for `duration` seconds (until dismissed when zero or less).
+    /// `MessagePriority::High` preempts the message on screen; normal messages wait their
+    /// turn. Translation keys are resolved by the label itself, so messages follow live
+    /// language changes.
+    #[func]
+    pub fn enqueue_message(&mut self, text: GString, duration: f64, priority: MessagePriority) {
+        self.push_message(&text.to_string(), duration, priority);
+    }
+
+    pub fn push_message(&mut self, text: &str, duration: f64, priority: MessagePriority) {
+        let message = QueuedMessage {
+            text: text.to_string(),
+            duration,
+            priority,
+        };
+
+        let Some(current) = self.current_message.take() else {
+            self.display_message(message);
+            return;
+        };
+
+        if priority != MessagePriority::High {
+            self.current_message = Some(current);
+            self.message_queue.push(message);
+            return;
+        }
+
+        if current.priority == MessagePriority::Normal {
+            let remaining = if current.duration > 0f64 {
+                self.message_timer.get_time_left().max(MIN_RESUMED_DURATION)
+            } else {
+                current.duration
+            };
+
+            self.message_queue.push_front(QueuedMessage {
+                duration: remaining,
+                ..current
+            });
+        }
+
+        self.display_message(message);
+    }
+
+    /// Drops every queued message and hides the one on screen.
+    #[func]
+    pub fn flush_messages(&mut self) {
+        self.message_queue.clear();
+        self.current_message = None;
+        self.message_timer.stop();
+        self.message_label.hide();
+    }
+
+    /// Ends the message on screen early and moves on to the next one.
+    #[func]
+    pub fn dismiss_message(&mut self) {
+        self.message_timer.stop();
+        self.advance_messages();
     }
 
-    pub fn show_message(&mut self, text: &str) {
-        self.message_label.set_text(text);
+    fn display_message(&mut self, message: QueuedMessage) {
+        self.message_label.set_text(&message.text);
         self.message_label.show();
-        self.message_timer.start();
+
+        if message.duration > 0f64 {
+            self.message_timer
+                .start_ex()
+                .time_sec(message.duration)
+                .done();
+        } else {
+            self.message_timer.stop();
+        }
+
+        self.current_message = Some(message);
+    }
+
+    fn advance_messages(&mut self) {
+        self.current_message = None;
+
+        match self.message_queue.pop() {
+            Some(message) => self.display_message(message),
+            None => {
+                self.message_label.hide();
+                self.signals().messages_drained().emit();
+            }
+        }
     }
 
+    /// Shows `score`, out of the target in modes that have one.
     pub fn update_score(&mut self, score: i64) {
-        self.score_label.set_text(&score.to_string());
+        let text = match self.mode.target_score() {
+            Some(target) => format!("{score}/{target}"),
+            None => score.to_string(),
+        };
+        self.score_label.set_text(&text);
+    }
+
+    /// Shows `seconds` survived, or the time left in modes with a time limit.
+    pub fn update_time(&mut self, seconds: f64) {
+        let shown = match self.mode.time_limit() {
+            Some(limit) => limit - seconds,
+            None => seconds,
+        };
+        self.time_label.set_text(&format_run_time(shown));
+    }
+
+    /// Lays the score, time and game-over summary out for the rules of `mode`. Called before
+    /// the run's countdown resets the labels.
+    pub fn set_mode(&mut self, mode: GameMode) {
+        self.mode = mode;
+    }
+
+    /// Shows the graze multiplier while it is above 1.
+    pub fn update_multiplier(&mut self, multiplier: f64) {
+        self.multiplier_label.set_text(&format!("x{multiplier:.2}"));
+        self.multiplier_label.set_visible(multiplier > 1f64);
+    }
+
+    /// Shows how the run compares with the ghost of the best one: the `points` it is ahead
+    /// of the best run's score at the same time, negative when behind. `None` hides the delta.
+    pub fn update_ghost_delta(&mut self, points: Option<i64>) {
+        self.ghost_points = points;
+
+        let Some(points) = points else {
+            self.ghost_delta.hide();
+            return;
+        };
+
+        let tint = if points > 0 {
+            AHEAD_COLOR
+        } else {
+            Color::WHITE
+        };
+
+        self.ghost_delta
+            .set_text(&format!("{} {points:+}", tr(TextKey::GhostBest)));
+        self.ghost_delta.set_modulate(tint);
+        self.ghost_delta.show();
+    }
+
+    /// Shows details about the rules of the run, such as the daily challenge's modifiers.
+    /// `ModeInfo::None` hides them.
+    pub fn set_mode_info(&mut self, info: ModeInfo) {
+        self.mode_info_value = info;
+
+        let text = info.text();
+        self.mode_info.set_text(&text);
+        self.mode_info.set_visible(!text.is_empty());
+    }
+
+    /// Builds the texts made with `tr` again in the current language.
+    fn retranslate(&mut self) {
+        self.update_ghost_delta(self.ghost_points);
+        self.set_mode_info(self.mode_info_value);
+
+        for (index, status) in self.player_statuses.clone().into_iter().enumerate() {
+            self.update_player_status(index, status);
+        }
+
+        if let Some(standings) = self.standings.take() {
+            self.set_standings(
+                standings.round,
+                &standings.wins,
+                &standings.tints,
+                standings.winner,
+                standings.match_over,
+            );
+        }
+    }
+
+    /// Timer whose remaining time drives the 3-2-1 countdown shown during
+    /// `GamePhase::Countdown`.
+    pub fn set_countdown_timer(&mut self, timer: Gd<Timer>) {
+        self.countdown_timer = Some(timer);
+    }
+
+    fn show_countdown_step(&mut self, text: &str, pitch: f32) {
+        self.push_message(text, COUNTDOWN_STEP_DURATION, MessagePriority::High);
+
+        let size = self.message_label.get_size();
+        self.message_label.set_pivot_offset(size / 2f32);
+        self.message_label.set_scale(Vector2::ONE * 1.6f32);
+
+        let label = self.message_label.clone();
+        if let Some(mut tween) = self.base_mut().create_tween() {
+            tween.tween_property(&label, "scale", &Vector2::ONE.to_variant(), 0.25f64);
+        }
+
+        self.countdown_tick.set_pitch_scale(pitch);
+        self.countdown_tick.play();
+    }
+
+    /// Fills the game-over summary with `stats` next to `bests`. The panel itself is shown by
+    /// the game-over flow, before the start button comes back.
+    pub fn set_summary(&mut self, stats: &RunStats, bests: &PersonalBests, new_record: bool) {
+        self.clear_summary();
+        self.standings = None;
+        self.game_over_text = match (stats.cleared, self.mode.time_limit()) {
+            (false, _) => TextKey::GameOver,
+            (true, Some(_)) => TextKey::TimeUp,
+            (true, None) => TextKey::SprintFinished,
+        }
+        .to_string();
+
+        let header = if new_record {
+            TextKey::NewRecord.to_string()
+        } else {
+            String::new()
+        };
+
+        let killed_by = stats
+            .death_archetype
+            .as_ref()
+            .map(|archetype| {
+                TextKey::for_archetype(&archetype.to_string())
+                    .map(|key| key.to_string())
+                    .unwrap_or_else(|| archetype.to_string())
+            })
+            .unwrap_or_default();
+
+        let death_position = stats
+            .death_position
+            .map(|position| format!("({:.0}, {:.0})", position.x, position.y))
+            .unwrap_or_default();
+
+        // Until a sprint is cleared there is no fastest time to show.
+        let best_time = if self.mode.fastest_time_wins() && bests.time <= 0f64 {
+            "-".to_string()
+        } else {
+            format_run_time(bests.time)
+        };
+
+        let rows = [
+            [
+                header,
+                TextKey::SummaryRun.to_string(),
+                TextKey::SummaryBest.to_string(),
+            ],
+            [
+                TextKey::SummaryTime.to_string(),
+                format_run_time(stats.time),
+                best_time,
+            ],
+            [
+                TextKey::SummaryScore.to_string(),
+                stats.score.to_string(),
+                bests.score.to_string(),
+            ],
+            [
+                TextKey::SummarySpawned.to_string(),
+                stats.enemies_spawned.to_string(),
+                String::new(),
+            ],
+            [
+                TextKey::SummaryDodged.to_string(),
+                stats.enemies_dodged.to_string(),
+                bests.enemies_dodged.to_string(),
+            ],
+            [
+                TextKey::SummaryGrazes.to_string(),
+                stats.grazes.to_string(),
+                bests.grazes.to_string(),
+            ],
+            [
+                TextKey::SummaryKilledBy.to_string(),
+                killed_by,
+                String::new(),
+            ],
+            [
+                TextKey::SummaryDeathPosition.to_string(),
+                death_position,
+                String::new(),
+            ],
+        ];
+
+        for row in rows {
+            for text in row {
+                let mut label = Label::new_alloc();
+                label.set_theme_type_variation(SMALL_LABEL);
+                label.set_text(&text);
+                self.summary_grid.add_child(&label);
+            }
+        }
+    }
+
+    pub fn set_restart_enabled(&mut self, enabled: bool) {
+        self.restart_enabled = enabled;
+    }
+
+    /// Sets up one status line per player, tinted like the player. Hidden for runs with a
+    /// single player.
+    pub fn set_players(&mut self, tints: &[Color]) {
+        self.player_statuses.clear();
+
+        for mut child in self.player_status.get_children().iter_shared() {
+            self.player_status.remove_child(&child);
+            child.queue_free();
+        }
+
+        for (index, tint) in tints.iter().enumerate() {
+            let mut label = Label::new_alloc();
+            label.set_theme_type_variation(SMALL_LABEL);
+            label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+            label.set_modulate(*tint);
+            self.player_status.add_child(&label);
+            self.update_player_status(index, PlayerStatus::Alive);
+        }
+
+        self.player_status.set_visible(tints.len() > 1);
+    }
+
+    /// Shows whether player `index` is alive, out, or how many seconds are left before it
+    /// comes back.
+    pub fn update_player_status(&mut self, index: usize, status: PlayerStatus) {
+        if self.player_statuses.len() <= index {
+            self.player_statuses.resize(index + 1, PlayerStatus::Alive);
+        }
+        self.player_statuses[index] = status;
+
+        let Some(mut label) = self
+            .player_status
+            .get_child(index as i32)
+            .and_then(|child| child.try_cast::<Label>().ok())
+        else {
+            return;
+        };
+
+        let status = match status {
+            PlayerStatus::Alive => tr(TextKey::PlayerAlive),
+            PlayerStatus::Down(seconds) => {
+                format!("{} {}s", tr(TextKey::PlayerReviving), seconds.ceil())
+            }
+            PlayerStatus::Out => tr(TextKey::PlayerOut),
+        };
+
+        label.set_text(&format!(
+            "{}{}: {status}",
+            tr(TextKey::PlayerShort),
+            index + 1
+        ));
+    }
+
+    /// Fills the summary panel with the versus standings after `round`: the wins of every
+    /// player, tinted like the player, with `winner` marked. Also sets the message announcing
+    /// the result, shown on `GamePhase::RoundOver` or, when `match_over`, by the game-over
+    /// flow.
+    pub fn set_standings(
+        &mut self,
+        round: i64,
+        wins: &[i64],
+        tints: &[Color],
+        winner: Option<usize>,
+        match_over: bool,
+    ) {
+        self.clear_summary();
+
+        let result = match winner {
+            Some(index) => {
+                let outcome = if match_over {
+                    TextKey::VersusMatchWinner
+                } else {
+                    TextKey::VersusRoundWinner
+                };
+                format!("{}{} {}", tr(TextKey::PlayerShort), index + 1, tr(outcome))
+            }
+            None => tr(TextKey::VersusDraw),
+        };
+
+        let header = [
+            format!("{} {round}", tr(TextKey::VersusRound)),
+            tr(TextKey::VersusWins),
+            String::new(),
+        ];
+        self.add_summary_row(&header, Color::WHITE);
+
+        for (index, count) in wins.iter().enumerate() {
+            let marker = if winner == Some(index) { "*" } else { "" };
+            let row = [
+                format!("{}{}", tr(TextKey::PlayerShort), index + 1),
+                count.to_string(),
+                marker.to_string(),
+            ];
+            let tint = tints.get(index).copied().unwrap_or(Color::WHITE);
+            self.add_summary_row(&row, tint);
+        }
+
+        if match_over {
+            self.game_over_text = result;
+        } else {
+            self.round_result = result;
+        }
+
+        self.standings = Some(Standings {
+            round,
+            wins: wins.to_vec(),
+            tints: tints.to_vec(),
+            winner,
+            match_over,
+        });
+    }
+
+    fn clear_summary(&mut self) {
+        for mut child in self.summary_grid.get_children().iter_shared() {
+            self.summary_grid.remove_child(&child);
+            child.queue_free();
+        }
+    }
+
+    /// Adds already translated `texts` to the summary grid, one label per column.
+    fn add_summary_row(&mut self, texts: &[String], tint: Color) {
+        for text in texts {
+            let mut label = Label::new_alloc();
+            label.set_theme_type_variation(SMALL_LABEL);
+            label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+            label.set_modulate(tint);
+            label.set_text(text);
+            self.summary_grid.add_child(&label);
+        }
+    }
+
+    /// Warns that a boss is coming, over any message on screen, for `duration` seconds.
+    pub fn warn_boss(&mut self, duration: f64) {
+        let text = tr(TextKey::BossWarning);
+        self.push_message(&text, duration, MessagePriority::High);
+    }
+
+    /// Announces the `bonus` for outlasting a boss, with a popup at `at` (in screen coordinates).
+    pub fn show_boss_bonus(&mut self, bonus: i64, at: Vector2) {
+        let text = tr(TextKey::BossBonus);
+        self.push_message(&text, BOSS_BONUS_MESSAGE_DURATION, MessagePriority::Normal);
+        self.show_popup(&format!("+{bonus}"), at);
+    }
+
+    /// Spawns a floating `text` popup centred on `at` (in screen coordinates).
+    pub fn show_popup(&mut self, text: &str, at: Vector2) {
+        let mut popup = ScorePopup::create(text, at);
+        popup.set_theme(&*self.popup_theme.get_or_insert_with(hud_theme));
+        popup.set_theme_type_variation(POPUP_LABEL);
+        self.base_mut().add_child(&popup);
+    }
+
+    #[func]
+    pub fn on_phase_changed(&mut self, from: GamePhase, to: GamePhase) {
+        self.phase = to;
+
+        match to {
+            GamePhase::Countdown => {
+                self.flush_messages();
+                self.start_button.hide();
+                self.main_menu_button.hide();
+                self.summary_panel.hide();
+                self.update_score(0);
+                self.update_time(0f64);
+                self.update_multiplier(1f64);
+                self.counting_down = true;
+                self.countdown_step = 0;
+            }
+            GamePhase::Paused => {
+                self.push_message(&TextKey::Paused.to_string(), 0f64, MessagePriority::High);
+                self.settings_button.show();
+                self.main_menu_button.show();
+                self.main_menu_button.grab_focus();
+            }
+            GamePhase::Playing if from == GamePhase::Paused => {
+                self.settings_button.hide();
+                self.main_menu_button.hide();
+                self.dismiss_message();
+            }
+            GamePhase::Playing => {
+                self.counting_down = false;
+                self.show_countdown_step(&TextKey::Go.to_string(), 1.5f32);
+            }
+            GamePhase::RoundOver => {
+                self.counting_down = false;
+                self.flush_messages();
+
+                let text = self.round_result.clone();
+                self.push_message(&text, 0f64, MessagePriority::High);
+                self.summary_panel.show();
+                self.main_menu_button.show();
+            }
+            GamePhase::GameOver => {
+                self.counting_down = false;
+
+                let hud = self.to_gd();
+                godot::task::spawn(Self::game_over(hud));
+            }
+            GamePhase::Title => {
+                self.counting_down = false;
+                self.flush_messages();
+                self.start_button.hide();
+                self.settings_button.hide();
+                self.main_menu_button.hide();
+                self.summary_panel.hide();
+                self.player_status.hide();
+                self.ghost_delta.hide();
+                self.mode_info.hide();
+            }
+        }
     }
 
     #[func]
     fn on_start_button_pressed(&mut self) {
-        self.start_button.hide();
         self.signals().start_game().emit();
     }
 
+    #[func]
+    fn on_main_menu_button_pressed(&mut self) {
+        self.signals().main_menu_requested().emit();
+    }
+
+    #[func]
+    fn on_settings_button_pressed(&mut self) {
+        self.signals().settings_requested().emit();
+    }
+
     #[func]
     fn on_message_timer_timeout(&mut self) {
-        self.message_label.hide();
+        self.advance_messages();
     }
 }
+
+/// Formats a run time as `mm:ss.cc`, truncating so the clock never shows time not yet survived.
+pub fn format_run_time(seconds: f64) -> String {
+    let centiseconds = (seconds.max(0f64) * 100f64).floor() as u64;
+
+    format!(
+        "{:02}:{:02}.{:02}",
+        centiseconds / 6000,
+        (centiseconds / 100) % 60,
+        centiseconds % 100
+    )
+}
+
+/// Short decaying 880 Hz beep used for the countdown when no stream is assigned.
+fn tick_stream() -> Gd<AudioStreamWav> {
+    tone_stream(&[880f32], 0.08, 0.5)
+}
@@ -0,0 +1,63 @@
+use godot::{
+    builtin::Side,
+    classes::{Control, Font, ResourceLoader, StyleBoxFlat, Theme, control::LayoutPreset},
+    prelude::*,
+};
+
+use crate::localization::{TextKey, tr};
+
+const FONT_PATH: &str = "res://assets/fonts/Xolonium-Regular.ttf";
+
+/// Theme type variation for the large score readout.
+pub const LARGE_LABEL: &str = "HudLargeLabel";
+/// Theme type variation for table cells such as the run summary.
+pub const SMALL_LABEL: &str = "HudSmallLabel";
+/// Theme type variation for floating score popups.
+pub const POPUP_LABEL: &str = "HudPopupLabel";
+
+/// Theme applied to HUD controls that `HUDBase` creates itself, matching the look of
+/// `HUD.tscn`: Xolonium at 32px, 64px for the score, and a translucent summary panel.
+pub fn hud_theme() -> Gd<Theme> {
+    let mut theme = Theme::new_gd();
+    theme.set_default_font_size(32);
+
+    match ResourceLoader::singleton()
+        .load(FONT_PATH)
+        .and_then(|resource| resource.try_cast::<Font>().ok())
+    {
+        Some(font) => theme.set_default_font(&font),
+        None => godot_warn!("{}", tr(TextKey::ErrorLoadFont)),
+    }
+
+    theme.set_type_variation(LARGE_LABEL, "Label");
+    theme.set_font_size("font_size", LARGE_LABEL, 64);
+
+    theme.set_font_size("font_size", "OptionButton", 16);
+    theme.set_font_size("font_size", "PopupMenu", 16);
+    theme.set_type_variation(SMALL_LABEL, "Label");
+    theme.set_font_size("font_size", SMALL_LABEL, 20);
+    theme.set_constant("h_separation", "GridContainer", 16);
+    theme.set_type_variation(POPUP_LABEL, "Label");
+    theme.set_font_size("font_size", POPUP_LABEL, 24);
+
+    let mut panel = StyleBoxFlat::new_gd();
+    panel.set_bg_color(Color::from_rgba(0f32, 0f32, 0f32, 0.75f32));
+    panel.set_corner_radius_all(8);
+    panel.set_content_margin_all(16f32);
+    theme.set_stylebox("panel", "PanelContainer", &panel);
+
+    theme
+}
+
+/// Anchors `control` with `preset` and sets its offsets relative to those anchors, in the
+/// order left, top, right, bottom.
+pub fn place(control: &mut Control, preset: LayoutPreset, offsets: [f32; 4]) {
+    control.set_anchors_preset(preset);
+
+    for (side, offset) in [Side::LEFT, Side::TOP, Side::RIGHT, Side::BOTTOM]
+        .into_iter()
+        .zip(offsets)
+    {
+        control.set_offset(side, offset);
+    }
+}
@@ -0,0 +1,476 @@
+use core::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use godot::{
+    classes::{
+        ConfigFile, Input, InputEvent, InputEventJoypadButton, InputEventJoypadMotion,
+        InputEventKey, InputMap, Os,
+    },
+    global::{Error, JoyAxis, JoyButton, Key},
+    prelude::*,
+};
+
+use crate::localization::{TextKey, tr};
+
+const BINDINGS_PATH: &str = "user://bindings.cfg";
+const BINDINGS_SECTION: &str = "bindings";
+const DEADZONE: f32 = 0.5;
+
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// Game-specific input actions. Gameplay and the HUD read these instead of the `ui_*`
+/// actions, which stay reserved for navigating menus.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameAction {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Start,
+    Pause,
+    Dash,
+}
+
+impl GameAction {
+    pub const ALL: [GameAction; 7] = [
+        GameAction::MoveLeft,
+        GameAction::MoveRight,
+        GameAction::MoveUp,
+        GameAction::MoveDown,
+        GameAction::Start,
+        GameAction::Pause,
+        GameAction::Dash,
+    ];
+
+    pub fn text_key(self) -> TextKey {
+        match self {
+            GameAction::MoveLeft => TextKey::ActionMoveLeft,
+            GameAction::MoveRight => TextKey::ActionMoveRight,
+            GameAction::MoveUp => TextKey::ActionMoveUp,
+            GameAction::MoveDown => TextKey::ActionMoveDown,
+            GameAction::Start => TextKey::ActionStart,
+            GameAction::Pause => TextKey::ActionPause,
+            GameAction::Dash => TextKey::ActionDash,
+        }
+    }
+
+    pub fn name(self) -> StringName {
+        StringName::from(&self.to_string())
+    }
+
+    /// Bindings shipped with the game: keyboard first, then gamepad.
+    pub fn default_events(self) -> Vec<Gd<InputEvent>> {
+        match self {
+            GameAction::MoveLeft => vec![
+                key(Key::LEFT),
+                key(Key::A),
+                joypad_button(JoyButton::DPAD_LEFT),
+                joypad_axis(JoyAxis::LEFT_X, -1f32),
+            ],
+            GameAction::MoveRight => vec![
+                key(Key::RIGHT),
+                key(Key::D),
+                joypad_button(JoyButton::DPAD_RIGHT),
+                joypad_axis(JoyAxis::LEFT_X, 1f32),
+            ],
+            GameAction::MoveUp => vec![
+                key(Key::UP),
+                key(Key::W),
+                joypad_button(JoyButton::DPAD_UP),
+                joypad_axis(JoyAxis::LEFT_Y, -1f32),
+            ],
+            GameAction::MoveDown => vec![
+                key(Key::DOWN),
+                key(Key::S),
+                joypad_button(JoyButton::DPAD_DOWN),
+                joypad_axis(JoyAxis::LEFT_Y, 1f32),
+            ],
+            GameAction::Start => vec![key(Key::SPACE), joypad_button(JoyButton::START)],
+            GameAction::Pause => vec![
+                key(Key::ESCAPE),
+                key(Key::P),
+                joypad_button(JoyButton::BACK),
+            ],
+            GameAction::Dash => vec![key(Key::SHIFT), joypad_button(JoyButton::A)],
+        }
+    }
+
+    /// Current bindings of the action in the `InputMap`.
+    pub fn events(self) -> Vec<Gd<InputEvent>> {
+        InputMap::singleton()
+            .action_get_events(&self.name())
+            .iter_shared()
+            .collect()
+    }
+
+    /// Replaces the bindings of the same kind as `event` (keyboard or gamepad) with `event`
+    /// alone. Any other action bound to the same input loses it, and is returned so the
+    /// caller can tell the player.
+    pub fn rebind(self, event: &Gd<InputEvent>) -> Option<GameAction> {
+        let mut input_map = InputMap::singleton();
+        let device = Device::of(event);
+
+        let conflict = GameAction::ALL.into_iter().find(|other| {
+            *other != self
+                && other
+                    .events()
+                    .iter()
+                    .any(|bound| bound.is_match_ex(event).exact_match(false).done())
+        });
+
+        if let Some(other) = conflict {
+            for bound in other.events() {
+                if bound.is_match_ex(event).exact_match(false).done() {
+                    input_map.action_erase_event(&other.name(), &bound);
+                }
+            }
+        }
+
+        for bound in self.events() {
+            if Device::of(&bound) == device {
+                input_map.action_erase_event(&self.name(), &bound);
+            }
+        }
+
+        input_map.action_add_event(&self.name(), event);
+        conflict
+    }
+
+    /// Short, human-readable list of the bindings, keyboard first.
+    pub fn describe(self) -> String {
+        let mut events = self.events();
+        events.sort_by_key(|event| Device::of(event) != Device::Keyboard);
+
+        events
+            .iter()
+            .map(describe_event)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Set of actions a player reads in local multiplayer, so several players can share the
+/// keyboard and each gamepad drives its own player. `Shared` is the single-player set: the
+/// rebindable `GameAction`s, answering to every keyboard layout and gamepad.
+#[derive(GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[godot(via = i64)]
+pub enum InputProfile {
+    #[default]
+    Shared,
+    PlayerOne,
+    PlayerTwo,
+    PlayerThree,
+    PlayerFour,
+}
+
+impl InputProfile {
+    /// Profiles handed out to local players, in seat order.
+    pub const LOCAL: [InputProfile; 4] = [
+        InputProfile::PlayerOne,
+        InputProfile::PlayerTwo,
+        InputProfile::PlayerThree,
+        InputProfile::PlayerFour,
+    ];
+
+    /// Actions that exist once per local profile. Start and pause stay shared.
+    const PER_PLAYER: [GameAction; 5] = [
+        GameAction::MoveLeft,
+        GameAction::MoveRight,
+        GameAction::MoveUp,
+        GameAction::MoveDown,
+        GameAction::Dash,
+    ];
+
+    /// Name of `action` in this profile, e.g. `p2_move_left`.
+    pub fn action(self, action: GameAction) -> StringName {
+        match self.seat() {
+            Some(seat) => StringName::from(&format!("p{}_{action}", seat + 1)),
+            None => action.name(),
+        }
+    }
+
+    /// Movement the profile's actions ask for, each axis from -1 to 1.
+    pub fn movement(self) -> Vector2 {
+        let input = Input::singleton();
+        let strength = |action: GameAction| input.get_action_strength(&self.action(action));
+
+        Vector2::new(
+            strength(GameAction::MoveRight) - strength(GameAction::MoveLeft),
+            strength(GameAction::MoveDown) - strength(GameAction::MoveUp),
+        )
+    }
+
+    pub fn dash_just_pressed(self) -> bool {
+        Input::singleton().is_action_just_pressed(&self.action(GameAction::Dash))
+    }
+
+    /// Index of the local player using the profile, which is also the gamepad it listens to.
+    fn seat(self) -> Option<usize> {
+        InputProfile::LOCAL
+            .into_iter()
+            .position(|profile| profile == self)
+    }
+
+    /// Keyboard cluster of the profile (left, right, up, down, dash) and its gamepad.
+    fn default_events(self, action: GameAction) -> Vec<Gd<InputEvent>> {
+        let Some(seat) = self.seat() else {
+            return action.default_events();
+        };
+
+        let keys = match self {
+            InputProfile::PlayerOne => [Key::A, Key::D, Key::W, Key::S, Key::SHIFT],
+            InputProfile::PlayerTwo => [Key::LEFT, Key::RIGHT, Key::UP, Key::DOWN, Key::ENTER],
+            InputProfile::PlayerThree => [Key::J, Key::L, Key::I, Key::K, Key::H],
+            _ => [Key::KP_4, Key::KP_6, Key::KP_8, Key::KP_5, Key::KP_0],
+        };
+
+        let (key_code, mut pad) = match action {
+            GameAction::MoveLeft => (keys[0], joypad_axis(JoyAxis::LEFT_X, -1f32)),
+            GameAction::MoveRight => (keys[1], joypad_axis(JoyAxis::LEFT_X, 1f32)),
+            GameAction::MoveUp => (keys[2], joypad_axis(JoyAxis::LEFT_Y, -1f32)),
+            GameAction::MoveDown => (keys[3], joypad_axis(JoyAxis::LEFT_Y, 1f32)),
+            _ => (keys[4], joypad_button(JoyButton::A)),
+        };
+
+        pad.set_device(seat as i32);
+
+        vec![key(key_code), pad]
+    }
+}
+
+impl fmt::Display for GameAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameAction::MoveLeft => write!(f, "move_left"),
+            GameAction::MoveRight => write!(f, "move_right"),
+            GameAction::MoveUp => write!(f, "move_up"),
+            GameAction::MoveDown => write!(f, "move_down"),
+            GameAction::Start => write!(f, "start"),
+            GameAction::Pause => write!(f, "pause"),
+            GameAction::Dash => write!(f, "dash"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Device {
+    Keyboard,
+    Gamepad,
+    Other,
+}
+
+impl Device {
+    fn of(event: &Gd<InputEvent>) -> Self {
+        if event.clone().try_cast::<InputEventKey>().is_ok() {
+            Device::Keyboard
+        } else if event.clone().try_cast::<InputEventJoypadButton>().is_ok()
+            || event.clone().try_cast::<InputEventJoypadMotion>().is_ok()
+        {
+            Device::Gamepad
+        } else {
+            Device::Other
+        }
+    }
+}
+
+/// Adds every `GameAction` and the per-player actions of each `InputProfile` to the
+/// `InputMap` with their default bindings, then applies the bindings saved in
+/// `user://bindings.cfg`. Only the first call does anything, so every node
+/// that reads the actions can call it from `ready`.
+pub fn ensure_registered() {
+    if REGISTERED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let mut input_map = InputMap::singleton();
+
+    for action in GameAction::ALL {
+        if input_map.has_action(&action.name()) {
+            continue;
+        }
+
+        input_map
+            .add_action_ex(&action.name())
+            .deadzone(DEADZONE)
+            .done();
+
+        for event in action.default_events() {
+            input_map.action_add_event(&action.name(), &event);
+        }
+    }
+
+    for profile in InputProfile::LOCAL {
+        for action in InputProfile::PER_PLAYER {
+            let name = profile.action(action);
+
+            if input_map.has_action(&name) {
+                continue;
+            }
+
+            input_map.add_action_ex(&name).deadzone(DEADZONE).done();
+
+            for event in profile.default_events(action) {
+                input_map.action_add_event(&name, &event);
+            }
+        }
+    }
+
+    load();
+}
+
+/// Puts every action back to its default bindings and saves them.
+pub fn reset_to_defaults() {
+    let mut input_map = InputMap::singleton();
+
+    for action in GameAction::ALL {
+        input_map.action_erase_events(&action.name());
+
+        for event in action.default_events() {
+            input_map.action_add_event(&action.name(), &event);
+        }
+    }
+
+    save();
+}
+
+pub fn save() {
+    let mut config = ConfigFile::new_gd();
+
+    for action in GameAction::ALL {
+        let events = action
+            .events()
+            .into_iter()
+            .map(|event| event.to_variant())
+            .collect::<VarArray>();
+        config.set_value(BINDINGS_SECTION, &action.to_string(), &events.to_variant());
+    }
+
+    if config.save(BINDINGS_PATH) != Error::OK {
+        godot_warn!("{}", tr(TextKey::ErrorSaveBindings));
+    }
+}
+
+fn load() {
+    let mut config = ConfigFile::new_gd();
+
+    if config.load(BINDINGS_PATH) != Error::OK {
+        return;
+    }
+
+    let mut input_map = InputMap::singleton();
+
+    for action in GameAction::ALL {
+        if !config.has_section_key(BINDINGS_SECTION, &action.to_string()) {
+            continue;
+        }
+
+        let Ok(events) = config
+            .get_value(BINDINGS_SECTION, &action.to_string())
+            .try_to::<VarArray>()
+        else {
+            continue;
+        };
+
+        input_map.action_erase_events(&action.name());
+
+        for event in events.iter_shared() {
+            if let Ok(event) = event.try_to::<Gd<InputEvent>>() {
+                input_map.action_add_event(&action.name(), &event);
+            }
+        }
+    }
+}
+
+/// A binding for `event` fit for the `InputMap`: any device, no modifiers, and a full
+/// deflection for sticks. Returns `None` for input that can't be bound, such as a released
+/// key or a stick resting near its centre.
+pub fn binding_for(event: &Gd<InputEvent>) -> Option<Gd<InputEvent>> {
+    if let Ok(key_event) = event.clone().try_cast::<InputEventKey>() {
+        if !key_event.is_pressed() || key_event.is_echo() {
+            return None;
+        }
+
+        let code = match key_event.get_physical_keycode() {
+            Key::NONE => key_event.get_keycode(),
+            physical => physical,
+        };
+
+        return Some(key(code));
+    }
+
+    if let Ok(button) = event.clone().try_cast::<InputEventJoypadButton>() {
+        return button
+            .is_pressed()
+            .then(|| joypad_button(button.get_button_index()));
+    }
+
+    if let Ok(motion) = event.clone().try_cast::<InputEventJoypadMotion>() {
+        let value = motion.get_axis_value();
+        return (value.abs() >= DEADZONE).then(|| joypad_axis(motion.get_axis(), value.signum()));
+    }
+
+    None
+}
+
+/// Whether `binding`, as returned by `binding_for`, is Escape or the gamepad's Back button,
+/// which call off a rebinding unless pressed twice.
+pub fn cancels_capture(binding: &Gd<InputEvent>) -> bool {
+    [key(Key::ESCAPE), joypad_button(JoyButton::BACK)]
+        .iter()
+        .any(|cancel| cancel.is_match(binding))
+}
+
+fn describe_event(event: &Gd<InputEvent>) -> String {
+    if let Ok(key_event) = event.clone().try_cast::<InputEventKey>() {
+        let code = match key_event.get_physical_keycode() {
+            Key::NONE => key_event.get_keycode(),
+            physical => physical,
+        };
+        return Os::singleton().get_keycode_string(code).to_string();
+    }
+
+    if let Ok(button) = event.clone().try_cast::<InputEventJoypadButton>() {
+        return format!(
+            "{} {}",
+            tr(TextKey::BindingPadButton),
+            button.get_button_index().ord()
+        );
+    }
+
+    if let Ok(motion) = event.clone().try_cast::<InputEventJoypadMotion>() {
+        let sign = if motion.get_axis_value() < 0f32 {
+            '-'
+        } else {
+            '+'
+        };
+        return format!(
+            "{} {}{sign}",
+            tr(TextKey::BindingPadAxis),
+            motion.get_axis().ord()
+        );
+    }
+
+    event.as_text().to_string()
+}
+
+fn key(code: Key) -> Gd<InputEvent> {
+    let mut event = InputEventKey::new_gd();
+    event.set_device(-1);
+    event.set_physical_keycode(code);
+    event.upcast()
+}
+
+fn joypad_button(button: JoyButton) -> Gd<InputEvent> {
+    let mut event = InputEventJoypadButton::new_gd();
+    event.set_device(-1);
+    event.set_button_index(button);
+    event.upcast()
+}
+
+fn joypad_axis(axis: JoyAxis, value: f32) -> Gd<InputEvent> {
+    let mut event = InputEventJoypadMotion::new_gd();
+    event.set_device(-1);
+    event.set_axis(axis);
+    event.set_axis_value(value);
+    event.upcast()
+}
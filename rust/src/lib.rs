@@ -1,11 +1,42 @@
+mod audio;
+mod boss;
+mod controls_panel;
+mod daily;
+mod daily_calendar;
 mod enemy;
+mod feedback;
+mod game_mode;
+mod game_phase;
+mod ghost;
 mod hud;
+mod hud_theme;
+mod input_bindings;
+mod lobby;
+mod localization;
+mod main_menu;
 mod main_scene;
+mod message_queue;
+mod network;
 mod player;
+mod run_stats;
+mod score_popup;
+mod scoring_rules;
+mod settings;
+mod settings_panel;
+mod spawn_pattern;
+mod versus;
+mod wave;
+mod wave_json;
 
 use godot::prelude::*;
 
 struct RustExtension;
 
 #[gdextension]
-unsafe impl ExtensionLibrary for RustExtension {}
+unsafe impl ExtensionLibrary for RustExtension {
+    fn on_stage_init(stage: InitStage) {
+        if stage == InitStage::Scene {
+            localization::register_translations();
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use core::fmt;
+
+use godot::{
+    builtin::Side,
+    classes::{
+        Button, CanvasLayer, Control, Engine, HBoxContainer, ICanvasLayer, InputEvent, Label,
+        LineEdit, PanelContainer, VBoxContainer,
+        box_container::AlignmentMode,
+        control::{LayoutPreset, SizeFlags},
+        node::AutoTranslateMode,
+    },
+    global::HorizontalAlignment,
+    prelude::*,
+};
+
+use crate::{
+    game_phase::GamePhase,
+    hud_theme::{LARGE_LABEL, SMALL_LABEL, hud_theme, place},
+    localization::{TextKey, tr},
+    network::{DEFAULT_ADDRESS, HOST_PEER},
+};
+
+/// Drawn above the HUD and the menu, like the settings panel.
+const LOBBY_LAYER: i32 = 2;
+
+enum LobbyChild {
+    Panel,
+    Content,
+    Title,
+    AddressEdit,
+    Buttons,
+    HostButton,
+    JoinButton,
+    Peers,
+    Status,
+    StartButton,
+    BackButton,
+}
+
+impl fmt::Display for LobbyChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LobbyChild::Panel => write!(f, "Panel"),
+            LobbyChild::Content => write!(f, "Panel/Content"),
+            LobbyChild::Title => write!(f, "Panel/Content/Title"),
+            LobbyChild::AddressEdit => write!(f, "Panel/Content/AddressEdit"),
+            LobbyChild::Buttons => write!(f, "Panel/Content/Buttons"),
+            LobbyChild::HostButton => write!(f, "Panel/Content/Buttons/HostButton"),
+            LobbyChild::JoinButton => write!(f, "Panel/Content/Buttons/JoinButton"),
+            LobbyChild::Peers => write!(f, "Panel/Content/Peers"),
+            LobbyChild::Status => write!(f, "Panel/Content/Status"),
+            LobbyChild::StartButton => write!(f, "Panel/Content/StartButton"),
+            LobbyChild::BackButton => write!(f, "Panel/Content/BackButton"),
+        }
+    }
+}
+
+impl LobbyChild {
+    /// Name of the node itself, without the path to it.
+    fn name(&self) -> String {
+        let path = self.to_string();
+        path.rsplit('/').next().unwrap_or_default().to_string()
+    }
+}
+
+/// Screen for hosting or joining a LAN game. It only asks for things through its signals;
+/// the owner passes them on to the `NetworkSession` and feeds `on_lobby_changed` back.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer, tool)]
+pub struct Lobby {
+    #[base]
+    base: Base<CanvasLayer>,
+    return_focus: Option<Gd<Control>>,
+    address_edit: OnReady<Gd<LineEdit>>,
+    host_button: OnReady<Gd<Button>>,
+    join_button: OnReady<Gd<Button>>,
+    peers: OnReady<Gd<Label>>,
+    status: OnReady<Gd<Label>>,
+    start_button: OnReady<Gd<Button>>,
+    back_button: OnReady<Gd<Button>>,
+}
+
+#[godot_api]
+impl ICanvasLayer for Lobby {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            return_focus: None,
+            address_edit: OnReady::from_node(&LobbyChild::AddressEdit.to_string()),
+            host_button: OnReady::from_node(&LobbyChild::HostButton.to_string()),
+            join_button: OnReady::from_node(&LobbyChild::JoinButton.to_string()),
+            peers: OnReady::from_node(&LobbyChild::Peers.to_string()),
+            status: OnReady::from_node(&LobbyChild::Status.to_string()),
+            start_button: OnReady::from_node(&LobbyChild::StartButton.to_string()),
+            back_button: OnReady::from_node(&LobbyChild::BackButton.to_string()),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        let owner = self.base().clone().upcast::<Node>();
+
+        let mut panel = self
+            .base()
+            .try_get_node_as::<PanelContainer>(&LobbyChild::Panel.to_string())
+            .unwrap_or_else(|| {
+                let mut panel = PanelContainer::new_alloc();
+                panel.set_name(&LobbyChild::Panel.to_string());
+                panel.set_theme(&hud_theme());
+                place(
+                    &mut panel,
+                    LayoutPreset::CENTER,
+                    [-220f32, -220f32, 220f32, 220f32],
+                );
+                self.base_mut().add_child(&panel);
+                panel.set_owner(&owner);
+                panel
+            });
+
+        let mut content = panel
+            .try_get_node_as::<VBoxContainer>(&LobbyChild::Content.name())
+            .unwrap_or_else(|| {
+                let mut content = VBoxContainer::new_alloc();
+                content.set_name(&LobbyChild::Content.name());
+                content.set_alignment(AlignmentMode::CENTER);
+                panel.add_child(&content);
+                content.set_owner(&owner);
+                content
+            });
+
+        if !content.has_node(&LobbyChild::Title.name()) {
+            let mut label = Label::new_alloc();
+            label.set_name(&LobbyChild::Title.name());
+            label.set_theme_type_variation(LARGE_LABEL);
+            label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+            label.set_text(&TextKey::LobbyTitle.to_string());
+            content.add_child(&label);
+            label.set_owner(&owner);
+        }
+
+        if !content.has_node(&LobbyChild::AddressEdit.name()) {
+            let mut edit = LineEdit::new_alloc();
+            edit.set_name(&LobbyChild::AddressEdit.name());
+            edit.set_text(DEFAULT_ADDRESS);
+            edit.set_placeholder(&TextKey::LobbyAddress.to_string());
+            edit.set_custom_minimum_size(Vector2::new(240f32, 0f32));
+            edit.set_h_size_flags(SizeFlags::SHRINK_CENTER);
+            content.add_child(&edit);
+            edit.set_owner(&owner);
+        }
+
+        let mut buttons = content
+            .try_get_node_as::<HBoxContainer>(&LobbyChild::Buttons.name())
+            .unwrap_or_else(|| {
+                let mut buttons = HBoxContainer::new_alloc();
+                buttons.set_name(&LobbyChild::Buttons.name());
+                buttons.set_alignment(AlignmentMode::CENTER);
+                content.add_child(&buttons);
+                buttons.set_owner(&owner);
+                buttons
+            });
+
+        for (child, key) in [
+            (LobbyChild::HostButton, TextKey::LobbyHost),
+            (LobbyChild::JoinButton, TextKey::LobbyJoin),
+        ] {
+            if buttons.has_node(&child.name()) {
+                continue;
+            }
+
+            let mut button = Button::new_alloc();
+            button.set_name(&child.name());
+            button.set_text(&key.to_string());
+            buttons.add_child(&button);
+            button.set_owner(&owner);
+        }
+
+        for child in [LobbyChild::Peers, LobbyChild::Status] {
+            if content.has_node(&child.name()) {
+                continue;
+            }
+
+            let mut label = Label::new_alloc();
+            label.set_name(&child.name());
+            label.set_theme_type_variation(SMALL_LABEL);
+            label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+            label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+            content.add_child(&label);
+            label.set_owner(&owner);
+        }
+
+        for (child, key) in [
+            (LobbyChild::StartButton, TextKey::LobbyStart),
+            (LobbyChild::BackButton, TextKey::MenuBack),
+        ] {
+            if content.has_node(&child.name()) {
+                continue;
+            }
+
+            let mut button = Button::new_alloc();
+            button.set_name(&child.name());
+            button.set_text(&key.to_string());
+            button.set_h_size_flags(SizeFlags::SHRINK_CENTER);
+            content.add_child(&button);
+            button.set_owner(&owner);
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_layer(LOBBY_LAYER);
+
+        self.host_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_host_pressed);
+        self.join_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_join_pressed);
+        self.start_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_start_pressed);
+        self.back_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_back_pressed);
+
+        self.setup_focus_wrap();
+
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        self.start_button.hide();
+        self.base_mut().hide();
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        if !self.base().is_visible() || !event.is_action_pressed("ui_cancel") {
+            return;
+        }
+
+        self.on_back_pressed();
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+    }
+}
+
+#[godot_api]
+impl Lobby {
+    #[signal]
+    pub fn host_requested();
+
+    #[signal]
+    pub fn join_requested(address: GString);
+
+    /// Only offered to the host, once at least one client has joined.
+    #[signal]
+    pub fn start_requested();
+
+    /// The lobby was closed with the back button or `ui_cancel`; the owner leaves the session.
+    #[signal]
+    pub fn back_requested();
+
+    /// Shows the lobby and remembers the focused control, which gets the focus back on
+    /// `close`.
+    #[func]
+    pub fn open(&mut self) {
+        if self.base().is_visible() {
+            return;
+        }
+
+        self.return_focus = self
+            .base()
+            .get_viewport()
+            .and_then(|viewport| viewport.gui_get_focus_owner());
+
+        self.base_mut().show();
+        self.host_button.grab_focus();
+    }
+
+    #[func]
+    pub fn close(&mut self) {
+        if !self.base().is_visible() {
+            return;
+        }
+
+        self.base_mut().hide();
+
+        if let Some(mut control) = self.return_focus.take()
+            && control.is_instance_valid()
+            && control.is_visible_in_tree()
+        {
+            control.grab_focus();
+        }
+    }
+
+    /// Lists `peers`, the first of which is this instance, and shows `status`. The host gets
+    /// the start button once someone has joined.
+    #[func]
+    pub fn on_lobby_changed(&mut self, peers: PackedInt64Array, local_peer: i64, status: GString) {
+        let lines = peers
+            .as_slice()
+            .iter()
+            .enumerate()
+            .map(|(index, peer)| {
+                let mut line = format!("{}{}", tr(TextKey::PlayerShort), index + 1);
+
+                if index == 0 && local_peer == HOST_PEER {
+                    line.push_str(&format!(" {}", tr(TextKey::LobbyHostTag)));
+                }
+
+                if *peer == local_peer {
+                    line.push_str(&format!(" {}", tr(TextKey::LobbyYouTag)));
+                }
+
+                line
+            })
+            .collect::<Vec<_>>();
+
+        let peers_text = if lines.is_empty() {
+            String::new()
+        } else {
+            format!("{} {}", tr(TextKey::LobbyPlayers), lines.join(", "))
+        };
+
+        self.peers.set_text(&peers_text);
+        self.status.set_text(&status);
+
+        let can_start = local_peer == HOST_PEER && peers.len() > 1;
+        self.start_button.set_visible(can_start);
+    }
+
+    /// Gets out of the way once a run starts.
+    #[func]
+    pub fn on_phase_changed(&mut self, _from: GamePhase, to: GamePhase) {
+        if to != GamePhase::Title {
+            self.close();
+        }
+    }
+
+    #[func]
+    fn on_host_pressed(&mut self) {
+        self.signals().host_requested().emit();
+    }
+
+    #[func]
+    fn on_join_pressed(&mut self) {
+        let address = self.address_edit.get_text();
+        self.signals().join_requested().emit(&address);
+    }
+
+    #[func]
+    fn on_start_pressed(&mut self) {
+        self.signals().start_requested().emit();
+    }
+
+    #[func]
+    fn on_back_pressed(&mut self) {
+        self.close();
+        self.signals().back_requested().emit();
+    }
+
+    /// Wraps focus between the address field and the back button.
+    fn setup_focus_wrap(&mut self) {
+        let mut first = self.address_edit.clone().upcast::<Control>();
+        let mut last = self.back_button.clone().upcast::<Control>();
+
+        let to_last = first.get_path_to(&last);
+        let to_first = last.get_path_to(&first);
+
+        first.set_focus_neighbor(Side::TOP, &to_last);
+        last.set_focus_neighbor(Side::BOTTOM, &to_first);
+    }
+}
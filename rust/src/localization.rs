@@ -0,0 +1,664 @@
+use core::fmt;
+
+use godot::{
+    classes::{Translation, TranslationServer},
+    prelude::*,
+};
+
+use crate::game_mode::GameMode;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    English,
+    PortugueseBrazil,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::PortugueseBrazil];
+
+    /// Name shown in the language selector, always in the language itself.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::PortugueseBrazil => "Português (Brasil)",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.to_string() == code)
+    }
+
+    /// Locale currently active in the `TranslationServer`, matched by language when the
+    /// exact region is not shipped (e.g. `pt_PT` falls back to `pt_BR`).
+    pub fn current() -> Self {
+        let code = TranslationServer::singleton().get_locale().to_string();
+
+        Self::from_code(&code)
+            .or_else(|| {
+                let language = code.split('_').next().unwrap_or_default();
+                Self::ALL
+                    .into_iter()
+                    .find(|locale| locale.to_string().split('_').next() == Some(language))
+            })
+            .unwrap_or(Locale::English)
+    }
+
+    pub fn apply(self) {
+        TranslationServer::singleton().set_locale(&self.to_string());
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Locale::English => write!(f, "en"),
+            Locale::PortugueseBrazil => write!(f, "pt_BR"),
+        }
+    }
+}
+
+/// Declares `TextKey` and `TextKey::ALL` from one list, so no key can be left out of `ALL`
+/// and go unregistered.
+macro_rules! text_keys {
+    ($($key:ident,)*) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum TextKey {
+            $($key,)*
+        }
+
+        impl TextKey {
+            pub const ALL: [TextKey; [$(stringify!($key)),*].len()] = [$(TextKey::$key,)*];
+        }
+    };
+}
+
+text_keys! {
+    Go,
+    GameOver,
+    Title,
+    Start,
+    Paused,
+    SummaryRun,
+    SummaryBest,
+    SummaryTime,
+    SummaryScore,
+    SummarySpawned,
+    SummaryDodged,
+    SummaryGrazes,
+    SummaryKilledBy,
+    SummaryDeathPosition,
+    NewRecord,
+    EnemyFlying,
+    EnemySwimming,
+    EnemyWalking,
+    MenuPlay,
+    MenuSettings,
+    MenuHighScores,
+    MenuQuit,
+    MenuBack,
+    MainMenu,
+    ModeEndless,
+    SettingsMasterVolume,
+    SettingsMusicVolume,
+    SettingsSfxVolume,
+    SettingsFullscreen,
+    SettingsWindowScale,
+    SettingsVsync,
+    SettingsReducedMotion,
+    SettingsLanguage,
+    ActionMoveLeft,
+    ActionMoveRight,
+    ActionMoveUp,
+    ActionMoveDown,
+    ActionStart,
+    ActionPause,
+    ActionDash,
+    BindingPadButton,
+    BindingPadAxis,
+    SettingsControls,
+    ControlsPrompt,
+    ControlsConflict,
+    ControlsReset,
+    ControlsCancel,
+    ModeCoop,
+    PlayerShort,
+    PlayerAlive,
+    PlayerReviving,
+    ModeVersus,
+    PlayerOut,
+    VersusRound,
+    VersusWins,
+    VersusRoundWinner,
+    VersusMatchWinner,
+    VersusDraw,
+    ModeNetwork,
+    LobbyTitle,
+    LobbyAddress,
+    LobbyHost,
+    LobbyJoin,
+    LobbyStart,
+    LobbyPlayers,
+    LobbyHostTag,
+    LobbyYouTag,
+    LobbyHosting,
+    LobbyConnecting,
+    LobbyWaiting,
+    LobbyFailed,
+    LobbyDisconnected,
+    GhostBest,
+    ModeDaily,
+    MenuDaily,
+    DailyTitle,
+    DailyPractice,
+    DailySpeed,
+    DailyToday,
+    DailyBest,
+    CalendarWeekdays,
+    ModeTimeAttack,
+    ModeZen,
+    ModeSprint,
+    TimeAttackInfo,
+    SprintInfo,
+    ZenInfo,
+    TimeUp,
+    SprintFinished,
+    SummaryMode,
+    BossWarning,
+    BossBonus,
+    ErrorSceneTree,
+    ErrorRestartTimer,
+    ErrorLoadPlayer,
+    ErrorInstantiatePlayer,
+    ErrorLoadHud,
+    ErrorInstantiateHud,
+    ErrorInstantiateEnemy,
+    ErrorSpriteFrames,
+    ErrorSaveStats,
+    ErrorLoadFont,
+    ErrorSaveSettings,
+    ErrorLoadAudio,
+    ErrorSaveBindings,
+    ErrorSaveGhost,
+    ErrorSaveDaily,
+    ErrorWaveRead,
+    ErrorWaveEnd,
+    ErrorWaveUnexpected,
+    ErrorWaveUnknownField,
+    ErrorWaveMissingField,
+    ErrorWaveType,
+    ErrorWaveRange,
+    ErrorWaveName,
+}
+
+impl TextKey {
+    /// Display name key for an enemy archetype (its animation name).
+    pub fn for_archetype(archetype: &str) -> Option<TextKey> {
+        match archetype {
+            "flying" => Some(TextKey::EnemyFlying),
+            "swimming" => Some(TextKey::EnemySwimming),
+            "walking" => Some(TextKey::EnemyWalking),
+            _ => None,
+        }
+    }
+
+    /// Display name key for a game mode.
+    pub fn for_mode(mode: GameMode) -> TextKey {
+        match mode {
+            GameMode::Endless => TextKey::ModeEndless,
+            GameMode::Coop => TextKey::ModeCoop,
+            GameMode::Versus => TextKey::ModeVersus,
+            GameMode::Network => TextKey::ModeNetwork,
+            GameMode::Daily => TextKey::ModeDaily,
+            GameMode::TimeAttack => TextKey::ModeTimeAttack,
+            GameMode::Zen => TextKey::ModeZen,
+            GameMode::Sprint => TextKey::ModeSprint,
+        }
+    }
+
+    /// Source text of the key for a given locale.
+    ///
+    /// Each locale has its own exhaustive `match`, so adding a key without translating it
+    /// into every shipped locale fails to compile.
+    pub fn text(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::English => match self {
+                TextKey::Go => "GO!",
+                TextKey::GameOver => "Game Over!",
+                TextKey::Title => "Dodge the creeps and survive!",
+                TextKey::Start => "start",
+                TextKey::Paused => "Paused",
+                TextKey::SummaryRun => "This run",
+                TextKey::SummaryBest => "Best",
+                TextKey::SummaryTime => "Time",
+                TextKey::SummaryScore => "Score",
+                TextKey::SummarySpawned => "Enemies",
+                TextKey::SummaryDodged => "Dodged",
+                TextKey::SummaryGrazes => "Grazes",
+                TextKey::SummaryKilledBy => "Killed by",
+                TextKey::SummaryDeathPosition => "Death position",
+                TextKey::NewRecord => "New record!",
+                TextKey::EnemyFlying => "Flyer",
+                TextKey::EnemySwimming => "Swimmer",
+                TextKey::EnemyWalking => "Walker",
+                TextKey::MenuPlay => "play",
+                TextKey::MenuSettings => "settings",
+                TextKey::MenuHighScores => "high scores",
+                TextKey::MenuQuit => "quit",
+                TextKey::MenuBack => "back",
+                TextKey::MainMenu => "menu",
+                TextKey::ModeEndless => "Endless",
+                TextKey::SettingsMasterVolume => "Master volume",
+                TextKey::SettingsMusicVolume => "Music volume",
+                TextKey::SettingsSfxVolume => "Effects volume",
+                TextKey::SettingsFullscreen => "Fullscreen",
+                TextKey::SettingsWindowScale => "Window scale",
+                TextKey::SettingsVsync => "VSync",
+                TextKey::SettingsReducedMotion => "Reduced motion",
+                TextKey::SettingsLanguage => "Language",
+                TextKey::ActionMoveLeft => "Move left",
+                TextKey::ActionMoveRight => "Move right",
+                TextKey::ActionMoveUp => "Move up",
+                TextKey::ActionMoveDown => "Move down",
+                TextKey::ActionStart => "Start",
+                TextKey::ActionPause => "Pause",
+                TextKey::ActionDash => "Dash",
+                TextKey::BindingPadButton => "Pad",
+                TextKey::BindingPadAxis => "Axis",
+                TextKey::SettingsControls => "Controls",
+                TextKey::ControlsPrompt => {
+                    "Press a key or button to bind. Escape or Back cancels; press it twice to bind it."
+                }
+                TextKey::ControlsConflict => "Also removed from",
+                TextKey::ControlsReset => "Reset to defaults",
+                TextKey::ControlsCancel => "Cancel",
+                TextKey::ModeCoop => "Co-op",
+                TextKey::PlayerShort => "P",
+                TextKey::PlayerAlive => "Alive",
+                TextKey::PlayerReviving => "Back in",
+                TextKey::ModeVersus => "Versus",
+                TextKey::PlayerOut => "Out",
+                TextKey::VersusRound => "Round",
+                TextKey::VersusWins => "Wins",
+                TextKey::VersusRoundWinner => "wins the round",
+                TextKey::VersusMatchWinner => "wins the match",
+                TextKey::VersusDraw => "Draw",
+                TextKey::ModeNetwork => "LAN",
+                TextKey::LobbyTitle => "LAN game",
+                TextKey::LobbyAddress => "Host address",
+                TextKey::LobbyHost => "host",
+                TextKey::LobbyJoin => "join",
+                TextKey::LobbyStart => "start match",
+                TextKey::LobbyPlayers => "Players:",
+                TextKey::LobbyHostTag => "(host)",
+                TextKey::LobbyYouTag => "(you)",
+                TextKey::LobbyHosting => "Hosting on port",
+                TextKey::LobbyConnecting => "Connecting...",
+                TextKey::LobbyWaiting => "Connected, waiting for the host to start",
+                TextKey::LobbyFailed => "Could not connect",
+                TextKey::LobbyDisconnected => "Disconnected from the host",
+                TextKey::GhostBest => "vs best",
+                TextKey::ModeDaily => "Daily",
+                TextKey::MenuDaily => "daily results",
+                TextKey::DailyTitle => "Daily challenge",
+                TextKey::DailyPractice => "practice, today is already scored",
+                TextKey::DailySpeed => "speed",
+                TextKey::DailyToday => "Today",
+                TextKey::DailyBest => "Best day",
+                TextKey::CalendarWeekdays => "S,M,T,W,T,F,S",
+                TextKey::ModeTimeAttack => "Time Attack",
+                TextKey::ModeZen => "Zen",
+                TextKey::ModeSprint => "Sprint",
+                TextKey::TimeAttackInfo => "Survive, only grazes score",
+                TextKey::SprintInfo => "Goal:",
+                TextKey::ZenInfo => "Hits:",
+                TextKey::TimeUp => "Time's up!",
+                TextKey::SprintFinished => "Finish!",
+                TextKey::SummaryMode => "Mode",
+                TextKey::BossWarning => "Warning! A boss is approaching!",
+                TextKey::BossBonus => "Boss survived!",
+                TextKey::ErrorSceneTree => "Failed to get the scene tree!",
+                TextKey::ErrorRestartTimer => "Failed to create the restart timer!",
+                TextKey::ErrorLoadPlayer => {
+                    "Failed to load player.tscn. Check that the scene exists at 'res://player.tscn'"
+                }
+                TextKey::ErrorInstantiatePlayer => "Failed to instantiate the player",
+                TextKey::ErrorLoadHud => {
+                    "Failed to load HUD.tscn. Check that the scene exists at 'res://HUD.tscn'"
+                }
+                TextKey::ErrorInstantiateHud => "Failed to instantiate the HUD",
+                TextKey::ErrorInstantiateEnemy => "Failed to instantiate the enemy in the scene",
+                TextKey::ErrorSpriteFrames => {
+                    "Failed to get the AnimatedSprite2D frames! Could not access the 'sprite_frames' property"
+                }
+                TextKey::ErrorSaveStats => "Could not save the personal bests to user://stats.cfg",
+                TextKey::ErrorLoadFont => {
+                    "Could not load the HUD font, falling back to the default font"
+                }
+                TextKey::ErrorSaveSettings => "Could not save the settings to user://settings.cfg",
+                TextKey::ErrorLoadAudio => "Could not load the audio stream",
+                TextKey::ErrorSaveBindings => "Could not save the controls to user://bindings.cfg",
+                TextKey::ErrorSaveGhost => "Could not save the ghost run to user://ghost.cfg",
+                TextKey::ErrorSaveDaily => "Could not save the daily results to user://daily.cfg",
+                TextKey::ErrorWaveRead => "Could not read the wave file",
+                TextKey::ErrorWaveEnd => "Unexpected end of file",
+                TextKey::ErrorWaveUnexpected => "Unexpected character",
+                TextKey::ErrorWaveUnknownField => "Unknown field",
+                TextKey::ErrorWaveMissingField => "Missing field",
+                TextKey::ErrorWaveType => "Wrong type for",
+                TextKey::ErrorWaveRange => "Out of range:",
+                TextKey::ErrorWaveName => "Unknown value for",
+            },
+            Locale::PortugueseBrazil => match self {
+                TextKey::Go => "JÁ!",
+                TextKey::GameOver => "Fim de Jogo!",
+                TextKey::Title => "Desvie e sobreviva aos monstros!",
+                TextKey::Start => "iniciar",
+                TextKey::Paused => "Pausado",
+                TextKey::SummaryRun => "Esta partida",
+                TextKey::SummaryBest => "Recorde",
+                TextKey::SummaryTime => "Tempo",
+                TextKey::SummaryScore => "Pontos",
+                TextKey::SummarySpawned => "Inimigos",
+                TextKey::SummaryDodged => "Desviados",
+                TextKey::SummaryGrazes => "Raspões",
+                TextKey::SummaryKilledBy => "Derrotado por",
+                TextKey::SummaryDeathPosition => "Local da derrota",
+                TextKey::NewRecord => "Novo recorde!",
+                TextKey::EnemyFlying => "Voador",
+                TextKey::EnemySwimming => "Nadador",
+                TextKey::EnemyWalking => "Andarilho",
+                TextKey::MenuPlay => "jogar",
+                TextKey::MenuSettings => "configurações",
+                TextKey::MenuHighScores => "recordes",
+                TextKey::MenuQuit => "sair",
+                TextKey::MenuBack => "voltar",
+                TextKey::MainMenu => "menu",
+                TextKey::ModeEndless => "Infinito",
+                TextKey::SettingsMasterVolume => "Volume geral",
+                TextKey::SettingsMusicVolume => "Volume da música",
+                TextKey::SettingsSfxVolume => "Volume dos efeitos",
+                TextKey::SettingsFullscreen => "Tela cheia",
+                TextKey::SettingsWindowScale => "Escala da janela",
+                TextKey::SettingsVsync => "VSync",
+                TextKey::SettingsReducedMotion => "Movimento reduzido",
+                TextKey::SettingsLanguage => "Idioma",
+                TextKey::ActionMoveLeft => "Mover para a esquerda",
+                TextKey::ActionMoveRight => "Mover para a direita",
+                TextKey::ActionMoveUp => "Mover para cima",
+                TextKey::ActionMoveDown => "Mover para baixo",
+                TextKey::ActionStart => "Iniciar",
+                TextKey::ActionPause => "Pausar",
+                TextKey::ActionDash => "Investida",
+                TextKey::BindingPadButton => "Controle",
+                TextKey::BindingPadAxis => "Eixo",
+                TextKey::SettingsControls => "Controles",
+                TextKey::ControlsPrompt => {
+                    "Pressione uma tecla ou botão para associar. Esc ou Back cancela; pressione duas vezes para associá-lo."
+                }
+                TextKey::ControlsConflict => "Também removido de",
+                TextKey::ControlsReset => "Restaurar padrões",
+                TextKey::ControlsCancel => "Cancelar",
+                TextKey::ModeCoop => "Cooperativo",
+                TextKey::PlayerShort => "J",
+                TextKey::PlayerAlive => "Vivo",
+                TextKey::PlayerReviving => "Volta em",
+                TextKey::ModeVersus => "Versus",
+                TextKey::PlayerOut => "Fora",
+                TextKey::VersusRound => "Rodada",
+                TextKey::VersusWins => "Vitórias",
+                TextKey::VersusRoundWinner => "vence a rodada",
+                TextKey::VersusMatchWinner => "vence a partida",
+                TextKey::VersusDraw => "Empate",
+                TextKey::ModeNetwork => "LAN",
+                TextKey::LobbyTitle => "Jogo em rede local",
+                TextKey::LobbyAddress => "Endereço do anfitrião",
+                TextKey::LobbyHost => "hospedar",
+                TextKey::LobbyJoin => "entrar",
+                TextKey::LobbyStart => "iniciar partida",
+                TextKey::LobbyPlayers => "Jogadores:",
+                TextKey::LobbyHostTag => "(anfitrião)",
+                TextKey::LobbyYouTag => "(você)",
+                TextKey::LobbyHosting => "Hospedando na porta",
+                TextKey::LobbyConnecting => "Conectando...",
+                TextKey::LobbyWaiting => "Conectado, aguardando o anfitrião iniciar",
+                TextKey::LobbyFailed => "Não foi possível conectar",
+                TextKey::LobbyDisconnected => "Desconectado do anfitrião",
+                TextKey::GhostBest => "vs recorde",
+                TextKey::ModeDaily => "Diário",
+                TextKey::MenuDaily => "resultados diários",
+                TextKey::DailyTitle => "Desafio diário",
+                TextKey::DailyPractice => "treino, o dia de hoje já foi pontuado",
+                TextKey::DailySpeed => "velocidade",
+                TextKey::DailyToday => "Hoje",
+                TextKey::DailyBest => "Melhor dia",
+                TextKey::CalendarWeekdays => "D,S,T,Q,Q,S,S",
+                TextKey::ModeTimeAttack => "Contra o Tempo",
+                TextKey::ModeZen => "Zen",
+                TextKey::ModeSprint => "Corrida",
+                TextKey::TimeAttackInfo => "Sobreviva, só raspões pontuam",
+                TextKey::SprintInfo => "Meta:",
+                TextKey::ZenInfo => "Golpes:",
+                TextKey::TimeUp => "Tempo esgotado!",
+                TextKey::SprintFinished => "Chegada!",
+                TextKey::SummaryMode => "Modo",
+                TextKey::BossWarning => "Atenção! Um chefe se aproxima!",
+                TextKey::BossBonus => "Chefe superado!",
+                TextKey::ErrorSceneTree => "Erro ao buscar a cena!",
+                TextKey::ErrorRestartTimer => "Erro ao criar o timer de reinício!",
+                TextKey::ErrorLoadPlayer => {
+                    "Falha ao carregar player.tscn. Verifique se a cena se encontra no caminho: 'res://player.tscn'"
+                }
+                TextKey::ErrorInstantiatePlayer => "Falha ao instanciar player",
+                TextKey::ErrorLoadHud => {
+                    "Falha ao carregar HUD.tscn. Verifique se a cena se encontra no caminho: 'res://HUD.tscn'"
+                }
+                TextKey::ErrorInstantiateHud => "Falha ao instanciar o HUD",
+                TextKey::ErrorInstantiateEnemy => "Erro ao instanciar inimigo na cena",
+                TextKey::ErrorSpriteFrames => {
+                    "Erro ao buscar os frames do AnimatedSprite2D! Não foi possível acessar a propriedade 'sprite_frames'"
+                }
+                TextKey::ErrorSaveStats => {
+                    "Não foi possível salvar os recordes em user://stats.cfg"
+                }
+                TextKey::ErrorLoadFont => {
+                    "Não foi possível carregar a fonte do HUD, usando a fonte padrão"
+                }
+                TextKey::ErrorSaveSettings => {
+                    "Não foi possível salvar as configurações em user://settings.cfg"
+                }
+                TextKey::ErrorLoadAudio => "Não foi possível carregar o áudio",
+                TextKey::ErrorSaveBindings => {
+                    "Não foi possível salvar os controles em user://bindings.cfg"
+                }
+                TextKey::ErrorSaveGhost => {
+                    "Não foi possível salvar a corrida fantasma em user://ghost.cfg"
+                }
+                TextKey::ErrorSaveDaily => {
+                    "Não foi possível salvar os resultados diários em user://daily.cfg"
+                }
+                TextKey::ErrorWaveRead => "Não foi possível ler o arquivo de ondas",
+                TextKey::ErrorWaveEnd => "Fim de arquivo inesperado",
+                TextKey::ErrorWaveUnexpected => "Caractere inesperado",
+                TextKey::ErrorWaveUnknownField => "Campo desconhecido",
+                TextKey::ErrorWaveMissingField => "Campo ausente",
+                TextKey::ErrorWaveType => "Tipo errado para",
+                TextKey::ErrorWaveRange => "Fora do intervalo:",
+                TextKey::ErrorWaveName => "Valor desconhecido para",
+            },
+        }
+    }
+}
+
+impl fmt::Display for TextKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextKey::Go => write!(f, "HUD_GO"),
+            TextKey::GameOver => write!(f, "HUD_GAME_OVER"),
+            TextKey::Title => write!(f, "HUD_TITLE"),
+            TextKey::Start => write!(f, "HUD_START"),
+            TextKey::Paused => write!(f, "HUD_PAUSED"),
+            TextKey::SummaryRun => write!(f, "SUMMARY_RUN"),
+            TextKey::SummaryBest => write!(f, "SUMMARY_BEST"),
+            TextKey::SummaryTime => write!(f, "SUMMARY_TIME"),
+            TextKey::SummaryScore => write!(f, "SUMMARY_SCORE"),
+            TextKey::SummarySpawned => write!(f, "SUMMARY_SPAWNED"),
+            TextKey::SummaryDodged => write!(f, "SUMMARY_DODGED"),
+            TextKey::SummaryGrazes => write!(f, "SUMMARY_GRAZES"),
+            TextKey::SummaryKilledBy => write!(f, "SUMMARY_KILLED_BY"),
+            TextKey::SummaryDeathPosition => write!(f, "SUMMARY_DEATH_POSITION"),
+            TextKey::NewRecord => write!(f, "SUMMARY_NEW_RECORD"),
+            TextKey::EnemyFlying => write!(f, "ENEMY_FLYING"),
+            TextKey::EnemySwimming => write!(f, "ENEMY_SWIMMING"),
+            TextKey::EnemyWalking => write!(f, "ENEMY_WALKING"),
+            TextKey::MenuPlay => write!(f, "MENU_PLAY"),
+            TextKey::MenuSettings => write!(f, "MENU_SETTINGS"),
+            TextKey::MenuHighScores => write!(f, "MENU_HIGH_SCORES"),
+            TextKey::MenuQuit => write!(f, "MENU_QUIT"),
+            TextKey::MenuBack => write!(f, "MENU_BACK"),
+            TextKey::MainMenu => write!(f, "HUD_MAIN_MENU"),
+            TextKey::ModeEndless => write!(f, "MODE_ENDLESS"),
+            TextKey::SettingsMasterVolume => write!(f, "SETTINGS_MASTER_VOLUME"),
+            TextKey::SettingsMusicVolume => write!(f, "SETTINGS_MUSIC_VOLUME"),
+            TextKey::SettingsSfxVolume => write!(f, "SETTINGS_SFX_VOLUME"),
+            TextKey::SettingsFullscreen => write!(f, "SETTINGS_FULLSCREEN"),
+            TextKey::SettingsWindowScale => write!(f, "SETTINGS_WINDOW_SCALE"),
+            TextKey::SettingsVsync => write!(f, "SETTINGS_VSYNC"),
+            TextKey::SettingsReducedMotion => write!(f, "SETTINGS_REDUCED_MOTION"),
+            TextKey::SettingsLanguage => write!(f, "SETTINGS_LANGUAGE"),
+            TextKey::ActionMoveLeft => write!(f, "ACTION_MOVE_LEFT"),
+            TextKey::ActionMoveRight => write!(f, "ACTION_MOVE_RIGHT"),
+            TextKey::ActionMoveUp => write!(f, "ACTION_MOVE_UP"),
+            TextKey::ActionMoveDown => write!(f, "ACTION_MOVE_DOWN"),
+            TextKey::ActionStart => write!(f, "ACTION_START"),
+            TextKey::ActionPause => write!(f, "ACTION_PAUSE"),
+            TextKey::ActionDash => write!(f, "ACTION_DASH"),
+            TextKey::BindingPadButton => write!(f, "BINDING_PAD_BUTTON"),
+            TextKey::BindingPadAxis => write!(f, "BINDING_PAD_AXIS"),
+            TextKey::SettingsControls => write!(f, "SETTINGS_CONTROLS"),
+            TextKey::ControlsPrompt => write!(f, "CONTROLS_PROMPT"),
+            TextKey::ControlsConflict => write!(f, "CONTROLS_CONFLICT"),
+            TextKey::ControlsReset => write!(f, "CONTROLS_RESET"),
+            TextKey::ControlsCancel => write!(f, "CONTROLS_CANCEL"),
+            TextKey::ModeCoop => write!(f, "MODE_COOP"),
+            TextKey::PlayerShort => write!(f, "PLAYER_SHORT"),
+            TextKey::PlayerAlive => write!(f, "PLAYER_ALIVE"),
+            TextKey::PlayerReviving => write!(f, "PLAYER_REVIVING"),
+            TextKey::ModeVersus => write!(f, "MODE_VERSUS"),
+            TextKey::PlayerOut => write!(f, "PLAYER_OUT"),
+            TextKey::VersusRound => write!(f, "VERSUS_ROUND"),
+            TextKey::VersusWins => write!(f, "VERSUS_WINS"),
+            TextKey::VersusRoundWinner => write!(f, "VERSUS_ROUND_WINNER"),
+            TextKey::VersusMatchWinner => write!(f, "VERSUS_MATCH_WINNER"),
+            TextKey::VersusDraw => write!(f, "VERSUS_DRAW"),
+            TextKey::ModeNetwork => write!(f, "MODE_NETWORK"),
+            TextKey::LobbyTitle => write!(f, "LOBBY_TITLE"),
+            TextKey::LobbyAddress => write!(f, "LOBBY_ADDRESS"),
+            TextKey::LobbyHost => write!(f, "LOBBY_HOST"),
+            TextKey::LobbyJoin => write!(f, "LOBBY_JOIN"),
+            TextKey::LobbyStart => write!(f, "LOBBY_START"),
+            TextKey::LobbyPlayers => write!(f, "LOBBY_PLAYERS"),
+            TextKey::LobbyHostTag => write!(f, "LOBBY_HOST_TAG"),
+            TextKey::LobbyYouTag => write!(f, "LOBBY_YOU_TAG"),
+            TextKey::LobbyHosting => write!(f, "LOBBY_HOSTING"),
+            TextKey::LobbyConnecting => write!(f, "LOBBY_CONNECTING"),
+            TextKey::LobbyWaiting => write!(f, "LOBBY_WAITING"),
+            TextKey::LobbyFailed => write!(f, "LOBBY_FAILED"),
+            TextKey::LobbyDisconnected => write!(f, "LOBBY_DISCONNECTED"),
+            TextKey::GhostBest => write!(f, "GHOST_BEST"),
+            TextKey::ModeDaily => write!(f, "MODE_DAILY"),
+            TextKey::MenuDaily => write!(f, "MENU_DAILY"),
+            TextKey::DailyTitle => write!(f, "DAILY_TITLE"),
+            TextKey::DailyPractice => write!(f, "DAILY_PRACTICE"),
+            TextKey::DailySpeed => write!(f, "DAILY_SPEED"),
+            TextKey::DailyToday => write!(f, "DAILY_TODAY"),
+            TextKey::DailyBest => write!(f, "DAILY_BEST"),
+            TextKey::CalendarWeekdays => write!(f, "CALENDAR_WEEKDAYS"),
+            TextKey::ModeTimeAttack => write!(f, "MODE_TIME_ATTACK"),
+            TextKey::ModeZen => write!(f, "MODE_ZEN"),
+            TextKey::ModeSprint => write!(f, "MODE_SPRINT"),
+            TextKey::TimeAttackInfo => write!(f, "TIME_ATTACK_INFO"),
+            TextKey::SprintInfo => write!(f, "SPRINT_INFO"),
+            TextKey::ZenInfo => write!(f, "ZEN_INFO"),
+            TextKey::TimeUp => write!(f, "HUD_TIME_UP"),
+            TextKey::SprintFinished => write!(f, "HUD_SPRINT_FINISHED"),
+            TextKey::SummaryMode => write!(f, "SUMMARY_MODE"),
+            TextKey::BossWarning => write!(f, "HUD_BOSS_WARNING"),
+            TextKey::BossBonus => write!(f, "HUD_BOSS_BONUS"),
+            TextKey::ErrorSceneTree => write!(f, "ERROR_SCENE_TREE"),
+            TextKey::ErrorRestartTimer => write!(f, "ERROR_RESTART_TIMER"),
+            TextKey::ErrorLoadPlayer => write!(f, "ERROR_LOAD_PLAYER"),
+            TextKey::ErrorInstantiatePlayer => write!(f, "ERROR_INSTANTIATE_PLAYER"),
+            TextKey::ErrorLoadHud => write!(f, "ERROR_LOAD_HUD"),
+            TextKey::ErrorInstantiateHud => write!(f, "ERROR_INSTANTIATE_HUD"),
+            TextKey::ErrorInstantiateEnemy => write!(f, "ERROR_INSTANTIATE_ENEMY"),
+            TextKey::ErrorSpriteFrames => write!(f, "ERROR_SPRITE_FRAMES"),
+            TextKey::ErrorSaveStats => write!(f, "ERROR_SAVE_STATS"),
+            TextKey::ErrorLoadFont => write!(f, "ERROR_LOAD_FONT"),
+            TextKey::ErrorSaveSettings => write!(f, "ERROR_SAVE_SETTINGS"),
+            TextKey::ErrorLoadAudio => write!(f, "ERROR_LOAD_AUDIO"),
+            TextKey::ErrorSaveBindings => write!(f, "ERROR_SAVE_BINDINGS"),
+            TextKey::ErrorSaveGhost => write!(f, "ERROR_SAVE_GHOST"),
+            TextKey::ErrorSaveDaily => write!(f, "ERROR_SAVE_DAILY"),
+            TextKey::ErrorWaveRead => write!(f, "ERROR_WAVE_READ"),
+            TextKey::ErrorWaveEnd => write!(f, "ERROR_WAVE_END"),
+            TextKey::ErrorWaveUnexpected => write!(f, "ERROR_WAVE_UNEXPECTED"),
+            TextKey::ErrorWaveUnknownField => write!(f, "ERROR_WAVE_UNKNOWN_FIELD"),
+            TextKey::ErrorWaveMissingField => write!(f, "ERROR_WAVE_MISSING_FIELD"),
+            TextKey::ErrorWaveType => write!(f, "ERROR_WAVE_TYPE"),
+            TextKey::ErrorWaveRange => write!(f, "ERROR_WAVE_RANGE"),
+            TextKey::ErrorWaveName => write!(f, "ERROR_WAVE_NAME"),
+        }
+    }
+}
+
+/// Builds one `Translation` per shipped locale and hands them to the `TranslationServer`.
+pub fn register_translations() {
+    let mut server = TranslationServer::singleton();
+
+    for locale in Locale::ALL {
+        let mut translation = Translation::new_gd();
+        translation.set_locale(&locale.to_string());
+
+        for key in TextKey::ALL {
+            translation.add_message(&key.to_string(), key.text(locale));
+        }
+
+        server.add_translation(&translation);
+    }
+}
+
+/// Resolves a key through the `TranslationServer` for the active locale.
+pub fn tr(key: TextKey) -> String {
+    TranslationServer::singleton()
+        .translate(&key.to_string())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn every_key_has_text_in_every_locale() {
+        for locale in Locale::ALL {
+            for key in TextKey::ALL {
+                let text = key.text(locale);
+                assert!(!text.trim().is_empty(), "{key:?} is empty in {locale}");
+            }
+        }
+    }
+
+    #[test]
+    fn every_key_has_a_distinct_message_id() {
+        let ids = TextKey::ALL
+            .iter()
+            .map(|key| key.to_string())
+            .collect::<HashSet<_>>();
+
+        assert_eq!(ids.len(), TextKey::ALL.len());
+    }
+}
@@ -0,0 +1,460 @@
+use core::fmt;
+
+use godot::{
+    builtin::Side,
+    classes::{
+        Button, CanvasLayer, Control, Engine, GridContainer, ICanvasLayer, InputEvent, Label,
+        OptionButton, PanelContainer, VBoxContainer,
+        control::{LayoutPreset, SizeFlags},
+        node::AutoTranslateMode,
+        text_server::AutowrapMode,
+    },
+    global::HorizontalAlignment,
+    prelude::*,
+};
+
+use crate::{
+    game_mode::GameMode,
+    game_phase::GamePhase,
+    hud::format_run_time,
+    hud_theme::{LARGE_LABEL, SMALL_LABEL, hud_theme, place},
+    localization::TextKey,
+    run_stats::HighScores,
+};
+
+enum MainMenuChild {
+    TitleLabel,
+    Buttons,
+    PlayButton,
+    ModeButton,
+    SettingsButton,
+    HighScoresButton,
+    DailyButton,
+    QuitButton,
+    HighScoresPanel,
+    Content,
+    HighScoresGrid,
+    BackButton,
+}
+
+impl fmt::Display for MainMenuChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MainMenuChild::TitleLabel => write!(f, "TitleLabel"),
+            MainMenuChild::Buttons => write!(f, "Buttons"),
+            MainMenuChild::PlayButton => write!(f, "Buttons/PlayButton"),
+            MainMenuChild::ModeButton => write!(f, "Buttons/ModeButton"),
+            MainMenuChild::SettingsButton => write!(f, "Buttons/SettingsButton"),
+            MainMenuChild::HighScoresButton => write!(f, "Buttons/HighScoresButton"),
+            MainMenuChild::DailyButton => write!(f, "Buttons/DailyButton"),
+            MainMenuChild::QuitButton => write!(f, "Buttons/QuitButton"),
+            MainMenuChild::HighScoresPanel => write!(f, "HighScoresPanel"),
+            MainMenuChild::Content => write!(f, "HighScoresPanel/Content"),
+            MainMenuChild::HighScoresGrid => write!(f, "HighScoresPanel/Content/HighScoresGrid"),
+            MainMenuChild::BackButton => write!(f, "HighScoresPanel/Content/BackButton"),
+        }
+    }
+}
+
+impl MainMenuChild {
+    /// Name of the node itself, without the path to it.
+    fn name(&self) -> String {
+        let path = self.to_string();
+        path.rsplit('/').next().unwrap_or_default().to_string()
+    }
+}
+
+/// Title screen shown by `MainScene` during `GamePhase::Title`: play with the selected
+/// `GameMode`, open the settings, browse the personal bests and daily results, or quit.
+/// Every entry is reachable with the `ui_*` actions, so keyboards and gamepads work without
+/// a mouse.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer, tool)]
+pub struct MainMenu {
+    #[base]
+    base: Base<CanvasLayer>,
+    play_button: OnReady<Gd<Button>>,
+    mode_button: OnReady<Gd<OptionButton>>,
+    settings_button: OnReady<Gd<Button>>,
+    high_scores_button: OnReady<Gd<Button>>,
+    daily_button: OnReady<Gd<Button>>,
+    quit_button: OnReady<Gd<Button>>,
+    buttons: OnReady<Gd<VBoxContainer>>,
+    high_scores_panel: OnReady<Gd<PanelContainer>>,
+    high_scores_grid: OnReady<Gd<GridContainer>>,
+    back_button: OnReady<Gd<Button>>,
+}
+
+#[godot_api]
+impl ICanvasLayer for MainMenu {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            play_button: OnReady::from_node(&MainMenuChild::PlayButton.to_string()),
+            mode_button: OnReady::from_node(&MainMenuChild::ModeButton.to_string()),
+            settings_button: OnReady::from_node(&MainMenuChild::SettingsButton.to_string()),
+            high_scores_button: OnReady::from_node(&MainMenuChild::HighScoresButton.to_string()),
+            daily_button: OnReady::from_node(&MainMenuChild::DailyButton.to_string()),
+            quit_button: OnReady::from_node(&MainMenuChild::QuitButton.to_string()),
+            buttons: OnReady::from_node(&MainMenuChild::Buttons.to_string()),
+            high_scores_panel: OnReady::from_node(&MainMenuChild::HighScoresPanel.to_string()),
+            high_scores_grid: OnReady::from_node(&MainMenuChild::HighScoresGrid.to_string()),
+            back_button: OnReady::from_node(&MainMenuChild::BackButton.to_string()),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        let mut theme = None;
+
+        self.base()
+            .try_get_node_as::<Label>(&MainMenuChild::TitleLabel.to_string())
+            .unwrap_or_else(|| {
+                let mut label = Label::new_alloc();
+                label.set_name(&MainMenuChild::TitleLabel.to_string());
+                label.set_theme(&*theme.get_or_insert_with(hud_theme));
+                label.set_theme_type_variation(LARGE_LABEL);
+                place(
+                    &mut label,
+                    LayoutPreset::TOP_WIDE,
+                    [16f32, 80f32, -16f32, 260f32],
+                );
+                label.set_text(&TextKey::Title.to_string());
+                label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+                label.set_autowrap_mode(AutowrapMode::WORD_SMART);
+                self.base_mut().add_child(&label);
+                label.set_owner(self.base().to_godot());
+                label
+            });
+
+        let mut buttons = self
+            .base()
+            .try_get_node_as::<VBoxContainer>(&MainMenuChild::Buttons.to_string())
+            .unwrap_or_else(|| {
+                let mut container = VBoxContainer::new_alloc();
+                container.set_name(&MainMenuChild::Buttons.to_string());
+                container.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut container,
+                    LayoutPreset::CENTER_BOTTOM,
+                    [-120f32, -400f32, 120f32, -80f32],
+                );
+                container.add_theme_constant_override("separation", 12);
+                self.base_mut().add_child(&container);
+                container.set_owner(self.base().to_godot());
+                container
+            });
+
+        let owner = self.base().clone().upcast::<Node>();
+
+        for (child, key) in [
+            (MainMenuChild::PlayButton, TextKey::MenuPlay),
+            (MainMenuChild::ModeButton, TextKey::ModeEndless),
+            (MainMenuChild::SettingsButton, TextKey::MenuSettings),
+            (MainMenuChild::HighScoresButton, TextKey::MenuHighScores),
+            (MainMenuChild::DailyButton, TextKey::MenuDaily),
+            (MainMenuChild::QuitButton, TextKey::MenuQuit),
+        ] {
+            if buttons.has_node(&child.name()) {
+                continue;
+            }
+
+            let mut button = match child {
+                MainMenuChild::ModeButton => OptionButton::new_alloc().upcast::<Button>(),
+                _ => Button::new_alloc(),
+            };
+            button.set_name(&child.name());
+            button.set_text(&key.to_string());
+            buttons.add_child(&button);
+            button.set_owner(&owner);
+        }
+
+        let mut panel = self
+            .base()
+            .try_get_node_as::<PanelContainer>(&MainMenuChild::HighScoresPanel.to_string())
+            .unwrap_or_else(|| {
+                let mut panel = PanelContainer::new_alloc();
+                panel.set_name(&MainMenuChild::HighScoresPanel.to_string());
+                panel.set_theme(&*theme.get_or_insert_with(hud_theme));
+                place(
+                    &mut panel,
+                    LayoutPreset::CENTER,
+                    [-300f32, -180f32, 300f32, 180f32],
+                );
+                panel.hide();
+                self.base_mut().add_child(&panel);
+                panel.set_owner(self.base().to_godot());
+                panel
+            });
+
+        let mut content = panel
+            .try_get_node_as::<VBoxContainer>(&MainMenuChild::Content.name())
+            .unwrap_or_else(|| {
+                let mut content = VBoxContainer::new_alloc();
+                content.set_name(&MainMenuChild::Content.name());
+                panel.add_child(&content);
+                content.set_owner(&owner);
+                content
+            });
+
+        if content
+            .try_get_node_as::<GridContainer>(&MainMenuChild::HighScoresGrid.name())
+            .is_none()
+        {
+            let mut grid = GridContainer::new_alloc();
+            grid.set_name(&MainMenuChild::HighScoresGrid.name());
+            grid.set_columns(5);
+            grid.set_v_size_flags(SizeFlags::EXPAND_FILL);
+            content.add_child(&grid);
+            grid.set_owner(&owner);
+        }
+
+        if content
+            .try_get_node_as::<Button>(&MainMenuChild::BackButton.name())
+            .is_none()
+        {
+            let mut button = Button::new_alloc();
+            button.set_name(&MainMenuChild::BackButton.name());
+            button.set_text(&TextKey::MenuBack.to_string());
+            content.add_child(&button);
+            button.set_owner(&owner);
+        }
+    }
+
+    fn ready(&mut self) {
+        self.play_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_play_pressed);
+
+        self.settings_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_settings_pressed);
+
+        self.high_scores_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::open_high_scores);
+
+        self.daily_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_daily_pressed);
+
+        self.quit_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_quit_pressed);
+
+        self.back_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::close_high_scores);
+
+        self.setup_focus_wrap();
+
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        // Filled at run time only, so the items aren't saved into the scene from the editor.
+        self.setup_mode_button();
+
+        if self.base().is_visible() {
+            self.play_button.grab_focus();
+        }
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        if !self.base().is_visible()
+            || !self.high_scores_panel.is_visible()
+            || !event.is_action_pressed("ui_cancel")
+        {
+            return;
+        }
+
+        self.close_high_scores();
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+    }
+}
+
+#[godot_api]
+impl MainMenu {
+    /// Emitted when the player presses Play, with the mode picked in the mode selector.
+    #[signal]
+    pub fn play_requested(mode: GameMode);
+
+    #[signal]
+    pub fn settings_requested();
+
+    /// The daily results button was pressed; the owner opens the `DailyCalendar`.
+    #[signal]
+    pub fn daily_requested();
+
+    /// Shows the menu and moves focus to Play.
+    #[func]
+    pub fn open(&mut self) {
+        self.close_high_scores();
+        self.base_mut().show();
+        self.play_button.grab_focus();
+    }
+
+    #[func]
+    pub fn close(&mut self) {
+        self.high_scores_panel.hide();
+        self.base_mut().hide();
+    }
+
+    /// The menu belongs to `GamePhase::Title` and hides in every other phase.
+    #[func]
+    pub fn on_phase_changed(&mut self, _from: GamePhase, to: GamePhase) {
+        if to == GamePhase::Title {
+            self.open();
+        } else {
+            self.close();
+        }
+    }
+
+    /// Mode currently picked in the mode selector.
+    #[func]
+    pub fn get_selected_mode(&self) -> GameMode {
+        let index = self.mode_button.get_selected();
+
+        if index < 0 {
+            return GameMode::default();
+        }
+
+        self.mode_button
+            .get_item_metadata(index)
+            .try_to::<GameMode>()
+            .unwrap_or_default()
+    }
+
+    /// Fills the high scores table with one row per mode that keeps personal bests.
+    pub fn set_high_scores(&mut self, high_scores: &HighScores) {
+        for mut child in self.high_scores_grid.get_children().iter_shared() {
+            self.high_scores_grid.remove_child(&child);
+            child.queue_free();
+        }
+
+        let header = [
+            TextKey::SummaryMode,
+            TextKey::SummaryTime,
+            TextKey::SummaryScore,
+            TextKey::SummaryDodged,
+            TextKey::SummaryGrazes,
+        ];
+
+        for key in header {
+            let mut label = Label::new_alloc();
+            label.set_theme_type_variation(SMALL_LABEL);
+            label.set_text(&key.to_string());
+            self.high_scores_grid.add_child(&label);
+        }
+
+        for mode in GameMode::ALL
+            .into_iter()
+            .filter(|mode| mode.has_high_scores())
+        {
+            let bests = high_scores.get(mode);
+
+            let mut name = Label::new_alloc();
+            name.set_theme_type_variation(SMALL_LABEL);
+            name.set_text(&TextKey::for_mode(mode).to_string());
+            self.high_scores_grid.add_child(&name);
+
+            // A sprint has no best time until one is cleared.
+            let time = if mode.fastest_time_wins() && bests.time <= 0f64 {
+                "-".to_string()
+            } else {
+                format_run_time(bests.time)
+            };
+
+            let values = [
+                time,
+                bests.score.to_string(),
+                bests.enemies_dodged.to_string(),
+                bests.grazes.to_string(),
+            ];
+
+            for value in values {
+                let mut value_label = Label::new_alloc();
+                value_label.set_theme_type_variation(SMALL_LABEL);
+                value_label.set_text(&value);
+                value_label.set_horizontal_alignment(HorizontalAlignment::RIGHT);
+                value_label.set_h_size_flags(SizeFlags::EXPAND_FILL);
+                value_label.set_auto_translate_mode(AutoTranslateMode::DISABLED);
+                self.high_scores_grid.add_child(&value_label);
+            }
+        }
+    }
+
+    #[func]
+    fn open_high_scores(&mut self) {
+        self.buttons.hide();
+        self.high_scores_panel.show();
+        self.back_button.grab_focus();
+    }
+
+    #[func]
+    fn close_high_scores(&mut self) {
+        if !self.high_scores_panel.is_visible() {
+            return;
+        }
+
+        self.high_scores_panel.hide();
+        self.buttons.show();
+        self.high_scores_button.grab_focus();
+    }
+
+    #[func]
+    fn on_play_pressed(&mut self) {
+        let mode = self.get_selected_mode();
+        self.signals().play_requested().emit(mode);
+    }
+
+    #[func]
+    fn on_settings_pressed(&mut self) {
+        self.signals().settings_requested().emit();
+    }
+
+    #[func]
+    fn on_daily_pressed(&mut self) {
+        self.signals().daily_requested().emit();
+    }
+
+    #[func]
+    fn on_quit_pressed(&mut self) {
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.quit();
+        }
+    }
+
+    fn setup_mode_button(&mut self) {
+        self.mode_button.clear();
+
+        for (index, mode) in GameMode::ALL.into_iter().enumerate() {
+            self.mode_button
+                .add_item(&TextKey::for_mode(mode).to_string());
+            self.mode_button
+                .set_item_metadata(index as i32, &mode.to_variant());
+        }
+
+        self.mode_button.select(0);
+    }
+
+    /// Wraps focus from the last menu entry back to the first and the other way round.
+    fn setup_focus_wrap(&mut self) {
+        let mut first = self.play_button.clone().upcast::<Control>();
+        let mut last = self.quit_button.clone().upcast::<Control>();
+
+        let to_last = first.get_path_to(&last);
+        let to_first = last.get_path_to(&first);
+
+        first.set_focus_neighbor(Side::TOP, &to_last);
+        last.set_focus_neighbor(Side::BOTTOM, &to_first);
+    }
+}
@@ -1,48 +1,96 @@
 use core::fmt;
-use std::f64::consts::PI;
+use std::f32::consts::PI;
 
 use godot::{
     classes::{
-        AudioStreamPlayer2D, ColorRect, Marker2D, Path2D, PathFollow2D, ResourceLoader, Timer,
+        ColorRect, Engine, InputEvent, Marker2D, Path2D, PathFollow2D, RandomNumberGenerator,
+        ResourceLoader, Timer, node::ProcessMode,
     },
-    global::{randf, randf_range, randi_range, randomize},
+    global::{randi, randomize},
     prelude::*,
 };
 
-use crate::{enemy::EnemyBase, hud::HUDBase, player::PlayerBase};
+use crate::{
+    audio::AudioManager,
+    boss::{BossBase, BossCue, BossEncounter, BossFight},
+    controls_panel::ControlsPanel,
+    daily::{DailyChallenge, DailyResult, DailyResults},
+    daily_calendar::DailyCalendar,
+    enemy::EnemyBase,
+    feedback::FeedbackBase,
+    game_mode::GameMode,
+    game_phase::GamePhase,
+    ghost::{Ghost, GhostRun},
+    hud::{HUDBase, ModeInfo},
+    input_bindings::{self, GameAction, InputProfile},
+    lobby::Lobby,
+    localization::{TextKey, tr},
+    main_menu::MainMenu,
+    network::{HOST_PEER, NetworkRole, NetworkSession},
+    player::{PLAYER_TINTS, PlayerBase, PlayerControl, PlayerStatus},
+    run_stats::{HighScores, PersonalBests, RunStats},
+    scoring_rules::ScoringRules,
+    settings_panel::SettingsPanel,
+    spawn_pattern::{PatternShot, SpawnPatterns},
+    versus,
+    wave::{SpawnEvent, WavePlayer, WaveScript},
+};
+
+/// Group every spawned enemy joins, so a run can be cleared when leaving it.
+const ENEMY_GROUP: &str = "enemies";
 
 enum MainSceneChild {
-    HUD,
+    Hud,
+    Menu,
+    Settings,
+    Controls,
+    Lobby,
+    Network,
+    Calendar,
     SpawnEnemy,
     PathEnemy,
     StarterPosition,
     EnemyTimer,
-    ScoreTimer,
     StarterTimer,
     Player,
+    Ghost,
+    Boss,
     Background,
-    Music,
-    SoundDeath,
+    Audio,
+    Feedback,
 }
 
 impl fmt::Display for MainSceneChild {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            MainSceneChild::HUD => write!(f, "HUD"),
+            MainSceneChild::Hud => write!(f, "HUD"),
+            MainSceneChild::Menu => write!(f, "Menu"),
+            MainSceneChild::Settings => write!(f, "Settings"),
+            MainSceneChild::Controls => write!(f, "Controls"),
+            MainSceneChild::Lobby => write!(f, "Lobby"),
+            MainSceneChild::Network => write!(f, "Network"),
+            MainSceneChild::Calendar => write!(f, "DailyCalendar"),
             MainSceneChild::SpawnEnemy => write!(f, "SpawnEnemy"),
             MainSceneChild::PathEnemy => write!(f, "PathEnemy"),
             MainSceneChild::StarterPosition => write!(f, "StarterPosition"),
             MainSceneChild::EnemyTimer => write!(f, "EnemyTimer"),
-            MainSceneChild::ScoreTimer => write!(f, "ScoreTimer"),
             MainSceneChild::StarterTimer => write!(f, "StarterTimer"),
             MainSceneChild::Player => write!(f, "Player"),
+            MainSceneChild::Ghost => write!(f, "Ghost"),
+            MainSceneChild::Boss => write!(f, "Boss"),
             MainSceneChild::Background => write!(f, "BG"),
-            MainSceneChild::Music => write!(f, "Music"),
-            MainSceneChild::SoundDeath => write!(f, "SoundDeath"),
+            MainSceneChild::Audio => write!(f, "Audio"),
+            MainSceneChild::Feedback => write!(f, "Feedback"),
         }
     }
 }
 
+/// A player taking part in the current run.
+struct PlayerSlot {
+    player: Gd<PlayerBase>,
+    state: PlayerStatus,
+}
+
 #[derive(GodotClass)]
 #[class(base=Node, tool)]
 pub struct MainScene {
@@ -50,18 +98,121 @@ pub struct MainScene {
     base: Base<Node>,
     #[export]
     enemy: Option<Gd<PackedScene>>,
+    #[export]
+    scoring_rules: Option<Gd<ScoringRules>>,
+    /// Patterns the spawner sends now and then instead of a single enemy. A default set is
+    /// used when none is given.
+    #[export]
+    spawn_patterns: Option<Gd<SpawnPatterns>>,
+    /// Bosses that pause the spawner at score milestones. A default set is used when none is
+    /// given.
+    #[export]
+    boss_encounter: Option<Gd<BossEncounter>>,
+    /// Seconds of 3-2-1 countdown before enemies start spawning.
+    #[export]
+    countdown_length: f64,
+    /// Shortest gap between spawns, reached after `spawn_ramp_time` seconds of a run. The
+    /// longest is the `EnemyTimer` wait time the scene starts with.
+    #[export]
+    min_spawn_interval: f64,
+    /// Seconds of play for the spawn rate to climb to its peak.
+    #[export]
+    spawn_ramp_time: f64,
+    /// Seconds survived between two milestone stingers. 0 disables them.
+    #[export]
+    milestone_interval: f64,
+    /// Seconds a downed player waits, while a partner survives, before coming back.
+    #[export]
+    revive_time: f64,
+    /// Players in a versus match, from 2 to 4.
+    #[export]
+    versus_players: i64,
+    /// Rounds of a versus match; winning more than half of them wins the match.
+    #[export]
+    versus_rounds: i64,
+    /// Seconds the standings stay on screen before the next versus round.
+    #[export]
+    round_break: f64,
+    /// `PlayerBase::push_strength` given to every player in versus.
+    #[export]
+    push_strength: f32,
+    /// Whether endless runs replay the seed of the best one, raced by its ghost.
+    #[export]
+    ghost_enabled: bool,
+    /// Waves a run spawns instead of random enemies, for as long as they last.
+    #[export]
+    wave_script: Option<Gd<WaveScript>>,
+    /// JSON file of waves read over `wave_script` when the scene is ready. Problems in it are
+    /// reported with their line, and leave `wave_script` as it was.
+    #[export(file = "*.json")]
+    wave_file: GString,
+    base_spawn_interval: f64,
+    next_milestone: f64,
     score: i64,
+    run_time: f64,
+    bonus: i64,
+    multiplier: f64,
+    phase: GamePhase,
+    mode: GameMode,
+    stats: RunStats,
+    high_scores: HighScores,
+    /// Every player of the run; the first one is the `Player` child.
+    players: Vec<PlayerSlot>,
+    /// Versus round being played, counted from 1.
+    round: i64,
+    /// Rounds won by each player in the current versus match.
+    wins: Vec<i64>,
+    /// Drives every random choice of a run's spawns, so a seed replays the same enemies.
+    rng: Gd<RandomNumberGenerator>,
+    run_seed: i64,
+    /// Seed the next run starts with instead of a random one, e.g. the one a host sent.
+    next_seed: Option<i64>,
+    /// Side of the network run in progress, `NetworkRole::Offline` outside of one.
+    net_role: NetworkRole,
+    /// Peer of each player seat in a network run, the host first.
+    roster: Vec<i64>,
+    /// Longest endless run, replayed by the `Ghost`.
+    best_run: Option<GhostRun>,
+    /// Track of the current run, while it can become the best one.
+    recording: Option<GhostRun>,
+    /// Rules of the daily challenge being played.
+    daily: Option<DailyChallenge>,
+    daily_results: DailyResults,
+    /// Whether the current daily run is the day's scored attempt rather than practice.
+    daily_attempt: bool,
+    /// Playback of `wave_script` in the current run.
+    waves: Option<WavePlayer>,
+    /// Animation names of the `enemy` scene, read once when the scene is ready.
+    archetypes: PackedStringArray,
+    /// Enemies of the patterns in progress still waiting for their turn, with the archetype
+    /// of their pattern.
+    pending_shots: Vec<(PatternShot, Option<StringName>)>,
+    /// Seconds before another pattern can start.
+    pattern_cooldown: f64,
+    /// Boss fight in progress, until the boss has left the screen.
+    boss_fight: Option<BossFight>,
+    /// Index of the next milestone of `boss_encounter`.
+    next_boss: usize,
+    /// Share of the spawn ramp skipped thanks to the bosses outlasted.
+    difficulty_boost: f64,
     path_enemy: OnReady<Gd<Path2D>>,
     path_follow: OnReady<Gd<PathFollow2D>>,
     starter_position: OnReady<Gd<Marker2D>>,
     enemy_timer: OnReady<Gd<Timer>>,
-    score_timer: OnReady<Gd<Timer>>,
     starter_timer: OnReady<Gd<Timer>>,
     player: OnReady<Gd<PlayerBase>>,
+    ghost: OnReady<Gd<Ghost>>,
+    boss: OnReady<Gd<BossBase>>,
     hud: OnReady<Gd<HUDBase>>,
+    menu: OnReady<Gd<MainMenu>>,
+    settings: OnReady<Gd<SettingsPanel>>,
+    controls: OnReady<Gd<ControlsPanel>>,
+    lobby: OnReady<Gd<Lobby>>,
+    network: OnReady<Gd<NetworkSession>>,
+    calendar: OnReady<Gd<DailyCalendar>>,
     color_rect: OnReady<Gd<ColorRect>>,
-    music: OnReady<Gd<AudioStreamPlayer2D>>,
-    sound_death: OnReady<Gd<AudioStreamPlayer2D>>,
+    audio: OnReady<Gd<AudioManager>>,
+    feedback: OnReady<Gd<FeedbackBase>>,
 }
 
 #[godot_api]
@@ -70,22 +221,73 @@ impl INode for MainScene {
         Self {
             base,
             score: 0,
+            run_time: 0f64,
+            bonus: 0,
+            multiplier: 1f64,
+            phase: GamePhase::Title,
+            mode: GameMode::Endless,
+            stats: RunStats::default(),
+            high_scores: HighScores::default(),
+            players: Vec::new(),
+            round: 0,
+            wins: Vec::new(),
+            rng: RandomNumberGenerator::new_gd(),
+            run_seed: 0,
+            next_seed: None,
+            net_role: NetworkRole::Offline,
+            roster: Vec::new(),
+            best_run: None,
+            recording: None,
+            daily: None,
+            daily_results: DailyResults::default(),
+            daily_attempt: false,
+            waves: None,
+            archetypes: PackedStringArray::new(),
+            pending_shots: Vec::new(),
+            pattern_cooldown: 0f64,
+            boss_fight: None,
+            next_boss: 0,
+            difficulty_boost: 0f64,
             enemy: None,
+            scoring_rules: None,
+            spawn_patterns: None,
+            boss_encounter: None,
+            countdown_length: 3f64,
+            min_spawn_interval: 0.25f64,
+            spawn_ramp_time: 120f64,
+            milestone_interval: 30f64,
+            revive_time: 10f64,
+            versus_players: 2,
+            versus_rounds: 3,
+            round_break: 3f64,
+            push_strength: 240f32,
+            ghost_enabled: true,
+            wave_script: None,
+            wave_file: GString::new(),
+            base_spawn_interval: 0.5f64,
+            next_milestone: 0f64,
             path_enemy: OnReady::from_node(&MainSceneChild::PathEnemy.to_string()),
             path_follow: OnReady::from_node(&format!(
                 "{}/{}",
-                MainSceneChild::PathEnemy.to_string(),
-                MainSceneChild::SpawnEnemy.to_string()
+                MainSceneChild::PathEnemy,
+                MainSceneChild::SpawnEnemy
             )),
             starter_position: OnReady::from_node(&MainSceneChild::StarterPosition.to_string()),
             enemy_timer: OnReady::from_node(&MainSceneChild::EnemyTimer.to_string()),
-            score_timer: OnReady::from_node(&MainSceneChild::ScoreTimer.to_string()),
             starter_timer: OnReady::from_node(&MainSceneChild::StarterTimer.to_string()),
             player: OnReady::from_node(&MainSceneChild::Player.to_string()),
-            hud: OnReady::from_node(&MainSceneChild::HUD.to_string()),
+            ghost: OnReady::from_node(&MainSceneChild::Ghost.to_string()),
+            boss: OnReady::from_node(&MainSceneChild::Boss.to_string()),
+            hud: OnReady::from_node(&MainSceneChild::Hud.to_string()),
+            menu: OnReady::from_node(&MainSceneChild::Menu.to_string()),
+            settings: OnReady::from_node(&MainSceneChild::Settings.to_string()),
+            controls: OnReady::from_node(&MainSceneChild::Controls.to_string()),
+            lobby: OnReady::from_node(&MainSceneChild::Lobby.to_string()),
+            network: OnReady::from_node(&MainSceneChild::Network.to_string()),
+            calendar: OnReady::from_node(&MainSceneChild::Calendar.to_string()),
             color_rect: OnReady::from_node(&MainSceneChild::Background.to_string()),
-            music: OnReady::from_node(&MainSceneChild::Music.to_string()),
-            sound_death: OnReady::from_node(&MainSceneChild::SoundDeath.to_string()),
+            audio: OnReady::from_node(&MainSceneChild::Audio.to_string()),
+            feedback: OnReady::from_node(&MainSceneChild::Feedback.to_string()),
         }
     }
 
@@ -100,18 +302,19 @@ impl INode for MainScene {
                 background
             });
 
-        self
-            .base()
+        self.base()
             .try_get_node_as::<PlayerBase>(&MainSceneChild::Player.to_string())
             .unwrap_or_else(|| {
                 let mut loader = ResourceLoader::singleton();
 
                 let scene = loader
                     .load("res://player.tscn")
-                    .expect("Falha ao carregar player.tscn. Verifique se a cena se encontra no caminho: 'res://player.tscn'")
+                    .unwrap_or_else(|| panic!("{}", tr(TextKey::ErrorLoadPlayer)))
                     .cast::<PackedScene>();
 
-                let mut player_instance = scene.try_instantiate_as::<PlayerBase>().expect("Falha ao instanciar player");
+                let mut player_instance = scene
+                    .try_instantiate_as::<PlayerBase>()
+                    .unwrap_or_else(|| panic!("{}", tr(TextKey::ErrorInstantiatePlayer)));
 
                 player_instance.set_name(&MainSceneChild::Player.to_string());
                 self.base_mut().add_child(&player_instance);
@@ -120,18 +323,39 @@ impl INode for MainScene {
                 player_instance
             });
 
-        self
-            .base()
-            .try_get_node_as::<HUDBase>(&MainSceneChild::HUD.to_string())
+        self.base()
+            .try_get_node_as::<Ghost>(&MainSceneChild::Ghost.to_string())
+            .unwrap_or_else(|| {
+                let mut ghost = Ghost::new_alloc();
+                ghost.set_name(&MainSceneChild::Ghost.to_string());
+                self.base_mut().add_child(&ghost);
+                ghost.set_owner(self.base().to_godot());
+                ghost
+            });
+
+        self.base()
+            .try_get_node_as::<BossBase>(&MainSceneChild::Boss.to_string())
+            .unwrap_or_else(|| {
+                let mut boss = BossBase::new_alloc();
+                boss.set_name(&MainSceneChild::Boss.to_string());
+                self.base_mut().add_child(&boss);
+                boss.set_owner(self.base().to_godot());
+                boss
+            });
+
+        self.base()
+            .try_get_node_as::<HUDBase>(&MainSceneChild::Hud.to_string())
             .unwrap_or_else(|| {
                 let mut loader = ResourceLoader::singleton();
                 let scene = loader
                     .load("res://HUD.tscn")
-                    .expect("Falha ao carregar hud.tscn. Verifique se a cena se encontra no caminho: 'res://hud.tscn'")
+                    .unwrap_or_else(|| panic!("{}", tr(TextKey::ErrorLoadHud)))
                     .cast::<PackedScene>();
 
-                let mut hud_instance = scene.try_instantiate_as::<HUDBase>().expect("Falha ao instanciar o HUD");
-                hud_instance.set_name(&MainSceneChild::HUD.to_string());
+                let mut hud_instance = scene
+                    .try_instantiate_as::<HUDBase>()
+                    .unwrap_or_else(|| panic!("{}", tr(TextKey::ErrorInstantiateHud)));
+                hud_instance.set_name(&MainSceneChild::Hud.to_string());
                 self.base_mut().add_child(&hud_instance);
                 hud_instance.set_owner(self.base().to_godot());
 
@@ -139,20 +363,71 @@ impl INode for MainScene {
             });
 
         self.base()
-            .try_get_node_as::<Timer>(&MainSceneChild::StarterTimer.to_string())
+            .try_get_node_as::<MainMenu>(&MainSceneChild::Menu.to_string())
             .unwrap_or_else(|| {
-                let mut timer = Timer::new_alloc();
-                timer.set_name(&MainSceneChild::StarterTimer.to_string());
-                self.base_mut().add_child(&timer);
-                timer.set_owner(self.base().to_godot());
-                timer
+                let mut menu = MainMenu::new_alloc();
+                menu.set_name(&MainSceneChild::Menu.to_string());
+                self.base_mut().add_child(&menu);
+                menu.set_owner(self.base().to_godot());
+                menu
+            });
+
+        self.base()
+            .try_get_node_as::<SettingsPanel>(&MainSceneChild::Settings.to_string())
+            .unwrap_or_else(|| {
+                let mut settings = SettingsPanel::new_alloc();
+                settings.set_name(&MainSceneChild::Settings.to_string());
+                self.base_mut().add_child(&settings);
+                settings.set_owner(self.base().to_godot());
+                settings
+            });
+
+        // Added after the settings panel so it sees `ui_cancel` first while both are open.
+        self.base()
+            .try_get_node_as::<ControlsPanel>(&MainSceneChild::Controls.to_string())
+            .unwrap_or_else(|| {
+                let mut controls = ControlsPanel::new_alloc();
+                controls.set_name(&MainSceneChild::Controls.to_string());
+                self.base_mut().add_child(&controls);
+                controls.set_owner(self.base().to_godot());
+                controls
+            });
+
+        self.base()
+            .try_get_node_as::<Lobby>(&MainSceneChild::Lobby.to_string())
+            .unwrap_or_else(|| {
+                let mut lobby = Lobby::new_alloc();
+                lobby.set_name(&MainSceneChild::Lobby.to_string());
+                self.base_mut().add_child(&lobby);
+                lobby.set_owner(self.base().to_godot());
+                lobby
             });
 
         self.base()
-            .try_get_node_as::<Timer>(&MainSceneChild::ScoreTimer.to_string())
+            .try_get_node_as::<NetworkSession>(&MainSceneChild::Network.to_string())
+            .unwrap_or_else(|| {
+                let mut network = NetworkSession::new_alloc();
+                network.set_name(&MainSceneChild::Network.to_string());
+                self.base_mut().add_child(&network);
+                network.set_owner(self.base().to_godot());
+                network
+            });
+
+        self.base()
+            .try_get_node_as::<DailyCalendar>(&MainSceneChild::Calendar.to_string())
+            .unwrap_or_else(|| {
+                let mut calendar = DailyCalendar::new_alloc();
+                calendar.set_name(&MainSceneChild::Calendar.to_string());
+                self.base_mut().add_child(&calendar);
+                calendar.set_owner(self.base().to_godot());
+                calendar
+            });
+
+        self.base()
+            .try_get_node_as::<Timer>(&MainSceneChild::StarterTimer.to_string())
             .unwrap_or_else(|| {
                 let mut timer = Timer::new_alloc();
-                timer.set_name(&MainSceneChild::ScoreTimer.to_string());
+                timer.set_name(&MainSceneChild::StarterTimer.to_string());
                 self.base_mut().add_child(&timer);
                 timer.set_owner(self.base().to_godot());
                 timer
@@ -202,44 +477,84 @@ impl INode for MainScene {
         }
 
         self.base()
-            .try_get_node_as::<AudioStreamPlayer2D>(&MainSceneChild::Music.to_string())
+            .try_get_node_as::<AudioManager>(&MainSceneChild::Audio.to_string())
             .unwrap_or_else(|| {
-                let mut audio = AudioStreamPlayer2D::new_alloc();
-                audio.set_name(&MainSceneChild::Music.to_string());
+                let mut audio = AudioManager::new_alloc();
+                audio.set_name(&MainSceneChild::Audio.to_string());
                 self.base_mut().add_child(&audio);
                 audio.set_owner(self.base().to_godot());
                 audio
             });
 
         self.base()
-            .try_get_node_as::<AudioStreamPlayer2D>(&MainSceneChild::SoundDeath.to_string())
+            .try_get_node_as::<FeedbackBase>(&MainSceneChild::Feedback.to_string())
             .unwrap_or_else(|| {
-                let mut audio = AudioStreamPlayer2D::new_alloc();
-                audio.set_name(&MainSceneChild::SoundDeath.to_string());
-                self.base_mut().add_child(&audio);
-                audio.set_owner(self.base().to_godot());
-                audio
+                let mut feedback = FeedbackBase::new_alloc();
+                feedback.set_name(&MainSceneChild::Feedback.to_string());
+                self.base_mut().add_child(&feedback);
+                feedback.set_owner(self.base().to_godot());
+                feedback
             });
     }
 
     fn ready(&mut self) {
         randomize();
 
-        self.player
-            .signals()
-            .hit()
-            .connect_other(self, Self::game_over);
+        if !Engine::singleton().is_editor_hint() {
+            input_bindings::ensure_registered();
+            self.setup_pause_modes();
+
+            if self.scoring_rules.is_none() {
+                self.scoring_rules = Some(ScoringRules::new_gd());
+            }
+
+            if self.spawn_patterns.is_none() {
+                self.spawn_patterns = Some(SpawnPatterns::new_gd());
+            }
+
+            if self.boss_encounter.is_none() {
+                self.boss_encounter = Some(BossEncounter::new_gd());
+            }
+
+            if self.enemy.is_some() {
+                let sample = self.instantiate_enemy();
+                self.archetypes = sample.bind().get_archetypes();
+                self.boss.bind_mut().mimic(&sample);
+                sample.free();
+            }
+
+            if !self.wave_file.is_empty() {
+                let script = WaveScript::load_json(self.wave_file.clone());
+                self.wave_script = script.or(self.wave_script.take());
+            }
+
+            self.high_scores = HighScores::load();
+            self.base_spawn_interval = self.enemy_timer.get_wait_time();
+
+            let high_scores = self.high_scores.clone();
+            self.menu.bind_mut().set_high_scores(&high_scores);
+
+            self.best_run = GhostRun::load();
+            self.daily_results = DailyResults::load();
+            let sprite = self.player.bind().get_sprite();
+            self.ghost.bind_mut().mimic(&sprite);
+
+            let reduced_motion = self.settings.bind().get_reduced_motion();
+            self.feedback.bind_mut().set_reduced_motion(reduced_motion);
+        }
+
+        let player = self.player.clone();
+        self.connect_player(0, &player);
+        self.players = vec![PlayerSlot {
+            player,
+            state: PlayerStatus::Alive,
+        }];
 
         self.starter_timer
             .signals()
             .timeout()
             .connect_other(self, Self::on_starter_timer_timeout);
 
-        self.score_timer
-            .signals()
-            .timeout()
-            .connect_other(self, Self::on_score_timer_timeout);
-
         self.enemy_timer
             .signals()
             .timeout()
@@ -248,72 +563,1574 @@ impl INode for MainScene {
         self.hud
             .signals()
             .start_game()
-            .connect_other(self, Self::new_game);
+            .connect_other(self, Self::on_hud_start_game);
+
+        self.hud
+            .signals()
+            .main_menu_requested()
+            .connect_other(self, Self::return_to_title);
+
+        self.menu
+            .signals()
+            .play_requested()
+            .connect_other(self, Self::start_game);
+
+        let settings = self.settings.clone();
+        self.menu
+            .signals()
+            .settings_requested()
+            .connect_other(&settings, SettingsPanel::open);
+        self.hud
+            .signals()
+            .settings_requested()
+            .connect_other(&settings, SettingsPanel::open);
+
+        let calendar = self.calendar.clone();
+        self.menu
+            .signals()
+            .daily_requested()
+            .connect_other(&calendar, DailyCalendar::open);
+
+        let controls = self.controls.clone();
+        self.settings
+            .signals()
+            .controls_requested()
+            .connect_other(&controls, ControlsPanel::open);
+
+        let feedback = self.feedback.clone();
+        self.settings
+            .signals()
+            .reduced_motion_changed()
+            .connect_other(&feedback, FeedbackBase::set_reduced_motion);
+
+        let starter_timer = self.starter_timer.clone();
+        self.hud.bind_mut().set_countdown_timer(starter_timer);
+
+        let hud = self.hud.clone();
+        self.signals()
+            .phase_changed()
+            .connect_other(&hud, HUDBase::on_phase_changed);
+
+        let menu = self.menu.clone();
+        self.signals()
+            .phase_changed()
+            .connect_other(&menu, MainMenu::on_phase_changed);
+
+        let lobby = self.lobby.clone();
+        self.signals()
+            .phase_changed()
+            .connect_other(&lobby, Lobby::on_phase_changed);
+
+        self.connect_audio();
+        self.connect_network();
+
+        if !Engine::singleton().is_editor_hint() {
+            // Nothing has transitioned yet, so bring every listener in line with the initial phase.
+            let phase = self.phase;
+            self.signals().phase_changed().emit(phase, phase);
+
+            if self.network.bind_mut().connect_from_command_line() {
+                self.lobby.bind_mut().open();
+            }
+        }
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        match self.net_role {
+            NetworkRole::Client => {
+                self.follow_host(delta);
+                return;
+            }
+            NetworkRole::Host => self.send_snapshot(delta),
+            NetworkRole::Offline => {}
+        }
+
+        if self.phase != GamePhase::Playing {
+            return;
+        }
+
+        self.run_time += delta;
+
+        if let Some(limit) = self.mode.time_limit() {
+            self.run_time = self.run_time.min(limit);
+        }
+
+        self.hud.bind_mut().update_time(self.run_time);
+        self.update_ghost();
+        self.update_difficulty();
+        self.update_boss(delta);
+        self.update_waves(delta);
+        self.update_pattern_shots(delta);
+        self.update_revivals(delta);
+
+        if let Some(rules) = self.scoring_rules.as_ref() {
+            let multiplier = rules.bind().decay_multiplier(self.multiplier, delta);
+            self.set_multiplier(multiplier);
+        }
+
+        self.refresh_score();
+
+        if self.mode.is_cleared(self.run_time, self.score) {
+            self.stats.cleared = true;
+            self.game_over();
+        }
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        let handled = if event.is_action_pressed(&GameAction::Start.name())
+            && self.phase == GamePhase::Countdown
+            && self.net_role != NetworkRole::Client
+        {
+            self.skip_countdown();
+            true
+        } else if event.is_action_pressed(&GameAction::Start.name())
+            && self.phase == GamePhase::RoundOver
+        {
+            self.next_round(self.round);
+            true
+        } else if event.is_action_pressed(&GameAction::Pause.name())
+            && matches!(self.phase, GamePhase::Playing | GamePhase::Paused)
+            && self.mode != GameMode::Network
+        {
+            match self.phase {
+                GamePhase::Playing => self.transition_to(GamePhase::Paused),
+                GamePhase::Paused if !self.settings.bind().is_open() => {
+                    self.transition_to(GamePhase::Playing)
+                }
+                _ => false,
+            }
+        } else if event.is_action_pressed("ui_cancel")
+            && matches!(self.phase, GamePhase::GameOver | GamePhase::RoundOver)
+        {
+            self.return_to_title()
+        } else {
+            false
+        };
+
+        if !handled {
+            return;
+        }
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
     }
 }
 
 #[godot_api]
 impl MainScene {
+    #[signal]
+    pub fn phase_changed(from: GamePhase, to: GamePhase);
+
+    /// Emitted whenever the score of the current run changes.
+    #[signal]
+    pub fn score_changed(score: i64);
+
+    /// Emitted after an enemy has been added to the scene and set in motion.
+    #[signal]
+    pub fn enemy_spawned(enemy: Gd<EnemyBase>);
+
+    /// Emitted when a player is hit, before the run moves to `GamePhase::GameOver` or, with
+    /// a partner still alive, the player goes down. In zen the player carries on instead.
+    /// Carries the same values as `PlayerBase::hit`.
+    #[signal]
+    pub fn player_hit(enemy: Gd<Node2D>, archetype: StringName, contact: Vector2);
+
+    /// Emitted once per run when it ends. `stats` holds `mode`, `score`, `time`,
+    /// `enemies_spawned`, `enemies_dodged`, `grazes`, `hits`, `cleared` and, when known,
+    /// `death_archetype` and `death_position`. A versus match adds `rounds` and the `wins` of
+    /// every player.
+    #[signal]
+    pub fn run_finished(stats: VarDictionary);
+
+    /// Starts a new run with the given `GameMode`. During the countdown it skips straight to
+    /// playing instead; otherwise it is ignored while a run is in progress.
+    ///
+    /// `GameMode::Network` opens the lobby while offline, and restarts the session for
+    /// everyone when hosting; clients wait for the host.
+    #[func]
+    pub fn start_game(&mut self, mode: GameMode) {
+        if self.phase == GamePhase::Countdown {
+            if self.net_role != NetworkRole::Client {
+                self.skip_countdown();
+            }
+            return;
+        }
+
+        if !self.phase.can_transition_to(GamePhase::Countdown) {
+            return;
+        }
+
+        if mode == GameMode::Network {
+            match self.net_role {
+                NetworkRole::Offline => self.lobby.bind_mut().open(),
+                NetworkRole::Host => {
+                    let seed = randi();
+                    let roster = self.network.bind_mut().announce_session(seed);
+
+                    if let Some(roster) = roster {
+                        self.roster = roster.as_slice().to_vec();
+                        self.begin_network_run(seed);
+                    }
+                }
+                NetworkRole::Client => {}
+            }
+            return;
+        }
+
+        self.mode = mode;
+        self.round = 0;
+        self.wins = vec![0; self.player_count()];
+        self.new_game();
+    }
+
+    #[func]
+    pub fn get_score(&self) -> i64 {
+        self.score
+    }
+
+    /// Seconds survived in the current run, accumulated from physics frames while playing.
+    #[func]
+    pub fn get_run_time(&self) -> f64 {
+        self.run_time
+    }
+
+    /// Adds `points` on top of the time-based score of the current run.
+    #[func]
+    pub fn add_bonus(&mut self, points: i64) {
+        self.bonus += points;
+        self.refresh_score();
+    }
+
+    /// Seed the spawns of the current run were drawn from.
+    #[func]
+    pub fn get_run_seed(&self) -> i64 {
+        self.run_seed
+    }
+
+    #[func]
+    pub fn get_mode(&self) -> GameMode {
+        self.mode
+    }
+
+    #[func]
+    pub fn get_phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    /// Spawns an enemy from the `enemy` scene at `at`, moving with `velocity` and facing
+    /// its direction. Works in any phase, so scripts can stage enemies on the title screen.
+    #[func]
+    pub fn spawn_enemy(&mut self, at: Vector2, velocity: Vector2) -> Gd<EnemyBase> {
+        let enemy = self.instantiate_enemy();
+        self.place_enemy(enemy, at, velocity, None)
+    }
+
+    /// Abandons a paused or finished run and goes back to the main menu, leaving the network
+    /// session of a network run. Returns whether the menu was reached.
+    #[func]
+    pub fn return_to_title(&mut self) -> bool {
+        if !self.phase.can_transition_to(GamePhase::Title) {
+            return false;
+        }
+
+        // Nothing else ends a zen run, so leaving it from the pause menu finishes it.
+        if self.mode == GameMode::Zen && self.phase == GamePhase::Paused {
+            self.stats.time = self.run_time;
+            self.stats.score = self.score;
+
+            if self.high_scores.record(self.mode, &self.stats) {
+                self.high_scores.save();
+                self.menu.bind_mut().set_high_scores(&self.high_scores);
+            }
+
+            self.emit_run_finished();
+        }
+
+        self.stop_run();
+        self.leave_session();
+        self.transition_to(GamePhase::Title)
+    }
+
+    /// Moves the run to `next` if `GamePhase::can_transition_to` allows it and emits
+    /// `phase_changed`. Returns whether the transition happened.
+    pub fn transition_to(&mut self, next: GamePhase) -> bool {
+        let previous = self.phase;
+
+        if !previous.can_transition_to(next) {
+            return false;
+        }
+
+        self.phase = next;
+
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.set_pause(next == GamePhase::Paused);
+        }
+
+        if self.net_role == NetworkRole::Host {
+            self.network.bind_mut().broadcast_phase(next);
+        }
+
+        self.signals().phase_changed().emit(previous, next);
+        true
+    }
+
+    fn on_player_hit(
+        &mut self,
+        index: usize,
+        enemy: Gd<Node2D>,
+        archetype: StringName,
+        contact: Vector2,
+    ) {
+        if !matches!(self.phase, GamePhase::Countdown | GamePhase::Playing) {
+            return;
+        }
+
+        if !self.mode.is_lethal() {
+            self.shrug_off_hit(index, enemy, archetype, contact);
+            return;
+        }
+
+        let status = if self.mode == GameMode::Versus {
+            PlayerStatus::Out
+        } else {
+            PlayerStatus::Down(self.revive_time)
+        };
+
+        let Some(slot) = self.players.get_mut(index) else {
+            return;
+        };
+
+        slot.state = status;
+        let death_position = slot.player.get_position();
+        self.stats.death_position = Some(death_position);
+        self.stats.death_archetype = Some(archetype.clone()).filter(|name| !name.is_empty());
+
+        self.signals()
+            .player_hit()
+            .emit(&enemy, &archetype, contact);
+        self.feedback.bind_mut().play_death(death_position);
+
+        let alive = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.state == PlayerStatus::Alive)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if self.mode == GameMode::Versus {
+            self.hud.bind_mut().update_player_status(index, status);
+
+            if alive.len() <= 1 {
+                self.end_round(alive.first().copied());
+            }
+            return;
+        }
+
+        if !alive.is_empty() {
+            self.hud.bind_mut().update_player_status(index, status);
+            return;
+        }
+
+        self.game_over();
+    }
+
     #[func]
+    fn on_enemy_dodged(&mut self) {
+        if self.phase == GamePhase::Playing {
+            self.stats.enemies_dodged += 1;
+        }
+    }
+
+    #[func]
+    fn on_player_grazed(&mut self, _enemy: Gd<Node2D>, at: Vector2) {
+        if self.phase != GamePhase::Playing {
+            return;
+        }
+
+        self.stats.grazes += 1;
+
+        let Some(rules) = self.scoring_rules.clone() else {
+            return;
+        };
+
+        let award = rules.bind().graze_award(self.multiplier);
+        let multiplier = rules.bind().raise_multiplier(self.multiplier);
+
+        self.add_bonus(award);
+        self.set_multiplier(multiplier);
+        let at = self.to_screen(at);
+        self.hud.bind_mut().show_popup(&format!("+{award}"), at);
+        self.audio.bind_mut().near_miss();
+    }
+
+    #[func]
+    fn on_hud_start_game(&mut self) {
+        self.start_game(self.mode);
+    }
+
     fn game_over(&mut self) {
-        self.score_timer.stop();
+        if !self.phase.can_transition_to(GamePhase::GameOver) {
+            return;
+        }
+
+        self.starter_timer.stop();
         self.enemy_timer.stop();
-        self.music.stop();
-        self.sound_death.play();
+        self.boss_fight = None;
+        self.boss.bind_mut().leave();
 
-        let hud = self.hud.clone();
-        godot::task::spawn(async move {
-            HUDBase::game_over(hud).await;
-        });
+        self.stats.time = self.run_time;
+        self.stats.score = self.score;
+        self.keep_best_run();
+        self.keep_daily_result();
+
+        // A versus match has already filled the summary with its standings. Modes without a
+        // high-score table, such as network runs and daily challenges, break no records.
+        if self.mode != GameMode::Versus {
+            let new_record = self.high_scores.record(self.mode, &self.stats);
+            if new_record {
+                self.high_scores.save();
+            }
+
+            let bests = self.high_scores.get(self.mode);
+            self.hud
+                .bind_mut()
+                .set_summary(&self.stats, &bests, new_record);
+            self.menu.bind_mut().set_high_scores(&self.high_scores);
+        }
+
+        self.transition_to(GamePhase::GameOver);
+        self.emit_run_finished();
     }
 
-    #[func]
-    fn on_starter_timer_timeout(&mut self) {
-        self.enemy_timer.start();
-        self.score_timer.start();
+    /// Emits `run_finished` with the stats of the run that just ended.
+    fn emit_run_finished(&mut self) {
+        let mut stats = self.stats.to_dictionary();
+        stats.set("mode", self.mode);
+
+        if self.mode == GameMode::Versus {
+            stats.set("rounds", self.round);
+            stats.set(
+                "wins",
+                self.wins.iter().copied().collect::<PackedInt64Array>(),
+            );
+        }
+
+        if let Some(daily) = &self.daily {
+            stats.set("daily_date", daily.date.to_string());
+        }
+
+        self.signals().run_finished().emit(&stats);
     }
 
     #[func]
-    fn on_score_timer_timeout(&mut self) {
-        self.score += 1;
-        self.hud.bind_mut().update_score(self.score);
+    fn on_starter_timer_timeout(&mut self) {
+        // A client's countdown only ticks for the HUD; the host says when play starts.
+        if self.net_role == NetworkRole::Client {
+            return;
+        }
+
+        if !self.transition_to(GamePhase::Playing) {
+            return;
+        }
+
+        self.enemy_timer.start();
     }
 
     #[func]
     fn on_enemy_timer_timeout(&mut self) {
-        self.path_follow.set_progress_ratio(randf() as f32);
-        let mut enemy = self
-            .enemy
-            .as_ref()
-            .expect("Erro ao instanciar inimigo na cena")
-            .try_instantiate_as::<EnemyBase>()
-            .expect("Erro ao instanciar inimigo na cena");
+        // Bosses pause the spawner; waves take over from random spawns until they run out.
+        if self.boss_fight.is_some()
+            || self
+                .waves
+                .as_ref()
+                .is_some_and(|waves| !waves.is_finished())
+        {
+            return;
+        }
 
-        self.base_mut().add_child(&enemy);
+        if self.start_pattern() {
+            return;
+        }
+
+        let ratio = self.rng.randf();
+        self.path_follow.set_progress_ratio(ratio);
+        let enemy = self.instantiate_enemy();
+
+        let speed_scale = self.daily.as_ref().map_or(1f32, |daily| daily.speed_scale);
+        let speed = self
+            .rng
+            .randi_range(enemy.bind().min_speed as i32, enemy.bind().max_speed as i32)
+            as f32
+            * speed_scale;
+
+        let mut direction = self.path_follow.get_rotation() + PI / 2f32;
+        direction += self.rng.randf_range(-PI / 4f32, PI / 4f32);
 
-        let linear_velocity = Vector2::new(
-            randi_range(enemy.bind().min_speed, enemy.bind().max_speed) as f32,
-            0f32,
+        let archetypes = self.archetypes.clone();
+        let archetype = self.pick_archetype(&archetypes);
+
+        let position = self.path_follow.get_position();
+        self.place_enemy(
+            enemy,
+            position,
+            Vector2::new(speed, 0f32).rotated(direction),
+            archetype,
         );
+    }
+
+    /// One of `archetypes` drawn from the run's generator, with the daily mix during a daily
+    /// challenge.
+    fn pick_archetype(&mut self, archetypes: &PackedStringArray) -> Option<StringName> {
+        match &self.daily {
+            Some(daily) => daily.pick_archetype(&mut self.rng, archetypes),
+            None if archetypes.is_empty() => None,
+            None => {
+                let index = self.rng.randi_range(0, archetypes.len() as i32 - 1);
+                archetypes.get(index as usize)
+            }
+        }
+        .map(|name| StringName::from(&name))
+    }
 
-        enemy.set_position(self.path_follow.get_position());
+    /// Spawns the events of the wave script that are due. Waves hold still during a boss
+    /// fight.
+    fn update_waves(&mut self, delta: f64) {
+        if self.boss_fight.is_some() {
+            return;
+        }
 
-        let mut direction = self.path_follow.get_rotation() as f64 + PI / 2f64;
-        direction += randf_range(-PI / 4f64, PI / 4f64);
-        enemy.set_rotation(direction as f32);
+        let Some(waves) = self.waves.as_mut() else {
+            return;
+        };
 
-        enemy.set_linear_velocity(linear_velocity.rotated(direction as f32));
+        for event in waves.advance(delta) {
+            self.spawn_wave_event(&event);
+        }
     }
 
-    fn new_game(&mut self) {
-        self.score = 0;
-        self.player
-            .bind_mut()
-            .start(self.starter_position.get_position());
+    /// Sends the enemies of `event` in from its edge of the `PathEnemy` bounds. An unknown
+    /// archetype or a zero speed is drawn at random, like a regular spawn.
+    fn spawn_wave_event(&mut self, event: &Gd<SpawnEvent>) {
+        let bounds = self.spawn_bounds();
+        let event = event.bind();
+        let speed_scale = self.daily.as_ref().map_or(1f32, |daily| daily.speed_scale);
+        let direction = event.direction();
 
-        self.starter_timer.start();
-        self.hud.bind_mut().show_message("Prepare-se");
-        self.hud.bind_mut().update_score(self.score);
-        self.music.play();
+        for at in event.spawn_points(bounds, &mut self.rng) {
+            let enemy = self.instantiate_enemy();
+
+            let speed = if event.speed > 0f32 {
+                event.speed
+            } else {
+                self.rng
+                    .randi_range(enemy.bind().min_speed as i32, enemy.bind().max_speed as i32)
+                    as f32
+            };
+
+            let archetypes = self.archetypes.clone();
+            let known = archetypes
+                .as_slice()
+                .iter()
+                .any(|name| StringName::from(name) == event.archetype);
+            let archetype = if known {
+                Some(event.archetype.clone())
+            } else {
+                self.pick_archetype(&archetypes)
+            };
+
+            self.place_enemy(enemy, at, direction * speed * speed_scale, archetype);
+        }
+    }
+
+    /// Sends a pattern from `spawn_patterns` instead of a single enemy, when its chance comes
+    /// up and the last one has cooled down. Every enemy of a pattern shares one archetype.
+    /// Returns whether a pattern started.
+    fn start_pattern(&mut self) -> bool {
+        if self.pattern_cooldown > 0f64 {
+            return false;
+        }
+
+        let Some(patterns) = self.spawn_patterns.clone() else {
+            return false;
+        };
+
+        let bounds = self.spawn_bounds();
+        let target = self.player.get_position();
+        let difficulty = self.spawn_progress() as f32;
+        let shots = patterns
+            .bind()
+            .pick(difficulty, bounds, target, &mut self.rng)
+            .filter(|shots| !shots.is_empty());
+
+        let Some(shots) = shots else {
+            return false;
+        };
+
+        let last = shots.iter().map(|shot| shot.delay).fold(0f64, f64::max);
+        self.pattern_cooldown = patterns.bind().cooldown + last;
+
+        let archetypes = self.archetypes.clone();
+        let archetype = self.pick_archetype(&archetypes);
+
+        self.pending_shots
+            .extend(shots.into_iter().map(|shot| (shot, archetype.clone())));
+        self.update_pattern_shots(0f64);
+        true
+    }
+
+    /// Counts down the pattern cooldown and sends the pattern enemies whose turn has come.
+    fn update_pattern_shots(&mut self, delta: f64) {
+        self.pattern_cooldown = (self.pattern_cooldown - delta).max(0f64);
+
+        let mut due = Vec::new();
+        self.pending_shots.retain_mut(|(shot, archetype)| {
+            shot.delay -= delta;
+            if shot.delay > 0f64 {
+                return true;
+            }
+
+            due.push((*shot, archetype.take()));
+            false
+        });
+
+        let speed_scale = self.daily.as_ref().map_or(1f32, |daily| daily.speed_scale);
+
+        for (shot, archetype) in due {
+            let enemy = self.instantiate_enemy();
+            self.place_enemy(enemy, shot.at, shot.velocity * speed_scale, archetype);
+        }
+    }
+
+    /// Starts a boss fight once the score reaches the next milestone, and plays the one in
+    /// progress. The fight ends when the boss has left the screen.
+    fn update_boss(&mut self, delta: f64) {
+        let Some(encounter) = self.boss_encounter.clone() else {
+            return;
+        };
+
+        let Some(fight) = self.boss_fight.as_mut() else {
+            self.start_boss_fight(&encounter);
+            return;
+        };
+
+        for cue in fight.advance(delta) {
+            match cue {
+                BossCue::Enter => {
+                    let bounds = self.spawn_bounds();
+                    let archetypes = self.archetypes.clone();
+                    let archetype = self.pick_archetype(&archetypes).unwrap_or_default();
+                    self.boss.bind_mut().enter(bounds, archetype);
+                }
+                BossCue::Attack(index) => self.boss_attack(&encounter, index),
+                BossCue::Leave => {
+                    let (bonus, step) = {
+                        let encounter = encounter.bind();
+                        (encounter.bonus, encounter.difficulty_step)
+                    };
+
+                    self.boss.bind_mut().leave();
+                    self.add_bonus(bonus);
+                    self.difficulty_boost += step;
+
+                    let at = self.to_screen(self.boss.get_position());
+                    self.hud.bind_mut().show_boss_bonus(bonus, at);
+                    self.audio.bind_mut().play_stinger();
+                }
+            }
+        }
+
+        let over = self.boss_fight.as_ref().is_some_and(BossFight::is_over);
+        if over && !self.boss.bind().is_active() {
+            self.boss_fight = None;
+        }
+    }
+
+    /// Warns of a boss and pauses the spawner when the score has reached the next milestone.
+    /// Milestones passed at once bring a single boss.
+    fn start_boss_fight(&mut self, encounter: &Gd<BossEncounter>) {
+        if !self.mode.has_bosses() {
+            return;
+        }
+
+        let encounter = encounter.bind();
+        let reached = encounter
+            .milestones
+            .as_slice()
+            .iter()
+            .filter(|milestone| self.score >= **milestone)
+            .count();
+
+        if reached <= self.next_boss {
+            return;
+        }
+
+        self.next_boss = reached;
+        self.boss_fight = Some(BossFight::new(&encounter));
+        self.hud.bind_mut().warn_boss(encounter.warning_time);
+        self.audio.bind_mut().play_stinger();
+    }
+
+    /// Queues attack `index` of the boss's sequence, aimed at the player. Its enemies share
+    /// the boss's archetype.
+    fn boss_attack(&mut self, encounter: &Gd<BossEncounter>, index: usize) {
+        let Some(attacks) = encounter.bind().attacks.clone() else {
+            return;
+        };
+
+        let bounds = self.spawn_bounds();
+        let origin = self.boss.get_position();
+        let target = self.player.get_position();
+        let shots = attacks
+            .bind()
+            .nth_shots(index, origin, bounds, target, &mut self.rng);
+
+        let Some(shots) = shots else {
+            return;
+        };
+
+        let archetype = Some(self.boss.bind().get_archetype()).filter(|name| !name.is_empty());
+        self.pending_shots
+            .extend(shots.into_iter().map(|shot| (shot, archetype.clone())));
+        self.update_pattern_shots(0f64);
+    }
+
+    /// Box around the `PathEnemy` curve, in the scene's coordinates.
+    fn spawn_bounds(&self) -> Rect2 {
+        let points = self
+            .path_enemy
+            .get_curve()
+            .map(|curve| curve.get_baked_points())
+            .unwrap_or_default();
+
+        let bounds = points
+            .as_slice()
+            .iter()
+            .fold(None, |bounds: Option<Rect2>, point| {
+                Some(bounds.map_or(Rect2::new(*point, Vector2::ZERO), |bounds| {
+                    bounds.expand(*point)
+                }))
+            })
+            .unwrap_or_default();
+
+        Rect2::new(
+            bounds.position + self.path_enemy.get_position(),
+            bounds.size,
+        )
+    }
+
+    fn new_game(&mut self) {
+        self.round += 1;
+        self.run_time = 0f64;
+        self.bonus = 0;
+        self.multiplier = 1f64;
+        self.set_score(0);
+        self.stats = RunStats::default();
+        self.next_milestone = self.milestone_interval;
+
+        let races_ghost = self.mode == GameMode::Endless
+            && self.net_role == NetworkRole::Offline
+            && self.ghost_enabled;
+        let ghost = self.best_run.clone().filter(|_| races_ghost);
+
+        if let Some(ghost) = &ghost {
+            self.next_seed = self.next_seed.or(Some(ghost.seed));
+        }
+
+        self.daily = (self.mode == GameMode::Daily).then(DailyChallenge::today);
+        self.daily_attempt = false;
+        let mut mode_info = self.mode_info();
+
+        if let Some(daily) = &self.daily {
+            self.next_seed = Some(daily.seed);
+            // The day's attempt is used up as soon as it starts, so leaving it early or
+            // quitting the game doesn't give another try. Finishing it replaces the zeroes.
+            self.daily_attempt = self
+                .daily_results
+                .record(daily.date, DailyResult::default());
+            if self.daily_attempt {
+                self.daily_results.save();
+            }
+
+            mode_info = ModeInfo::Daily {
+                date: daily.date,
+                speed_scale: daily.speed_scale,
+                practice: !self.daily_attempt,
+            };
+        }
+
+        self.run_seed = self.next_seed.take().unwrap_or_else(randi);
+        self.rng.set_seed(self.run_seed as u64);
+        self.recording = races_ghost.then(|| GhostRun::new(self.run_seed));
+        self.waves = self.wave_script.as_ref().map(WavePlayer::new);
+        self.pending_shots.clear();
+        self.pattern_cooldown = 0f64;
+        self.boss_fight = None;
+        self.next_boss = 0;
+        self.difficulty_boost = 0f64;
+        self.boss.bind_mut().stop();
+
+        let delta = ghost.as_ref().map(|ghost| -ghost.score_at(0f64));
+        self.ghost.bind_mut().set_run(ghost);
+        self.hud.bind_mut().update_ghost_delta(delta);
+        self.hud.bind_mut().set_mode(self.mode);
+        self.hud.bind_mut().set_mode_info(mode_info);
+        self.enemy_timer.set_wait_time(self.base_spawn_interval);
+        self.audio.bind_mut().set_intensity(0f32);
+        self.setup_players();
+
+        if self.countdown_length <= 0f64 {
+            self.transition_to(GamePhase::Countdown);
+            self.on_starter_timer_timeout();
+            return;
+        }
+
+        self.starter_timer
+            .start_ex()
+            .time_sec(self.countdown_length)
+            .done();
+        self.transition_to(GamePhase::Countdown);
+    }
+
+    /// Adds or frees players to match the mode, hands out input profiles, controls and tints,
+    /// and lines them up around the `StarterPosition`. A network host steers its own player
+    /// and those of clients with the input they send; clients only place puppets.
+    fn setup_players(&mut self) {
+        let count = self.player_count();
+        let push_strength = if self.mode == GameMode::Versus {
+            self.push_strength
+        } else {
+            0f32
+        };
+
+        while self.players.len() > count {
+            if let Some(mut slot) = self.players.pop() {
+                slot.player.queue_free();
+            }
+        }
+
+        while self.players.len() < count {
+            let index = self.players.len();
+
+            let mut player = self
+                .player
+                .duplicate()
+                .and_then(|node| node.try_cast::<PlayerBase>().ok())
+                .unwrap_or_else(|| panic!("{}", tr(TextKey::ErrorInstantiatePlayer)));
+            player.set_name(&format!("{}{}", MainSceneChild::Player, index + 1));
+            player.set_process_mode(ProcessMode::PAUSABLE);
+            self.base_mut().add_child(&player);
+            self.connect_player(index, &player);
+
+            self.players.push(PlayerSlot {
+                player,
+                state: PlayerStatus::Alive,
+            });
+        }
+
+        let center = self.starter_position.get_position();
+        let spacing = 60f32;
+
+        let role = self.net_role;
+
+        for (index, slot) in self.players.iter_mut().enumerate() {
+            let (profile, control) = match role {
+                NetworkRole::Client => (InputProfile::Shared, PlayerControl::Puppet),
+                NetworkRole::Host if index > 0 => (InputProfile::Shared, PlayerControl::Remote),
+                NetworkRole::Offline if count > 1 => {
+                    (InputProfile::LOCAL[index], PlayerControl::Local)
+                }
+                _ => (InputProfile::Shared, PlayerControl::Local),
+            };
+            let offset = (index as f32 - (count - 1) as f32 / 2f32) * spacing;
+
+            slot.state = PlayerStatus::Alive;
+            slot.player.set_modulate(PLAYER_TINTS[index]);
+
+            let mut player = slot.player.bind_mut();
+            player.input_profile = profile;
+            player.control = control;
+            player.set_push_strength(push_strength);
+            player.start(center + Vector2::new(offset, 0f32));
+        }
+
+        self.hud.bind_mut().set_players(&PLAYER_TINTS[..count]);
+    }
+
+    /// Players taking part in a run of the current mode.
+    fn player_count(&self) -> usize {
+        let count = match self.mode {
+            GameMode::Versus => self.versus_players.max(0) as usize,
+            GameMode::Network => self.roster.len(),
+            mode => mode.player_count(),
+        };
+
+        count.clamp(self.mode.player_count(), InputProfile::LOCAL.len())
+    }
+
+    /// Scores a versus round for `winner`, or for nobody on a draw, then shows the standings
+    /// until the next round. The match ends once a player has won more than half of
+    /// `versus_rounds`, or when they have all been played.
+    fn end_round(&mut self, winner: Option<usize>) {
+        self.stop_run();
+
+        if let Some(wins) = winner.and_then(|index| self.wins.get_mut(index)) {
+            *wins += 1;
+        }
+
+        let match_over = versus::match_over(&self.wins, self.round, self.versus_rounds);
+        let shown_winner = if match_over {
+            versus::match_leader(&self.wins)
+        } else {
+            winner
+        };
+
+        let wins = self.wins.clone();
+        let round = self.round;
+        self.hud.bind_mut().set_standings(
+            round,
+            &wins,
+            &PLAYER_TINTS[..wins.len()],
+            shown_winner,
+            match_over,
+        );
+
+        if match_over {
+            self.game_over();
+            return;
+        }
+
+        if !self.transition_to(GamePhase::RoundOver) {
+            return;
+        }
+
+        let scene = self.to_gd();
+        let timer = self
+            .base()
+            .get_tree()
+            .and_then(|mut tree| tree.create_timer(self.round_break.max(0f64)));
+
+        if let Some(timer) = timer {
+            // The tree's timer outlives the scene if it is freed during the break.
+            timer.signals().timeout().connect(move || {
+                if scene.is_instance_valid() {
+                    scene.clone().bind_mut().next_round(round);
+                }
+            });
+        }
+    }
+
+    /// Starts the round after `round`, unless the standings were already left.
+    fn next_round(&mut self, round: i64) {
+        if self.phase == GamePhase::RoundOver && self.round == round {
+            self.new_game();
+        }
+    }
+
+    /// Stops spawning, hides every player and the ghost, and frees the enemies left on screen.
+    fn stop_run(&mut self) {
+        self.starter_timer.stop();
+        self.enemy_timer.stop();
+        self.recording = None;
+        self.waves = None;
+        self.pending_shots.clear();
+        self.boss_fight = None;
+        self.boss.bind_mut().stop();
+        self.ghost.bind_mut().set_run(None);
+
+        for slot in self.players.iter_mut() {
+            slot.player.bind_mut().stop();
+        }
+
+        if let Some(mut tree) = self.base().get_tree() {
+            for mut enemy in tree.get_nodes_in_group(ENEMY_GROUP).iter_shared() {
+                enemy.queue_free();
+            }
+        }
+    }
+
+    fn connect_player(&mut self, index: usize, player: &Gd<PlayerBase>) {
+        let mut scene = self.to_gd();
+        player
+            .signals()
+            .hit()
+            .connect(move |enemy, archetype, contact| {
+                scene
+                    .bind_mut()
+                    .on_player_hit(index, enemy, archetype, contact);
+            });
+
+        player
+            .signals()
+            .grazed()
+            .connect_other(self, Self::on_player_grazed);
+    }
+
+    /// Counts down downed players and brings them back at the `StarterPosition`.
+    fn update_revivals(&mut self, delta: f64) {
+        let at = self.starter_position.get_position();
+        let mut hud = self.hud.clone();
+
+        for (index, slot) in self.players.iter_mut().enumerate() {
+            let PlayerStatus::Down(remaining) = slot.state else {
+                continue;
+            };
+
+            let remaining = remaining - delta;
+
+            if remaining > 0f64 {
+                slot.state = PlayerStatus::Down(remaining);
+                hud.bind_mut().update_player_status(index, slot.state);
+                continue;
+            }
+
+            slot.state = PlayerStatus::Alive;
+            slot.player.bind_mut().start(at);
+            hud.bind_mut().update_player_status(index, slot.state);
+        }
+    }
+
+    /// Records where the player is and the score, moves the ghost along, then shows how the
+    /// score compares with the best run's at the same time.
+    fn update_ghost(&mut self) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+
+        recording.record(self.run_time, self.player.get_position(), self.score);
+        self.ghost.bind_mut().follow(self.run_time);
+
+        if let Some(best) = &self.best_run {
+            let delta = self.score - best.score_at(self.run_time);
+            self.hud.bind_mut().update_ghost_delta(Some(delta));
+        }
+    }
+
+    /// Saves the track of the run that just ended if it outlasted the best one.
+    fn keep_best_run(&mut self) {
+        self.ghost.bind_mut().set_run(None);
+
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+
+        if self
+            .best_run
+            .as_ref()
+            .is_some_and(|best| best.time >= recording.time)
+        {
+            return;
+        }
+
+        recording.save();
+        self.best_run = Some(recording);
+    }
+
+    /// Keeps the score of the day's first daily challenge run, which was recorded with no
+    /// score when it started; later ones are practice.
+    fn keep_daily_result(&mut self) {
+        let Some(daily) = self.daily.as_ref().filter(|_| self.daily_attempt) else {
+            return;
+        };
+
+        let result = DailyResult {
+            score: self.stats.score,
+            time: self.stats.time,
+        };
+
+        self.daily_results.update(daily.date, result);
+        self.daily_results.save();
+
+        self.daily_attempt = false;
+    }
+
+    /// A hit in a mode where it isn't lethal: the enemy goes away, the multiplier resets and
+    /// the player carries on where it was.
+    fn shrug_off_hit(
+        &mut self,
+        index: usize,
+        mut enemy: Gd<Node2D>,
+        archetype: StringName,
+        contact: Vector2,
+    ) {
+        let Some(slot) = self.players.get(index) else {
+            return;
+        };
+
+        // The player stopped itself on the hit and disables its collision deferred, so it
+        // has to come back after that.
+        let mut player = slot.player.clone();
+        let position = player.get_position();
+        player.call_deferred("start", &[position.to_variant()]);
+
+        self.stats.hits += 1;
+        self.set_multiplier(1f64);
+
+        self.signals()
+            .player_hit()
+            .emit(&enemy, &archetype, contact);
+        enemy.queue_free();
+
+        self.feedback.bind_mut().burst(contact);
+        self.feedback.bind_mut().add_trauma(0.5f32);
+
+        let mode_info = self.mode_info();
+        self.hud.bind_mut().set_mode_info(mode_info);
+    }
+
+    /// Reminder of the rules of the current mode, shown by the HUD.
+    fn mode_info(&self) -> ModeInfo {
+        match self.mode {
+            GameMode::TimeAttack => ModeInfo::TimeAttack,
+            GameMode::Sprint => ModeInfo::Sprint {
+                target: self.mode.target_score().unwrap_or_default(),
+            },
+            GameMode::Zen => ModeInfo::Zen {
+                hits: self.stats.hits,
+            },
+            _ => ModeInfo::None,
+        }
+    }
+
+    fn skip_countdown(&mut self) {
+        self.starter_timer.stop();
+        self.on_starter_timer_timeout();
+    }
+
+    /// Shortens the spawn interval as the run goes on and feeds how far it has come to the
+    /// music as its intensity. Bosses outlasted push it further along. Also plays a stinger
+    /// at every milestone.
+    fn update_difficulty(&mut self) {
+        let progress = self.spawn_progress();
+
+        let min_interval = self.min_spawn_interval.min(self.base_spawn_interval);
+        let interval =
+            self.base_spawn_interval + (min_interval - self.base_spawn_interval) * progress;
+        self.enemy_timer.set_wait_time(interval.max(0.01f64));
+
+        let mut audio = self.audio.bind_mut();
+        audio.set_intensity(progress as f32);
+
+        if self.milestone_interval > 0f64 && self.run_time >= self.next_milestone {
+            self.next_milestone += self.milestone_interval;
+            audio.play_stinger();
+        }
+    }
+
+    /// How far the run is along the spawn ramp, from 0 at its start to 1 once the spawn
+    /// interval is at its shortest. Drives the interval, the music and the patterns alike.
+    fn spawn_progress(&self) -> f64 {
+        let ramp_time = self.mode.spawn_ramp_time(self.spawn_ramp_time);
+
+        if ramp_time <= 0f64 {
+            return 1f64;
+        }
+
+        (self.run_time / ramp_time + self.difficulty_boost).clamp(0f64, 1f64)
+    }
+
+    fn refresh_score(&mut self) {
+        let score = self
+            .scoring_rules
+            .as_ref()
+            .map(|rules| {
+                let run_time = if self.mode.scores_time() {
+                    self.run_time
+                } else {
+                    0f64
+                };
+                rules.bind().score_for(run_time, self.bonus)
+            })
+            .unwrap_or(self.bonus);
+
+        if score != self.score {
+            self.set_score(score);
+        }
+    }
+
+    fn set_multiplier(&mut self, multiplier: f64) {
+        if multiplier != self.multiplier {
+            self.multiplier = multiplier;
+            self.hud.bind_mut().update_multiplier(multiplier);
+        }
+    }
+
+    fn set_score(&mut self, score: i64) {
+        self.score = score;
+        self.hud.bind_mut().update_score(self.score);
+        self.signals().score_changed().emit(score);
+    }
+
+    /// Where `at`, in the scene's coordinates, is drawn on screen, e.g. for HUD popups.
+    fn to_screen(&self, at: Vector2) -> Vector2 {
+        self.base()
+            .get_viewport()
+            .map_or(at, |viewport| viewport.get_canvas_transform() * at)
+    }
+
+    fn instantiate_enemy(&self) -> Gd<EnemyBase> {
+        self.enemy
+            .as_ref()
+            .and_then(|scene| scene.try_instantiate_as::<EnemyBase>())
+            .unwrap_or_else(|| panic!("{}", tr(TextKey::ErrorInstantiateEnemy)))
+    }
+
+    /// Adds `enemy` to the scene and sets it in motion. Without an `archetype` it keeps the one
+    /// it picked at random. A network host replicates the spawn to its clients.
+    fn place_enemy(
+        &mut self,
+        mut enemy: Gd<EnemyBase>,
+        at: Vector2,
+        velocity: Vector2,
+        archetype: Option<StringName>,
+    ) -> Gd<EnemyBase> {
+        self.base_mut().add_child(&enemy);
+        enemy.set_process_mode(ProcessMode::PAUSABLE);
+        enemy.add_to_group(ENEMY_GROUP);
+
+        if let Some(archetype) = archetype {
+            enemy.bind_mut().set_archetype(archetype);
+        }
+
+        enemy.set_position(at);
+        enemy.set_rotation(velocity.angle());
+        enemy.set_linear_velocity(velocity);
+
+        enemy
+            .signals()
+            .dodged()
+            .connect_other(self, Self::on_enemy_dodged);
+
+        if self.net_role == NetworkRole::Host {
+            let archetype = enemy.bind().get_archetype();
+            self.network
+                .bind_mut()
+                .broadcast_spawn(at, velocity, archetype);
+        }
+
+        self.stats.enemies_spawned += 1;
+        self.signals().enemy_spawned().emit(&enemy);
+        enemy
+    }
+
+    /// `MainScene` keeps processing while the tree is paused so it can resume; the gameplay
+    /// nodes stop with the pause.
+    fn setup_pause_modes(&mut self) {
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+
+        self.player.set_process_mode(ProcessMode::PAUSABLE);
+        self.path_enemy.set_process_mode(ProcessMode::PAUSABLE);
+        self.boss.set_process_mode(ProcessMode::PAUSABLE);
+        self.enemy_timer.set_process_mode(ProcessMode::PAUSABLE);
+        self.starter_timer.set_process_mode(ProcessMode::PAUSABLE);
+    }
+
+    fn connect_network(&mut self) {
+        let network = self.network.clone();
+        self.lobby
+            .signals()
+            .host_requested()
+            .connect_other(&network, |network| {
+                network.host();
+            });
+        self.lobby
+            .signals()
+            .join_requested()
+            .connect_other(&network, |network, address| {
+                network.join(address);
+            });
+        self.lobby
+            .signals()
+            .start_requested()
+            .connect_other(&network, NetworkSession::start_session);
+        self.lobby
+            .signals()
+            .back_requested()
+            .connect_other(&network, NetworkSession::leave);
+
+        let lobby = self.lobby.clone();
+        self.network
+            .signals()
+            .lobby_changed()
+            .connect_other(&lobby, Lobby::on_lobby_changed);
+
+        self.network
+            .signals()
+            .session_started()
+            .connect_other(self, Self::on_session_started);
+        self.network
+            .signals()
+            .session_ended()
+            .connect_other(self, Self::on_session_ended);
+        self.network
+            .signals()
+            .peer_left()
+            .connect_other(self, Self::on_peer_left);
+        self.network
+            .signals()
+            .input_received()
+            .connect_other(self, Self::on_input_received);
+        self.network
+            .signals()
+            .spawn_received()
+            .connect_other(self, Self::on_spawn_received);
+        self.network
+            .signals()
+            .phase_received()
+            .connect_other(self, Self::mirror_phase);
+    }
+
+    /// Starts a network run with one player per seat of `roster`, on the host and on every
+    /// client alike.
+    #[func]
+    fn on_session_started(&mut self, seed: i64, roster: PackedInt64Array) {
+        let local_peer = self
+            .base()
+            .get_multiplayer()
+            .map(|mut multiplayer| multiplayer.get_unique_id() as i64)
+            .unwrap_or(HOST_PEER);
+
+        self.net_role = if local_peer == HOST_PEER {
+            NetworkRole::Host
+        } else {
+            NetworkRole::Client
+        };
+        self.mode = GameMode::Network;
+        self.roster = roster.as_slice().to_vec();
+        self.lobby.bind_mut().close();
+
+        if self.net_role == NetworkRole::Client
+            || self.phase.can_transition_to(GamePhase::Countdown)
+        {
+            self.begin_network_run(seed);
+        }
+    }
+
+    /// The host starts the run itself; a client seats the players and waits for the host's
+    /// phases to drive it.
+    fn begin_network_run(&mut self, seed: i64) {
+        self.next_seed = Some(seed);
+        self.round = 0;
+        self.wins = vec![0; self.player_count()];
+
+        let is_host = self.net_role == NetworkRole::Host;
+        self.hud.bind_mut().set_restart_enabled(is_host);
+
+        if is_host {
+            self.new_game();
+            return;
+        }
+
+        self.stop_run();
+        self.run_time = 0f64;
+        self.set_score(0);
+        self.stats = RunStats::default();
+        self.hud.bind_mut().set_mode(self.mode);
+        self.run_seed = self.next_seed.take().unwrap_or_default();
+        self.setup_players();
+    }
+
+    /// The host went away: back to the title, with the lobby telling why.
+    #[func]
+    fn on_session_ended(&mut self) {
+        if self.net_role != NetworkRole::Client {
+            return;
+        }
+
+        self.stop_run();
+        self.net_role = NetworkRole::Offline;
+        self.roster.clear();
+        self.mirror_phase(GamePhase::Title);
+        self.lobby.bind_mut().open();
+    }
+
+    /// A client left mid-run; its player is out for the rest of it.
+    #[func]
+    fn on_peer_left(&mut self, peer: i64) {
+        let Some(index) = self.roster.iter().position(|seat| *seat == peer) else {
+            return;
+        };
+
+        let Some(slot) = self.players.get_mut(index) else {
+            return;
+        };
+
+        slot.state = PlayerStatus::Out;
+        slot.player.bind_mut().stop();
+        self.hud
+            .bind_mut()
+            .update_player_status(index, PlayerStatus::Out);
+
+        let anyone_alive = self
+            .players
+            .iter()
+            .any(|slot| slot.state == PlayerStatus::Alive);
+
+        if !anyone_alive && matches!(self.phase, GamePhase::Countdown | GamePhase::Playing) {
+            self.game_over();
+        }
+    }
+
+    #[func]
+    fn on_input_received(&mut self, peer: i64, direction: Vector2, dash: bool) {
+        let Some(index) = self.roster.iter().position(|seat| *seat == peer) else {
+            return;
+        };
+
+        if let Some(slot) = self.players.get_mut(index) {
+            slot.player.bind_mut().set_remote_input(direction, dash);
+        }
+    }
+
+    #[func]
+    fn on_spawn_received(&mut self, at: Vector2, velocity: Vector2, archetype: StringName) {
+        if self.net_role != NetworkRole::Client {
+            return;
+        }
+
+        let enemy = self.instantiate_enemy();
+        self.place_enemy(enemy, at, velocity, Some(archetype));
+    }
+
+    /// Follows a phase the host's run entered, without checking the transition rules; the
+    /// host already did.
+    #[func]
+    fn mirror_phase(&mut self, phase: GamePhase) {
+        let previous = self.phase;
+
+        if self.net_role == NetworkRole::Client {
+            match phase {
+                GamePhase::Countdown => {
+                    self.starter_timer
+                        .start_ex()
+                        .time_sec(self.countdown_length.max(0.01f64))
+                        .done();
+                }
+                GamePhase::GameOver => {
+                    self.starter_timer.stop();
+                    self.stats.time = self.run_time;
+                    self.stats.score = self.score;
+                    self.hud
+                        .bind_mut()
+                        .set_summary(&self.stats, &PersonalBests::default(), false);
+                }
+                _ => {}
+            }
+        }
+
+        if previous == phase {
+            return;
+        }
+
+        self.phase = phase;
+        self.signals().phase_changed().emit(previous, phase);
+    }
+
+    /// Shows the host's run on a client: sends the local input and moves every puppet to
+    /// where the interpolated snapshots put it.
+    fn follow_host(&mut self, delta: f64) {
+        if !matches!(self.phase, GamePhase::Countdown | GamePhase::Playing) {
+            return;
+        }
+
+        self.network.bind_mut().send_input();
+
+        let Some(sample) = self.network.bind_mut().sample(delta) else {
+            return;
+        };
+
+        self.run_time = sample.time;
+        self.hud.bind_mut().update_time(self.run_time);
+
+        if sample.score != self.score {
+            self.set_score(sample.score);
+        }
+
+        let mut hud = self.hud.clone();
+
+        for (index, slot) in self.players.iter_mut().enumerate() {
+            let state = match sample.down.get(index).copied().unwrap_or_default() {
+                down if down > 0f32 => PlayerStatus::Down(down as f64),
+                down if down < 0f32 => PlayerStatus::Out,
+                _ => PlayerStatus::Alive,
+            };
+
+            if state != slot.state {
+                slot.player.set_visible(state == PlayerStatus::Alive);
+            }
+
+            slot.state = state;
+            hud.bind_mut().update_player_status(index, state);
+
+            if let Some(position) = sample.positions.get(index) {
+                slot.player.bind_mut().set_puppet_position(*position, delta);
+            }
+        }
+    }
+
+    /// Sends the host's run to the clients. Downed players are sent with the seconds before
+    /// they come back and players who are out with -1.
+    fn send_snapshot(&mut self, delta: f64) {
+        if !matches!(self.phase, GamePhase::Countdown | GamePhase::Playing) {
+            return;
+        }
+
+        let positions = self
+            .players
+            .iter()
+            .map(|slot| slot.player.get_position())
+            .collect::<PackedVector2Array>();
+        let down = self
+            .players
+            .iter()
+            .map(|slot| match slot.state {
+                PlayerStatus::Alive => 0f32,
+                PlayerStatus::Down(remaining) => remaining as f32,
+                PlayerStatus::Out => -1f32,
+            })
+            .collect::<PackedFloat32Array>();
+
+        let (time, score) = (self.run_time, self.score);
+        self.network
+            .bind_mut()
+            .send_snapshot(delta, time, score, positions, down);
+    }
+
+    /// Closes the network session, if any, so the next run is played locally.
+    fn leave_session(&mut self) {
+        if self.net_role == NetworkRole::Offline && self.mode != GameMode::Network {
+            return;
+        }
+
+        self.network.bind_mut().leave();
+        self.net_role = NetworkRole::Offline;
+        self.roster.clear();
+        self.hud.bind_mut().set_restart_enabled(true);
+    }
+
+    fn connect_audio(&mut self) {
+        let mut audio = self.audio.clone();
+
+        self.signals().phase_changed().connect(move |from, to| {
+            let mut audio = audio.bind_mut();
+
+            match to {
+                GamePhase::Countdown => {
+                    audio.duck_music(false);
+                    audio.play_default_music();
+                }
+                GamePhase::Paused => audio.set_music_paused(true),
+                GamePhase::Playing if from == GamePhase::Paused => audio.set_music_paused(false),
+                GamePhase::RoundOver => audio.duck_music(true),
+                GamePhase::GameOver => audio.play_game_over(),
+                GamePhase::Title if from != GamePhase::Title => {
+                    audio.set_music_paused(false);
+                    audio.duck_music(false);
+                    audio.stop_music();
+                }
+                _ => {}
+            }
+        });
     }
 }
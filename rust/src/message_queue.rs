@@ -0,0 +1,131 @@
+use std::collections::VecDeque;
+
+use godot::prelude::*;
+
+/// How a message queued on `HUDBase` treats the one on screen.
+#[derive(
+    GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default,
+)]
+#[godot(via = i64)]
+pub enum MessagePriority {
+    /// Waits until every message before it has been shown.
+    #[default]
+    Normal,
+    /// Replaces the message on screen right away. A preempted normal message is shown again
+    /// afterwards with its remaining time; a preempted priority message is dropped.
+    High,
+}
+
+#[derive(Clone)]
+pub struct QueuedMessage {
+    pub text: String,
+    /// Seconds on screen. Zero or less keeps the message until it is dismissed or preempted.
+    pub duration: f64,
+    pub priority: MessagePriority,
+}
+
+/// Pending messages ordered by priority, first come first served within a priority.
+#[derive(Default)]
+pub struct MessageQueue {
+    pending: VecDeque<QueuedMessage>,
+}
+
+impl MessageQueue {
+    /// Queues `message` behind every pending message of the same or higher priority.
+    pub fn push(&mut self, message: QueuedMessage) {
+        let index = self
+            .pending
+            .iter()
+            .position(|pending| pending.priority < message.priority)
+            .unwrap_or(self.pending.len());
+
+        self.pending.insert(index, message);
+    }
+
+    /// Queues `message` ahead of every pending message of the same or lower priority.
+    pub fn push_front(&mut self, message: QueuedMessage) {
+        let index = self
+            .pending
+            .iter()
+            .position(|pending| pending.priority <= message.priority)
+            .unwrap_or(self.pending.len());
+
+        self.pending.insert(index, message);
+    }
+
+    pub fn pop(&mut self) -> Option<QueuedMessage> {
+        self.pending.pop_front()
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(text: &str, priority: MessagePriority) -> QueuedMessage {
+        QueuedMessage {
+            text: text.to_string(),
+            duration: 1f64,
+            priority,
+        }
+    }
+
+    fn drain(queue: &mut MessageQueue) -> Vec<String> {
+        std::iter::from_fn(|| queue.pop())
+            .map(|message| message.text)
+            .collect()
+    }
+
+    #[test]
+    fn same_priority_is_first_come_first_served() {
+        let mut queue = MessageQueue::default();
+        queue.push(message("a", MessagePriority::Normal));
+        queue.push(message("b", MessagePriority::Normal));
+        queue.push(message("c", MessagePriority::Normal));
+
+        assert_eq!(drain(&mut queue), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn high_priority_goes_before_normal_but_after_other_high() {
+        let mut queue = MessageQueue::default();
+        queue.push(message("normal", MessagePriority::Normal));
+        queue.push(message("high 1", MessagePriority::High));
+        queue.push(message("high 2", MessagePriority::High));
+        queue.push(message("later", MessagePriority::Normal));
+
+        assert_eq!(drain(&mut queue), ["high 1", "high 2", "normal", "later"]);
+    }
+
+    #[test]
+    fn push_front_goes_ahead_of_its_priority_only() {
+        let mut queue = MessageQueue::default();
+        queue.push(message("high", MessagePriority::High));
+        queue.push(message("normal", MessagePriority::Normal));
+        queue.push_front(message("resumed", MessagePriority::Normal));
+
+        assert_eq!(drain(&mut queue), ["high", "resumed", "normal"]);
+    }
+
+    #[test]
+    fn push_front_into_an_empty_queue() {
+        let mut queue = MessageQueue::default();
+        queue.push_front(message("only", MessagePriority::Normal));
+
+        assert_eq!(drain(&mut queue), ["only"]);
+    }
+
+    #[test]
+    fn clear_drops_everything() {
+        let mut queue = MessageQueue::default();
+        queue.push(message("a", MessagePriority::Normal));
+        queue.push(message("b", MessagePriority::High));
+        queue.clear();
+
+        assert!(queue.pop().is_none());
+    }
+}
@@ -0,0 +1,566 @@
+use std::collections::VecDeque;
+
+use godot::{
+    classes::{ENetMultiplayerPeer, Engine, INode, MultiplayerApi, OfflineMultiplayerPeer, Os},
+    global::{Error, randi},
+    prelude::*,
+};
+
+use crate::{
+    game_phase::GamePhase,
+    input_bindings::InputProfile,
+    localization::{TextKey, tr},
+};
+
+/// Peer id Godot always gives the host.
+pub const HOST_PEER: i64 = 1;
+
+/// Most players a session seats, host included.
+const MAX_PLAYERS: usize = 4;
+
+/// Snapshots kept by a client, a little over a second at the default rate.
+const SNAPSHOT_BUFFER: usize = 32;
+
+/// How far, in seconds, the client clock may drift from the host before it is snapped back.
+const MAX_DRIFT: f64 = 0.25;
+
+/// Loopback address offered by the lobby, so two instances on one machine can play.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
+
+/// Side of the connection this instance is on.
+#[derive(GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[godot(via = i64)]
+pub enum NetworkRole {
+    #[default]
+    Offline,
+    /// Runs the simulation: spawning, collisions, scoring and phases.
+    Host,
+    /// Sends its input and shows what the host replicates.
+    Client,
+}
+
+/// State of the host's run at `time`, as received by a client.
+struct Snapshot {
+    time: f64,
+    score: i64,
+    positions: PackedVector2Array,
+    down: PackedFloat32Array,
+}
+
+/// What a client shows on a frame: player positions blended between the two snapshots around
+/// its render time, which trails the host by `NetworkSession::interpolation_delay`.
+pub struct NetworkSample {
+    pub time: f64,
+    pub score: i64,
+    pub positions: Vec<Vector2>,
+    /// Seconds before each player is revived; 0 while alive.
+    pub down: Vec<f32>,
+}
+
+/// LAN sessions over ENet with a host-authoritative simulation. The host seats every
+/// connected peer, replicates the run seed, enemy spawns, phases and player positions, and
+/// clients only send their input. Launching with `-- --host` or `-- --join=ADDRESS` connects
+/// right away, which makes testing with two local instances quick.
+#[derive(GodotClass)]
+#[class(base=Node, tool)]
+pub struct NetworkSession {
+    #[base]
+    base: Base<Node>,
+    #[export]
+    port: i64,
+    /// Clients the host accepts, up to three.
+    #[export]
+    max_clients: i64,
+    /// Snapshots the host sends per second.
+    #[export]
+    snapshot_rate: f64,
+    /// Seconds clients stay behind the host, so there is usually a newer snapshot to blend
+    /// towards even when packets arrive late.
+    #[export]
+    interpolation_delay: f64,
+    role: NetworkRole,
+    status: String,
+    roster: Vec<i64>,
+    snapshots: VecDeque<Snapshot>,
+    render_time: Option<f64>,
+    since_snapshot: f64,
+}
+
+#[godot_api]
+impl INode for NetworkSession {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            port: 24565,
+            max_clients: 3,
+            snapshot_rate: 20f64,
+            interpolation_delay: 0.1f64,
+            role: NetworkRole::Offline,
+            status: String::new(),
+            roster: Vec::new(),
+            snapshots: VecDeque::new(),
+            render_time: None,
+            since_snapshot: 0f64,
+        }
+    }
+
+    fn ready(&mut self) {
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        let Some(multiplayer) = self.multiplayer() else {
+            return;
+        };
+
+        multiplayer
+            .signals()
+            .peer_connected()
+            .connect_other(self, Self::on_peer_connected);
+        multiplayer
+            .signals()
+            .peer_disconnected()
+            .connect_other(self, Self::on_peer_disconnected);
+        multiplayer
+            .signals()
+            .connected_to_server()
+            .connect_other(self, Self::on_connected_to_server);
+        multiplayer
+            .signals()
+            .connection_failed()
+            .connect_other(self, Self::on_connection_failed);
+        multiplayer
+            .signals()
+            .server_disconnected()
+            .connect_other(self, Self::on_server_disconnected);
+    }
+}
+
+#[godot_api]
+impl NetworkSession {
+    /// Emitted whenever the connected peers or the connection status change. `peers` starts
+    /// with the local peer and is empty while offline.
+    #[signal]
+    pub fn lobby_changed(peers: PackedInt64Array, local_peer: i64, status: GString);
+
+    /// Emitted on every peer when the host starts a run. `roster` lists the peer of each
+    /// player seat, the host first.
+    #[signal]
+    pub fn session_started(seed: i64, roster: PackedInt64Array);
+
+    /// Emitted on a client when the connection to the host is lost.
+    #[signal]
+    pub fn session_ended();
+
+    /// Emitted on the host when a seated peer disconnects.
+    #[signal]
+    pub fn peer_left(peer: i64);
+
+    /// Emitted on the host for every input a client sends.
+    #[signal]
+    pub fn input_received(peer: i64, direction: Vector2, dash: bool);
+
+    /// Emitted on a client for every enemy the host spawns.
+    #[signal]
+    pub fn spawn_received(at: Vector2, velocity: Vector2, archetype: StringName);
+
+    /// Emitted on a client for every phase the host's run enters.
+    #[signal]
+    pub fn phase_received(phase: GamePhase);
+
+    /// Starts hosting on `port`. Returns whether the server could be created.
+    #[func]
+    pub fn host(&mut self) -> bool {
+        self.leave();
+
+        let mut peer = ENetMultiplayerPeer::new_gd();
+        let result = peer
+            .create_server_ex(self.port as i32)
+            .max_clients(self.max_clients.clamp(1, MAX_PLAYERS as i64 - 1) as i32)
+            .done();
+
+        if result != Error::OK {
+            self.set_status(tr(TextKey::LobbyFailed));
+            return false;
+        }
+
+        self.set_peer(peer.upcast());
+        self.role = NetworkRole::Host;
+        self.set_status(format!("{} {}", tr(TextKey::LobbyHosting), self.port));
+        true
+    }
+
+    /// Connects to a host at `address`. Returns whether the connection attempt started; the
+    /// outcome arrives through `lobby_changed`.
+    #[func]
+    pub fn join(&mut self, address: GString) -> bool {
+        self.leave();
+
+        let address = match address.to_string().trim() {
+            "" => DEFAULT_ADDRESS.to_string(),
+            address => address.to_string(),
+        };
+
+        let mut peer = ENetMultiplayerPeer::new_gd();
+        if peer.create_client(&address, self.port as i32) != Error::OK {
+            self.set_status(tr(TextKey::LobbyFailed));
+            return false;
+        }
+
+        self.set_peer(peer.upcast());
+        self.role = NetworkRole::Client;
+        self.set_status(tr(TextKey::LobbyConnecting));
+        true
+    }
+
+    /// Closes the connection, if any, and goes back offline.
+    #[func]
+    pub fn leave(&mut self) {
+        if self.role == NetworkRole::Offline {
+            return;
+        }
+
+        self.close_peer();
+        self.set_status(String::new());
+    }
+
+    #[func]
+    pub fn get_role(&self) -> NetworkRole {
+        self.role
+    }
+
+    /// Peer id of this instance, `HOST_PEER` while offline.
+    #[func]
+    pub fn get_local_peer(&self) -> i64 {
+        if self.role == NetworkRole::Offline {
+            return HOST_PEER;
+        }
+
+        self.multiplayer()
+            .map(|mut multiplayer| multiplayer.get_unique_id() as i64)
+            .unwrap_or(HOST_PEER)
+    }
+
+    /// Hosts or joins when the game was launched with `--host` or `--join[=ADDRESS]` after
+    /// `--`. Returns whether either was asked for.
+    pub fn connect_from_command_line(&mut self) -> bool {
+        let args = Os::singleton().get_cmdline_user_args();
+
+        for arg in args.as_slice() {
+            let arg = arg.to_string();
+
+            if arg == "--host" {
+                self.host();
+                return true;
+            }
+
+            if let Some(rest) = arg.strip_prefix("--join") {
+                let address = rest.strip_prefix('=').unwrap_or(DEFAULT_ADDRESS);
+                self.join(address.into());
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Seats the connected peers and tells every client to start a run with `seed`. The host
+    /// itself starts on `session_started`.
+    #[func]
+    pub fn start_session(&mut self) {
+        let seed = randi();
+
+        if let Some(roster) = self.announce_session(seed) {
+            self.signals().session_started().emit(seed, &roster);
+        }
+    }
+
+    /// Tells every client to start a run with `seed` and returns the seats, or `None` unless
+    /// hosting. Used directly by the host to restart after a game over.
+    pub fn announce_session(&mut self, seed: i64) -> Option<PackedInt64Array> {
+        if self.role != NetworkRole::Host {
+            return None;
+        }
+
+        let mut peers = self
+            .multiplayer()
+            .map(|mut multiplayer| multiplayer.get_peers().as_slice().to_vec())
+            .unwrap_or_default()
+            .into_iter()
+            .map(i64::from)
+            .collect::<Vec<_>>();
+        peers.sort_unstable();
+
+        self.roster = std::iter::once(HOST_PEER)
+            .chain(peers)
+            .take(MAX_PLAYERS)
+            .collect();
+        self.since_snapshot = 0f64;
+
+        let roster = self.roster.iter().copied().collect::<PackedInt64Array>();
+        self.base_mut()
+            .rpc("receive_session", &[seed.to_variant(), roster.to_variant()]);
+
+        Some(roster)
+    }
+
+    pub fn broadcast_phase(&mut self, phase: GamePhase) {
+        if self.role == NetworkRole::Host {
+            self.base_mut().rpc("receive_phase", &[phase.to_variant()]);
+        }
+    }
+
+    pub fn broadcast_spawn(&mut self, at: Vector2, velocity: Vector2, archetype: StringName) {
+        if self.role == NetworkRole::Host {
+            self.base_mut().rpc(
+                "receive_spawn",
+                &[
+                    at.to_variant(),
+                    velocity.to_variant(),
+                    archetype.to_variant(),
+                ],
+            );
+        }
+    }
+
+    /// Sends the state of the run to the clients, at most `snapshot_rate` times per second.
+    /// `down` holds the seconds before each player is revived, 0 while alive.
+    pub fn send_snapshot(
+        &mut self,
+        delta: f64,
+        time: f64,
+        score: i64,
+        positions: PackedVector2Array,
+        down: PackedFloat32Array,
+    ) {
+        if self.role != NetworkRole::Host {
+            return;
+        }
+
+        self.since_snapshot += delta;
+
+        if self.snapshot_rate > 0f64 && self.since_snapshot < 1f64 / self.snapshot_rate {
+            return;
+        }
+
+        self.since_snapshot = 0f64;
+        self.base_mut().rpc(
+            "receive_snapshot",
+            &[
+                time.to_variant(),
+                score.to_variant(),
+                positions.to_variant(),
+                down.to_variant(),
+            ],
+        );
+    }
+
+    /// Sends the local player's input to the host.
+    pub fn send_input(&mut self) {
+        if self.role != NetworkRole::Client {
+            return;
+        }
+
+        let profile = InputProfile::Shared;
+        let direction = profile.movement();
+        let dash = profile.dash_just_pressed();
+
+        self.base_mut().rpc_id(
+            HOST_PEER,
+            "receive_input",
+            &[direction.to_variant(), dash.to_variant()],
+        );
+    }
+
+    /// Advances the client's render clock by `delta` and blends the snapshots around it.
+    /// Returns `None` until the first snapshot arrives.
+    pub fn sample(&mut self, delta: f64) -> Option<NetworkSample> {
+        let latest = self.snapshots.back()?.time;
+        let target = latest - self.interpolation_delay.max(0f64);
+
+        let render_time = match self.render_time {
+            Some(time) if (time + delta - target).abs() <= MAX_DRIFT => (time + delta).min(latest),
+            _ => target,
+        };
+        self.render_time = Some(render_time);
+
+        while self.snapshots.len() > 2 && self.snapshots[1].time <= render_time {
+            self.snapshots.pop_front();
+        }
+
+        let from = self.snapshots.front()?;
+        let to = self.snapshots.get(1).unwrap_or(from);
+
+        let weight = if to.time > from.time {
+            ((render_time - from.time) / (to.time - from.time)).clamp(0f64, 1f64) as f32
+        } else {
+            1f32
+        };
+
+        let positions = to
+            .positions
+            .as_slice()
+            .iter()
+            .enumerate()
+            .map(|(index, end)| match from.positions.get(index) {
+                Some(start) => start.lerp(*end, weight),
+                None => *end,
+            })
+            .collect();
+
+        Some(NetworkSample {
+            time: from.time + (to.time - from.time) * weight as f64,
+            score: to.score,
+            positions,
+            down: to.down.as_slice().to_vec(),
+        })
+    }
+
+    #[rpc(authority, reliable)]
+    fn receive_session(&mut self, seed: i64, roster: PackedInt64Array) {
+        self.roster = roster.as_slice().to_vec();
+        self.snapshots.clear();
+        self.render_time = None;
+        self.signals().session_started().emit(seed, &roster);
+    }
+
+    #[rpc(authority, reliable)]
+    fn receive_phase(&mut self, phase: GamePhase) {
+        self.signals().phase_received().emit(phase);
+    }
+
+    #[rpc(authority, reliable)]
+    fn receive_spawn(&mut self, at: Vector2, velocity: Vector2, archetype: StringName) {
+        self.signals()
+            .spawn_received()
+            .emit(at, velocity, &archetype);
+    }
+
+    #[rpc(authority, unreliable_ordered)]
+    fn receive_snapshot(
+        &mut self,
+        time: f64,
+        score: i64,
+        positions: PackedVector2Array,
+        down: PackedFloat32Array,
+    ) {
+        // A new run starts its clock again; anything buffered belongs to the previous one.
+        if self
+            .snapshots
+            .back()
+            .is_some_and(|latest| time < latest.time)
+        {
+            self.snapshots.clear();
+            self.render_time = None;
+        }
+
+        self.snapshots.push_back(Snapshot {
+            time,
+            score,
+            positions,
+            down,
+        });
+
+        while self.snapshots.len() > SNAPSHOT_BUFFER {
+            self.snapshots.pop_front();
+        }
+    }
+
+    #[rpc(any_peer, unreliable_ordered)]
+    fn receive_input(&mut self, direction: Vector2, dash: bool) {
+        if self.role != NetworkRole::Host {
+            return;
+        }
+
+        let Some(peer) = self
+            .multiplayer()
+            .map(|mut multiplayer| multiplayer.get_remote_sender_id() as i64)
+        else {
+            return;
+        };
+
+        self.signals().input_received().emit(peer, direction, dash);
+    }
+
+    #[func]
+    fn on_peer_connected(&mut self, _peer: i64) {
+        self.emit_lobby_changed();
+    }
+
+    #[func]
+    fn on_peer_disconnected(&mut self, peer: i64) {
+        self.emit_lobby_changed();
+
+        if self.role == NetworkRole::Host && self.roster.contains(&peer) {
+            self.signals().peer_left().emit(peer);
+        }
+    }
+
+    #[func]
+    fn on_connected_to_server(&mut self) {
+        self.set_status(tr(TextKey::LobbyWaiting));
+    }
+
+    #[func]
+    fn on_connection_failed(&mut self) {
+        self.close_peer();
+        self.set_status(tr(TextKey::LobbyFailed));
+    }
+
+    #[func]
+    fn on_server_disconnected(&mut self) {
+        self.close_peer();
+        self.set_status(tr(TextKey::LobbyDisconnected));
+        self.signals().session_ended().emit();
+    }
+
+    fn set_status(&mut self, status: String) {
+        self.status = status;
+        self.emit_lobby_changed();
+    }
+
+    fn emit_lobby_changed(&mut self) {
+        let mut peers = PackedInt64Array::new();
+
+        if self.role != NetworkRole::Offline {
+            let local = self.get_local_peer();
+            peers.push(local);
+
+            let mut others = self
+                .multiplayer()
+                .map(|mut multiplayer| multiplayer.get_peers().as_slice().to_vec())
+                .unwrap_or_default();
+            others.sort_unstable();
+            peers.extend(others.into_iter().map(i64::from));
+        }
+
+        let local = self.get_local_peer();
+        let status = GString::from(&self.status);
+        self.signals().lobby_changed().emit(&peers, local, &status);
+    }
+
+    fn set_peer(&mut self, peer: Gd<godot::classes::MultiplayerPeer>) {
+        if let Some(mut multiplayer) = self.multiplayer() {
+            multiplayer.set_multiplayer_peer(&peer);
+        }
+    }
+
+    fn close_peer(&mut self) {
+        if let Some(mut multiplayer) = self.multiplayer() {
+            if let Some(mut peer) = multiplayer.get_multiplayer_peer() {
+                peer.close();
+            }
+
+            multiplayer.set_multiplayer_peer(&OfflineMultiplayerPeer::new_gd());
+        }
+
+        self.role = NetworkRole::Offline;
+        self.roster.clear();
+        self.snapshots.clear();
+        self.render_time = None;
+    }
+
+    fn multiplayer(&self) -> Option<Gd<MultiplayerApi>> {
+        self.base().get_multiplayer()
+    }
+}
@@ -1,14 +1,55 @@
 use core::fmt;
 
 use godot::{
-    classes::{AnimatedSprite2D, Area2D, CollisionShape2D, Engine, GpuParticles2D, IArea2D, Input},
+    classes::{
+        AnimatedSprite2D, Area2D, CircleShape2D, CollisionShape2D, Engine, GpuParticles2D, IArea2D,
+    },
     prelude::*,
 };
 
+use crate::{
+    enemy::EnemyBase,
+    input_bindings::{self, InputProfile},
+};
+
+/// Tint of each local player, in seat order. The first one leaves the sprite untouched.
+pub const PLAYER_TINTS: [Color; 4] = [
+    Color::from_rgb(1f32, 1f32, 1f32),
+    Color::from_rgb(0.55f32, 0.8f32, 1f32),
+    Color::from_rgb(1f32, 0.7f32, 0.4f32),
+    Color::from_rgb(0.6f32, 1f32, 0.6f32),
+];
+
+/// Where a player stands in a run with several players.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PlayerStatus {
+    Alive,
+    /// Downed, coming back after this many more seconds while a partner is alive.
+    Down(f64),
+    /// Eliminated until the next round.
+    Out,
+}
+
+/// Where a player's movement comes from.
+#[derive(GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[godot(via = i64)]
+pub enum PlayerControl {
+    /// The actions of its `InputProfile`.
+    #[default]
+    Local,
+    /// The last input received through `set_remote_input`, for players of network clients
+    /// simulated by the host.
+    Remote,
+    /// Nothing: a network client places it where the host says and never lets it collide.
+    Puppet,
+}
+
 enum PlayerChild {
     AnimatedSprite2D,
     CollisionShape2D,
     Particles2D,
+    GrazeArea,
+    GrazeShape,
 }
 
 impl fmt::Display for PlayerChild {
@@ -17,6 +58,8 @@ impl fmt::Display for PlayerChild {
             PlayerChild::AnimatedSprite2D => write!(f, "AnimatedSprite2D"),
             PlayerChild::CollisionShape2D => write!(f, "CollisionShape2D"),
             PlayerChild::Particles2D => write!(f, "Rastro"),
+            PlayerChild::GrazeArea => write!(f, "GrazeArea"),
+            PlayerChild::GrazeShape => write!(f, "GrazeArea/CollisionShape2D"),
         }
     }
 }
@@ -28,10 +71,39 @@ pub struct PlayerBase {
     base: Base<Area2D>,
     #[export]
     speed: i32,
+    /// Actions the player is steered with. Local multiplayer gives every player its own.
+    #[export]
+    pub input_profile: InputProfile,
+    #[export]
+    pub control: PlayerControl,
+    /// Radius of the ring around the hurtbox where passing enemies count as a graze.
+    #[export]
+    graze_radius: f32,
+    /// Speed multiplier while dashing.
+    #[export]
+    dash_multiplier: f32,
+    /// Seconds a dash lasts.
+    #[export]
+    dash_duration: f64,
+    /// Seconds after a dash starts before the next one is allowed.
+    #[export]
+    dash_cooldown: f64,
+    /// Speed, in pixels per second, at which overlapping players push each other apart.
+    /// 0 lets players pass through each other.
+    #[export]
+    #[var(get, set = set_push_strength)]
+    push_strength: f32,
+    dash_remaining: f64,
+    dash_cooldown_remaining: f64,
+    remote_direction: Vector2,
+    remote_dash: bool,
     screen_size: Vector2,
+    grazing: Vec<InstanceId>,
     animated_sprite: OnReady<Gd<AnimatedSprite2D>>,
     collision_shape: OnReady<Gd<CollisionShape2D>>,
     particles: OnReady<Gd<GpuParticles2D>>,
+    graze_area: OnReady<Gd<Area2D>>,
+    graze_shape: OnReady<Gd<CollisionShape2D>>,
 }
 
 #[godot_api]
@@ -40,10 +112,24 @@ impl IArea2D for PlayerBase {
         Self {
             base,
             speed: 400,
+            input_profile: InputProfile::Shared,
+            control: PlayerControl::Local,
+            graze_radius: 90f32,
+            dash_multiplier: 2.5f32,
+            dash_duration: 0.15f64,
+            dash_cooldown: 1f64,
+            push_strength: 0f32,
+            dash_remaining: 0f64,
+            dash_cooldown_remaining: 0f64,
+            remote_direction: Vector2::ZERO,
+            remote_dash: false,
             screen_size: Vector2::ZERO,
+            grazing: Vec::new(),
             animated_sprite: OnReady::from_node(&PlayerChild::AnimatedSprite2D.to_string()),
             collision_shape: OnReady::from_node(&PlayerChild::CollisionShape2D.to_string()),
             particles: OnReady::from_node(&PlayerChild::Particles2D.to_string()),
+            graze_area: OnReady::from_node(&PlayerChild::GrazeArea.to_string()),
+            graze_shape: OnReady::from_node(&PlayerChild::GrazeShape.to_string()),
         }
     }
 
@@ -77,6 +163,34 @@ impl IArea2D for PlayerBase {
                 particles.set_owner(self.base().to_godot());
                 particles
             });
+
+        let mut graze_area = self
+            .base()
+            .try_get_node_as::<Area2D>(&PlayerChild::GrazeArea.to_string())
+            .unwrap_or_else(|| {
+                let mut area = Area2D::new_alloc();
+                area.set_name(&PlayerChild::GrazeArea.to_string());
+                area.set_collision_layer(0);
+                let mask = self.base().get_collision_mask();
+                area.set_collision_mask(mask);
+                self.base_mut().add_child(&area);
+                area.set_owner(self.base().to_godot());
+                area
+            });
+
+        if graze_area
+            .try_get_node_as::<CollisionShape2D>(&PlayerChild::CollisionShape2D.to_string())
+            .is_none()
+        {
+            let mut shape = CircleShape2D::new_gd();
+            shape.set_radius(self.graze_radius);
+
+            let mut collision_shape = CollisionShape2D::new_alloc();
+            collision_shape.set_name(&PlayerChild::CollisionShape2D.to_string());
+            collision_shape.set_shape(&shape);
+            graze_area.add_child(&collision_shape);
+            collision_shape.set_owner(self.base().to_godot());
+        }
     }
 
     fn ready(&mut self) {
@@ -86,6 +200,26 @@ impl IArea2D for PlayerBase {
         self.signals()
             .body_entered()
             .connect_self(Self::on_player_body_entered);
+
+        self.graze_area
+            .signals()
+            .body_entered()
+            .connect_other(self, Self::on_graze_body_entered);
+
+        self.graze_area
+            .signals()
+            .body_exited()
+            .connect_other(self, Self::on_graze_body_exited);
+
+        if !Engine::singleton().is_editor_hint() {
+            input_bindings::ensure_registered();
+
+            // A shape saved in the scene is shared by every instance, so each player gets its
+            // own, sized from `graze_radius`.
+            let mut shape = CircleShape2D::new_gd();
+            shape.set_radius(self.graze_radius);
+            self.graze_shape.set_shape(&shape);
+        }
     }
 
     fn process(&mut self, delta: f64) {
@@ -94,34 +228,167 @@ impl IArea2D for PlayerBase {
             return;
         }
 
+        // Puppets are moved from outside, through `set_puppet_position`.
+        if self.control == PlayerControl::Puppet {
+            return;
+        }
+
         let mut velocity = self.get_input_velocity();
+        self.update_dash(velocity, delta);
 
         if velocity.length() > 0f32 {
             velocity = velocity.normalized() * self.speed as f32;
+
+            if self.dash_remaining > 0f64 {
+                velocity *= self.dash_multiplier;
+            }
         }
 
         self.update_animation(velocity);
-        self.set_movement(velocity, delta);
+
+        let push = self.get_push_velocity();
+        self.set_movement(velocity + push, delta);
     }
 }
 
 #[godot_api]
 impl PlayerBase {
+    /// Emitted when a body touches the hurtbox. `archetype` is the enemy's archetype when
+    /// `enemy` is an `EnemyBase` (empty otherwise) and `contact` is the estimated point of
+    /// collision, halfway between both centres.
     #[signal]
-    pub fn hit();
+    pub fn hit(enemy: Gd<Node2D>, archetype: StringName, contact: Vector2);
+
+    /// Emitted when `enemy` leaves the graze ring without having touched the hurtbox.
+    /// `at` is where it left the ring.
+    #[signal]
+    pub fn grazed(enemy: Gd<Node2D>, at: Vector2);
 
     #[func]
-    pub fn on_player_body_entered(&mut self, _body: Gd<Node2D>) {
-        self.base_mut().hide();
-        self.signals().hit().emit();
-        self.collision_shape
-            .set_deferred("disabled", &true.to_variant());
+    pub fn on_player_body_entered(&mut self, body: Gd<Node2D>) {
+        let archetype = body
+            .clone()
+            .try_cast::<EnemyBase>()
+            .map(|enemy| enemy.bind().get_archetype())
+            .unwrap_or_default();
+        let contact = (self.base().get_global_position() + body.get_global_position()) / 2f32;
+
+        self.stop();
+        self.signals().hit().emit(&body, &archetype, contact);
+    }
+
+    #[func]
+    fn on_graze_body_entered(&mut self, body: Gd<Node2D>) {
+        if self.base().is_visible() {
+            self.grazing.push(body.instance_id());
+        }
+    }
+
+    #[func]
+    fn on_graze_body_exited(&mut self, body: Gd<Node2D>) {
+        let Some(index) = self.grazing.iter().position(|id| *id == body.instance_id()) else {
+            return;
+        };
+
+        self.grazing.swap_remove(index);
+
+        // Enemies freed inside the ring leave it too, without having been dodged.
+        if self.base().is_visible() && !body.is_queued_for_deletion() {
+            let at = body.get_global_position();
+            self.signals().grazed().emit(&body, at);
+        }
     }
 
+    /// The sprite the player is drawn with, e.g. for a ghost to look the same.
+    pub fn get_sprite(&self) -> Gd<AnimatedSprite2D> {
+        self.animated_sprite.clone()
+    }
+
+    /// Places the player at `position`, shows it and re-enables its collision. Puppets stay
+    /// without collision.
+    #[func]
     pub fn start(&mut self, position: Vector2) {
         self.base_mut().set_position(position);
         self.base_mut().show();
-        self.collision_shape.set_disabled(false);
+        self.grazing.clear();
+        self.dash_remaining = 0f64;
+        self.dash_cooldown_remaining = 0f64;
+        self.remote_direction = Vector2::ZERO;
+        self.remote_dash = false;
+
+        let disabled = self.control == PlayerControl::Puppet;
+        self.collision_shape.set_disabled(disabled);
+        self.graze_shape.set_disabled(disabled);
+    }
+
+    /// Input of a `PlayerControl::Remote` player: its movement, each axis from -1 to 1, and
+    /// whether dash was pressed since the last input.
+    #[func]
+    pub fn set_remote_input(&mut self, direction: Vector2, dash: bool) {
+        self.remote_direction = direction.limit_length(Some(1f32));
+        self.remote_dash |= dash;
+    }
+
+    /// Moves a `PlayerControl::Puppet` to `position`, animating it as if it had walked there
+    /// over `delta` seconds.
+    pub fn set_puppet_position(&mut self, position: Vector2, delta: f64) {
+        let velocity = if delta > 0f64 {
+            (position - self.base().get_position()) / delta as f32
+        } else {
+            Vector2::ZERO
+        };
+
+        self.update_animation(velocity);
+        self.base_mut().set_position(position);
+    }
+
+    /// Also makes the hurtbox watch the player's own collision layer, since pushing relies on
+    /// players seeing each other's `Area2D` overlap.
+    #[func]
+    pub fn set_push_strength(&mut self, strength: f32) {
+        self.push_strength = strength.max(0f32);
+
+        let layer = self.base().get_collision_layer();
+        let mask = self.base().get_collision_mask();
+        let mask = if self.push_strength > 0f32 {
+            mask | layer
+        } else {
+            mask & !layer
+        };
+        self.base_mut().set_collision_mask(mask);
+    }
+
+    /// Hides the player and disables its collision until the next `start`.
+    #[func]
+    pub fn stop(&mut self) {
+        self.base_mut().hide();
+        self.grazing.clear();
+        self.collision_shape
+            .set_deferred("disabled", &true.to_variant());
+        self.graze_shape
+            .set_deferred("disabled", &true.to_variant());
+    }
+
+    /// Starts a dash when `dash` is pressed while moving and off cooldown, and counts down
+    /// the running dash and the cooldown.
+    fn update_dash(&mut self, direction: Vector2, delta: f64) {
+        self.dash_remaining = (self.dash_remaining - delta).max(0f64);
+        self.dash_cooldown_remaining = (self.dash_cooldown_remaining - delta).max(0f64);
+
+        let pressed = match self.control {
+            PlayerControl::Local => self.input_profile.dash_just_pressed(),
+            PlayerControl::Remote => std::mem::take(&mut self.remote_dash),
+            PlayerControl::Puppet => false,
+        };
+
+        if pressed
+            && self.base().is_visible()
+            && direction.length() > 0f32
+            && self.dash_cooldown_remaining <= 0f64
+        {
+            self.dash_remaining = self.dash_duration;
+            self.dash_cooldown_remaining = self.dash_cooldown;
+        }
     }
 
     fn update_animation(&mut self, velocity: Vector2) {
@@ -173,12 +440,32 @@ impl PlayerBase {
         self.base_mut().set_position(new_position_limit_viewport);
     }
 
-    fn get_input_velocity(&self) -> Vector2 {
-        let input = Input::singleton();
+    fn get_push_velocity(&self) -> Vector2 {
+        if self.push_strength <= 0f32 || !self.base().is_visible() {
+            return Vector2::ZERO;
+        }
+
+        let position = self.base().get_global_position();
 
-        Vector2::new(
-            input.get_action_strength("ui_right") - input.get_action_strength("ui_left"),
-            input.get_action_strength("ui_down") - input.get_action_strength("ui_up"),
-        )
+        self.base()
+            .get_overlapping_areas()
+            .iter_shared()
+            .filter_map(|area| area.try_cast::<PlayerBase>().ok())
+            .filter(|other| other.is_visible())
+            .map(|other| {
+                (position - other.get_global_position())
+                    .try_normalized()
+                    .unwrap_or(Vector2::RIGHT)
+                    * self.push_strength
+            })
+            .fold(Vector2::ZERO, |total, push| total + push)
+    }
+
+    fn get_input_velocity(&self) -> Vector2 {
+        match self.control {
+            PlayerControl::Local => self.input_profile.movement(),
+            PlayerControl::Remote => self.remote_direction,
+            PlayerControl::Puppet => Vector2::ZERO,
+        }
     }
 }
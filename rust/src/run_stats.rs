@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use godot::{classes::ConfigFile, global::Error, prelude::*};
+
+use crate::{
+    game_mode::GameMode,
+    localization::{TextKey, tr},
+};
+
+const PERSONAL_BESTS_PATH: &str = "user://stats.cfg";
+const PERSONAL_BESTS_SECTION: &str = "best";
+
+/// What happened during a single run, collected by `MainScene`.
+#[derive(Clone, Default)]
+pub struct RunStats {
+    pub time: f64,
+    pub score: i64,
+    pub enemies_spawned: i64,
+    pub enemies_dodged: i64,
+    pub grazes: i64,
+    /// Hits taken without losing the run, in zen.
+    pub hits: i64,
+    /// Whether the run reached the goal of its mode, e.g. the end of a time attack.
+    pub cleared: bool,
+    /// Animation name of the enemy that ended the run, e.g. `flying`.
+    pub death_archetype: Option<StringName>,
+    pub death_position: Option<Vector2>,
+}
+
+impl RunStats {
+    pub fn to_dictionary(&self) -> VarDictionary {
+        let mut stats = VarDictionary::new();
+        stats.set("time", self.time);
+        stats.set("score", self.score);
+        stats.set("enemies_spawned", self.enemies_spawned);
+        stats.set("enemies_dodged", self.enemies_dodged);
+        stats.set("grazes", self.grazes);
+        stats.set("hits", self.hits);
+        stats.set("cleared", self.cleared);
+
+        if let Some(archetype) = &self.death_archetype {
+            stats.set("death_archetype", archetype.clone());
+        }
+
+        if let Some(position) = self.death_position {
+            stats.set("death_position", position);
+        }
+
+        stats
+    }
+}
+
+/// Best values ever reached in a mode, each tracked on its own.
+#[derive(Clone, Default)]
+pub struct PersonalBests {
+    /// Longest run, or the fastest cleared one when `GameMode::fastest_time_wins`.
+    pub time: f64,
+    pub score: i64,
+    pub enemies_dodged: i64,
+    pub grazes: i64,
+}
+
+impl PersonalBests {
+    /// Raises every best beaten by `stats`, a run of `mode`. Returns whether any record was
+    /// broken.
+    pub fn record(&mut self, stats: &RunStats, mode: GameMode) -> bool {
+        let previous = self.clone();
+
+        self.time = if !mode.fastest_time_wins() {
+            self.time.max(stats.time)
+        } else if stats.cleared && (self.time <= 0f64 || stats.time < self.time) {
+            stats.time
+        } else {
+            self.time
+        };
+        self.score = self.score.max(stats.score);
+        self.enemies_dodged = self.enemies_dodged.max(stats.enemies_dodged);
+        self.grazes = self.grazes.max(stats.grazes);
+
+        self.time != previous.time
+            || self.score > previous.score
+            || self.enemies_dodged > previous.enemies_dodged
+            || self.grazes > previous.grazes
+    }
+}
+
+/// Personal bests of every mode with a high-score table, persisted in `user://stats.cfg`
+/// with one section per mode.
+#[derive(Clone, Default)]
+pub struct HighScores {
+    tables: HashMap<GameMode, PersonalBests>,
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        let mut config = ConfigFile::new_gd();
+
+        if config.load(PERSONAL_BESTS_PATH) != Error::OK {
+            return Self::default();
+        }
+
+        let tables = GameMode::ALL
+            .into_iter()
+            .filter(|mode| mode.has_high_scores())
+            .map(|mode| {
+                let section = Self::section(mode);
+                let read = |key: &str| {
+                    config
+                        .get_value_ex(&section, key)
+                        .default(&0.to_variant())
+                        .done()
+                };
+
+                let bests = PersonalBests {
+                    time: read("time").try_to().unwrap_or_default(),
+                    score: read("score").try_to().unwrap_or_default(),
+                    enemies_dodged: read("enemies_dodged").try_to().unwrap_or_default(),
+                    grazes: read("grazes").try_to().unwrap_or_default(),
+                };
+                (mode, bests)
+            })
+            .collect();
+
+        Self { tables }
+    }
+
+    pub fn save(&self) {
+        let mut config = ConfigFile::new_gd();
+
+        for (mode, bests) in &self.tables {
+            let section = Self::section(*mode);
+            config.set_value(&section, "time", &bests.time.to_variant());
+            config.set_value(&section, "score", &bests.score.to_variant());
+            config.set_value(
+                &section,
+                "enemies_dodged",
+                &bests.enemies_dodged.to_variant(),
+            );
+            config.set_value(&section, "grazes", &bests.grazes.to_variant());
+        }
+
+        if config.save(PERSONAL_BESTS_PATH) != Error::OK {
+            godot_warn!("{}", tr(TextKey::ErrorSaveStats));
+        }
+    }
+
+    /// Bests of `mode`, all zero before its first run.
+    pub fn get(&self, mode: GameMode) -> PersonalBests {
+        self.tables.get(&mode).cloned().unwrap_or_default()
+    }
+
+    /// Raises the bests of `mode` beaten by `stats`. Returns whether any record was broken;
+    /// modes without a table never break one.
+    pub fn record(&mut self, mode: GameMode, stats: &RunStats) -> bool {
+        if !mode.has_high_scores() {
+            return false;
+        }
+
+        self.tables.entry(mode).or_default().record(stats, mode)
+    }
+
+    /// Section of `mode`. Endless keeps the one its bests had before there were other modes.
+    fn section(mode: GameMode) -> String {
+        match mode {
+            GameMode::Endless => PERSONAL_BESTS_SECTION.to_string(),
+            mode => format!("{PERSONAL_BESTS_SECTION}_{}", mode.key()),
+        }
+    }
+}
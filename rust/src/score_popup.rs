@@ -0,0 +1,65 @@
+use godot::{
+    classes::{ILabel, Label, node::AutoTranslateMode},
+    global::HorizontalAlignment,
+    prelude::*,
+};
+
+/// Floating text such as "+10" that rises from where it was spawned, fades out and frees itself.
+#[derive(GodotClass)]
+#[class(base=Label)]
+pub struct ScorePopup {
+    #[base]
+    base: Base<Label>,
+    /// Distance in pixels the popup travels upwards.
+    #[export]
+    rise: f32,
+    #[export]
+    duration: f64,
+}
+
+#[godot_api]
+impl ILabel for ScorePopup {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            rise: 48f32,
+            duration: 0.8f64,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut()
+            .set_horizontal_alignment(HorizontalAlignment::CENTER);
+        self.base_mut()
+            .set_auto_translate_mode(AutoTranslateMode::DISABLED);
+
+        let target = self.base().get_position() + Vector2::UP * self.rise;
+        let duration = self.duration;
+        let this = self.to_gd();
+
+        let Some(mut tween) = self.base_mut().create_tween() else {
+            self.base_mut().queue_free();
+            return;
+        };
+
+        tween.set_parallel();
+        tween.tween_property(&this, "position", &target.to_variant(), duration);
+        tween.tween_property(&this, "modulate:a", &0f32.to_variant(), duration);
+        tween.chain();
+        tween.tween_callback(&Callable::from_object_method(&this, "queue_free"));
+    }
+}
+
+#[godot_api]
+impl ScorePopup {
+    /// Creates a popup showing `text`, centred on `at` in the coordinates of its future parent.
+    pub fn create(text: &str, at: Vector2) -> Gd<Self> {
+        let size = Vector2::new(96f32, 32f32);
+
+        let mut popup = Self::new_alloc();
+        popup.set_text(text);
+        popup.set_size(size);
+        popup.set_position(at - size / 2f32);
+        popup
+    }
+}
@@ -0,0 +1,65 @@
+use godot::prelude::*;
+
+/// Converts a run into a score. Kept as a resource so designers can tune it per scene and
+/// leaderboards can compare runs with the same rules.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct ScoringRules {
+    #[base]
+    base: Base<Resource>,
+    #[export]
+    pub points_per_second: f64,
+    /// Points for a graze before the multiplier is applied.
+    #[export]
+    pub graze_points: i64,
+    /// Added to the multiplier by each graze.
+    #[export]
+    pub multiplier_step: f64,
+    /// Lost by the multiplier per second, down to 1.
+    #[export]
+    pub multiplier_decay: f64,
+    #[export]
+    pub max_multiplier: f64,
+}
+
+#[godot_api]
+impl IResource for ScoringRules {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            points_per_second: 1f64,
+            graze_points: 5,
+            multiplier_step: 0.25f64,
+            multiplier_decay: 0.2f64,
+            max_multiplier: 4f64,
+        }
+    }
+}
+
+#[godot_api]
+impl ScoringRules {
+    /// Score for `run_time` seconds survived plus `bonus` points. Only whole points count;
+    /// ties between equal scores are broken by the run time itself.
+    #[func]
+    pub fn score_for(&self, run_time: f64, bonus: i64) -> i64 {
+        (run_time * self.points_per_second).floor() as i64 + bonus
+    }
+
+    /// Points awarded for a graze made with the current `multiplier`.
+    #[func]
+    pub fn graze_award(&self, multiplier: f64) -> i64 {
+        (self.graze_points as f64 * multiplier).round() as i64
+    }
+
+    /// Multiplier after one more graze.
+    #[func]
+    pub fn raise_multiplier(&self, multiplier: f64) -> f64 {
+        (multiplier + self.multiplier_step).min(self.max_multiplier.max(1f64))
+    }
+
+    /// Multiplier after `delta` seconds without a graze.
+    #[func]
+    pub fn decay_multiplier(&self, multiplier: f64, delta: f64) -> f64 {
+        (multiplier - self.multiplier_decay * delta).max(1f64)
+    }
+}
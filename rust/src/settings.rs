@@ -0,0 +1,213 @@
+use godot::{
+    classes::{
+        AudioServer, ConfigFile, DisplayServer, ProjectSettings,
+        display_server::{VSyncMode, WindowMode},
+    },
+    global::{Error, linear_to_db},
+    prelude::*,
+};
+
+use crate::localization::{Locale, TextKey, tr};
+
+const SETTINGS_PATH: &str = "user://settings.cfg";
+const AUDIO_SECTION: &str = "audio";
+const DISPLAY_SECTION: &str = "display";
+const ACCESSIBILITY_SECTION: &str = "accessibility";
+const LOCALE_SECTION: &str = "locale";
+
+pub const MASTER_BUS: &str = "Master";
+pub const MUSIC_BUS: &str = "Music";
+pub const SFX_BUS: &str = "SFX";
+
+/// Largest integer window scale offered for the windowed mode.
+pub const MAX_WINDOW_SCALE: i64 = 3;
+
+/// Player preferences, persisted in `user://settings.cfg` and applied on boot.
+#[derive(Clone)]
+pub struct Settings {
+    /// Linear volume from 0 to 1.
+    pub master_volume: f64,
+    pub music_volume: f64,
+    pub sfx_volume: f64,
+    pub fullscreen: bool,
+    /// Multiple of the project viewport size used for the window when not fullscreen.
+    pub window_scale: i64,
+    pub vsync: bool,
+    /// Forwarded to `FeedbackBase::reduced_motion`.
+    pub reduced_motion: bool,
+    /// `None` keeps the locale picked by the engine from the system language.
+    pub language: Option<Locale>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1f64,
+            music_volume: 0.8f64,
+            sfx_volume: 1f64,
+            fullscreen: false,
+            window_scale: 1,
+            vsync: true,
+            reduced_motion: false,
+            language: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let mut config = ConfigFile::new_gd();
+        let defaults = Self::default();
+
+        if config.load(SETTINGS_PATH) != Error::OK {
+            return defaults;
+        }
+
+        let read = |section: &str, key: &str, default: Variant| {
+            config.get_value_ex(section, key).default(&default).done()
+        };
+
+        let volume = |key: &str, default: f64| {
+            read(AUDIO_SECTION, key, default.to_variant())
+                .try_to::<f64>()
+                .unwrap_or(default)
+                .clamp(0f64, 1f64)
+        };
+
+        let language = read(LOCALE_SECTION, "language", GString::new().to_variant())
+            .try_to::<GString>()
+            .ok()
+            .and_then(|code| Locale::from_code(&code.to_string()));
+
+        Self {
+            master_volume: volume("master", defaults.master_volume),
+            music_volume: volume("music", defaults.music_volume),
+            sfx_volume: volume("sfx", defaults.sfx_volume),
+            fullscreen: read(
+                DISPLAY_SECTION,
+                "fullscreen",
+                defaults.fullscreen.to_variant(),
+            )
+            .try_to()
+            .unwrap_or(defaults.fullscreen),
+            window_scale: read(
+                DISPLAY_SECTION,
+                "window_scale",
+                defaults.window_scale.to_variant(),
+            )
+            .try_to::<i64>()
+            .unwrap_or(defaults.window_scale)
+            .clamp(1, MAX_WINDOW_SCALE),
+            vsync: read(DISPLAY_SECTION, "vsync", defaults.vsync.to_variant())
+                .try_to()
+                .unwrap_or(defaults.vsync),
+            reduced_motion: read(
+                ACCESSIBILITY_SECTION,
+                "reduced_motion",
+                defaults.reduced_motion.to_variant(),
+            )
+            .try_to()
+            .unwrap_or(defaults.reduced_motion),
+            language,
+        }
+    }
+
+    pub fn save(&self) {
+        let mut config = ConfigFile::new_gd();
+        config.set_value(AUDIO_SECTION, "master", &self.master_volume.to_variant());
+        config.set_value(AUDIO_SECTION, "music", &self.music_volume.to_variant());
+        config.set_value(AUDIO_SECTION, "sfx", &self.sfx_volume.to_variant());
+        config.set_value(DISPLAY_SECTION, "fullscreen", &self.fullscreen.to_variant());
+        config.set_value(
+            DISPLAY_SECTION,
+            "window_scale",
+            &self.window_scale.to_variant(),
+        );
+        config.set_value(DISPLAY_SECTION, "vsync", &self.vsync.to_variant());
+        config.set_value(
+            ACCESSIBILITY_SECTION,
+            "reduced_motion",
+            &self.reduced_motion.to_variant(),
+        );
+
+        let language = self
+            .language
+            .map(|locale| locale.to_string())
+            .unwrap_or_default();
+        config.set_value(LOCALE_SECTION, "language", &language.to_variant());
+
+        if config.save(SETTINGS_PATH) != Error::OK {
+            godot_warn!("{}", tr(TextKey::ErrorSaveSettings));
+        }
+    }
+
+    /// Applies everything owned by engine singletons: bus volumes, window mode, vsync and
+    /// language. Reduced motion lives on `FeedbackBase` and is applied by its owner.
+    pub fn apply(&self) {
+        self.apply_audio();
+        self.apply_display();
+
+        if let Some(locale) = self.language {
+            locale.apply();
+        }
+    }
+
+    pub fn apply_audio(&self) {
+        for (bus, volume) in [
+            (MASTER_BUS, self.master_volume),
+            (MUSIC_BUS, self.music_volume),
+            (SFX_BUS, self.sfx_volume),
+        ] {
+            let index = ensure_bus(bus);
+            let mut server = AudioServer::singleton();
+            server.set_bus_mute(index, volume <= 0f64);
+            server.set_bus_volume_db(index, linear_to_db(volume.max(0.0001f64)) as f32);
+        }
+    }
+
+    pub fn apply_display(&self) {
+        let mut display = DisplayServer::singleton();
+
+        display.window_set_vsync_mode(if self.vsync {
+            VSyncMode::ENABLED
+        } else {
+            VSyncMode::DISABLED
+        });
+
+        if self.fullscreen {
+            display.window_set_mode(WindowMode::FULLSCREEN);
+            return;
+        }
+
+        display.window_set_mode(WindowMode::WINDOWED);
+        display.window_set_size(viewport_size() * self.window_scale as i32);
+    }
+}
+
+/// Index of the bus called `name`, adding it with a send to `Master` when the project's bus
+/// layout doesn't define it.
+pub fn ensure_bus(name: &str) -> i32 {
+    let mut server = AudioServer::singleton();
+    let index = server.get_bus_index(name);
+
+    if index >= 0 {
+        return index;
+    }
+
+    server.add_bus();
+    let index = server.get_bus_count() - 1;
+    server.set_bus_name(index, name);
+    server.set_bus_send(index, MASTER_BUS);
+    index
+}
+
+fn viewport_size() -> Vector2i {
+    let settings = ProjectSettings::singleton();
+    let read =
+        |key: &str, default: i32| settings.get_setting(key).try_to::<i32>().unwrap_or(default);
+
+    Vector2i::new(
+        read("display/window/size/viewport_width", 480),
+        read("display/window/size/viewport_height", 720),
+    )
+}
@@ -0,0 +1,511 @@
+use core::fmt;
+
+use godot::{
+    builtin::Side,
+    classes::{
+        Button, CanvasLayer, CheckButton, Control, Engine, GridContainer, HSlider, ICanvasLayer,
+        InputEvent, Label, OptionButton, PanelContainer, VBoxContainer,
+        box_container::AlignmentMode,
+        control::{LayoutPreset, SizeFlags},
+        node::{AutoTranslateMode, ProcessMode},
+    },
+    prelude::*,
+};
+
+use crate::{
+    hud_theme::{SMALL_LABEL, hud_theme, place},
+    input_bindings,
+    localization::{Locale, TextKey},
+    settings::{MAX_WINDOW_SCALE, Settings},
+};
+
+/// Drawn above the HUD and the menu, which both live on layer 1.
+const SETTINGS_LAYER: i32 = 2;
+
+enum SettingsPanelChild {
+    Panel,
+    Content,
+    Grid,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Fullscreen,
+    WindowScale,
+    Vsync,
+    ReducedMotion,
+    Language,
+    ControlsButton,
+    BackButton,
+}
+
+impl fmt::Display for SettingsPanelChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsPanelChild::Panel => write!(f, "Panel"),
+            SettingsPanelChild::Content => write!(f, "Panel/Content"),
+            SettingsPanelChild::Grid => write!(f, "Panel/Content/Grid"),
+            SettingsPanelChild::MasterVolume => write!(f, "Panel/Content/Grid/MasterVolume"),
+            SettingsPanelChild::MusicVolume => write!(f, "Panel/Content/Grid/MusicVolume"),
+            SettingsPanelChild::SfxVolume => write!(f, "Panel/Content/Grid/SfxVolume"),
+            SettingsPanelChild::Fullscreen => write!(f, "Panel/Content/Grid/Fullscreen"),
+            SettingsPanelChild::WindowScale => write!(f, "Panel/Content/Grid/WindowScale"),
+            SettingsPanelChild::Vsync => write!(f, "Panel/Content/Grid/Vsync"),
+            SettingsPanelChild::ReducedMotion => write!(f, "Panel/Content/Grid/ReducedMotion"),
+            SettingsPanelChild::Language => write!(f, "Panel/Content/Grid/Language"),
+            SettingsPanelChild::ControlsButton => write!(f, "Panel/Content/ControlsButton"),
+            SettingsPanelChild::BackButton => write!(f, "Panel/Content/BackButton"),
+        }
+    }
+}
+
+impl SettingsPanelChild {
+    /// Name of the node itself, without the path to it.
+    fn name(&self) -> String {
+        let path = self.to_string();
+        path.rsplit('/').next().unwrap_or_default().to_string()
+    }
+}
+
+/// Settings screen opened from the main menu and the pause screen. Every change is applied
+/// and written to `user://settings.cfg` right away; the settings are also loaded and applied
+/// when the panel enters the tree, so they take effect on boot, together with the saved
+/// input bindings.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer, tool)]
+pub struct SettingsPanel {
+    #[base]
+    base: Base<CanvasLayer>,
+    settings: Settings,
+    return_focus: Option<Gd<Control>>,
+    master_volume: OnReady<Gd<HSlider>>,
+    music_volume: OnReady<Gd<HSlider>>,
+    sfx_volume: OnReady<Gd<HSlider>>,
+    fullscreen: OnReady<Gd<CheckButton>>,
+    window_scale: OnReady<Gd<OptionButton>>,
+    vsync: OnReady<Gd<CheckButton>>,
+    reduced_motion: OnReady<Gd<CheckButton>>,
+    language: OnReady<Gd<OptionButton>>,
+    controls_button: OnReady<Gd<Button>>,
+    back_button: OnReady<Gd<Button>>,
+}
+
+#[godot_api]
+impl ICanvasLayer for SettingsPanel {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            settings: Settings::default(),
+            return_focus: None,
+            master_volume: OnReady::from_node(&SettingsPanelChild::MasterVolume.to_string()),
+            music_volume: OnReady::from_node(&SettingsPanelChild::MusicVolume.to_string()),
+            sfx_volume: OnReady::from_node(&SettingsPanelChild::SfxVolume.to_string()),
+            fullscreen: OnReady::from_node(&SettingsPanelChild::Fullscreen.to_string()),
+            window_scale: OnReady::from_node(&SettingsPanelChild::WindowScale.to_string()),
+            vsync: OnReady::from_node(&SettingsPanelChild::Vsync.to_string()),
+            reduced_motion: OnReady::from_node(&SettingsPanelChild::ReducedMotion.to_string()),
+            language: OnReady::from_node(&SettingsPanelChild::Language.to_string()),
+            controls_button: OnReady::from_node(&SettingsPanelChild::ControlsButton.to_string()),
+            back_button: OnReady::from_node(&SettingsPanelChild::BackButton.to_string()),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        if !Engine::singleton().is_editor_hint() {
+            self.settings = Settings::load();
+            self.settings.apply();
+            input_bindings::ensure_registered();
+        }
+
+        let owner = self.base().clone().upcast::<Node>();
+
+        let mut panel = self
+            .base()
+            .try_get_node_as::<PanelContainer>(&SettingsPanelChild::Panel.to_string())
+            .unwrap_or_else(|| {
+                let mut panel = PanelContainer::new_alloc();
+                panel.set_name(&SettingsPanelChild::Panel.to_string());
+                panel.set_theme(&hud_theme());
+                place(
+                    &mut panel,
+                    LayoutPreset::CENTER,
+                    [-220f32, -260f32, 220f32, 260f32],
+                );
+                self.base_mut().add_child(&panel);
+                panel.set_owner(&owner);
+                panel
+            });
+
+        let mut content = panel
+            .try_get_node_as::<VBoxContainer>(&SettingsPanelChild::Content.name())
+            .unwrap_or_else(|| {
+                let mut content = VBoxContainer::new_alloc();
+                content.set_name(&SettingsPanelChild::Content.name());
+                content.set_alignment(AlignmentMode::CENTER);
+                panel.add_child(&content);
+                content.set_owner(&owner);
+                content
+            });
+
+        let mut grid = content
+            .try_get_node_as::<GridContainer>(&SettingsPanelChild::Grid.name())
+            .unwrap_or_else(|| {
+                let mut grid = GridContainer::new_alloc();
+                grid.set_name(&SettingsPanelChild::Grid.name());
+                grid.set_columns(2);
+                content.add_child(&grid);
+                grid.set_owner(&owner);
+                grid
+            });
+
+        for (child, key) in [
+            (
+                SettingsPanelChild::MasterVolume,
+                TextKey::SettingsMasterVolume,
+            ),
+            (
+                SettingsPanelChild::MusicVolume,
+                TextKey::SettingsMusicVolume,
+            ),
+            (SettingsPanelChild::SfxVolume, TextKey::SettingsSfxVolume),
+        ] {
+            add_row(&mut grid, &owner, &child, key, || {
+                let mut slider = HSlider::new_alloc();
+                slider.set_max(1f64);
+                slider.set_step(0.05f64);
+                slider.set_custom_minimum_size(Vector2::new(160f32, 32f32));
+                slider.upcast()
+            });
+        }
+
+        add_row(
+            &mut grid,
+            &owner,
+            &SettingsPanelChild::Fullscreen,
+            TextKey::SettingsFullscreen,
+            || CheckButton::new_alloc().upcast(),
+        );
+        add_row(
+            &mut grid,
+            &owner,
+            &SettingsPanelChild::WindowScale,
+            TextKey::SettingsWindowScale,
+            || OptionButton::new_alloc().upcast(),
+        );
+        add_row(
+            &mut grid,
+            &owner,
+            &SettingsPanelChild::Vsync,
+            TextKey::SettingsVsync,
+            || CheckButton::new_alloc().upcast(),
+        );
+        add_row(
+            &mut grid,
+            &owner,
+            &SettingsPanelChild::ReducedMotion,
+            TextKey::SettingsReducedMotion,
+            || CheckButton::new_alloc().upcast(),
+        );
+        add_row(
+            &mut grid,
+            &owner,
+            &SettingsPanelChild::Language,
+            TextKey::SettingsLanguage,
+            || OptionButton::new_alloc().upcast(),
+        );
+
+        for (child, key) in [
+            (
+                SettingsPanelChild::ControlsButton,
+                TextKey::SettingsControls,
+            ),
+            (SettingsPanelChild::BackButton, TextKey::MenuBack),
+        ] {
+            if content.has_node(&child.name()) {
+                continue;
+            }
+
+            let mut button = Button::new_alloc();
+            button.set_name(&child.name());
+            button.set_text(&key.to_string());
+            button.set_h_size_flags(SizeFlags::SHRINK_CENTER);
+            content.add_child(&button);
+            button.set_owner(&owner);
+        }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_layer(SETTINGS_LAYER);
+
+        for (index, scale) in (1..=MAX_WINDOW_SCALE).enumerate() {
+            self.window_scale.add_item(&format!("{scale}x"));
+            self.window_scale
+                .set_item_metadata(index as i32, &scale.to_variant());
+        }
+        self.window_scale
+            .set_auto_translate_mode(AutoTranslateMode::DISABLED);
+
+        for (index, locale) in Locale::ALL.into_iter().enumerate() {
+            self.language.add_item(locale.native_name());
+            self.language
+                .set_item_metadata(index as i32, &locale.to_string().to_variant());
+        }
+        self.language
+            .set_auto_translate_mode(AutoTranslateMode::DISABLED);
+
+        self.connect_controls();
+        self.setup_focus_wrap();
+
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        // The panel is reachable from the pause screen, so it must keep working while paused.
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+        self.base_mut().hide();
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        if !self.base().is_visible() || !event.is_action_pressed("ui_cancel") {
+            return;
+        }
+
+        self.close();
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+    }
+}
+
+#[godot_api]
+impl SettingsPanel {
+    #[signal]
+    pub fn closed();
+
+    #[signal]
+    pub fn reduced_motion_changed(enabled: bool);
+
+    /// The controls button was pressed; the owner opens the `ControlsPanel`.
+    #[signal]
+    pub fn controls_requested();
+
+    /// Shows the panel with the saved settings and remembers the focused control, which gets
+    /// the focus back on `close`.
+    #[func]
+    pub fn open(&mut self) {
+        self.settings = Settings::load();
+        self.refresh_controls();
+
+        self.return_focus = self
+            .base()
+            .get_viewport()
+            .and_then(|viewport| viewport.gui_get_focus_owner());
+
+        self.base_mut().show();
+        self.master_volume.grab_focus();
+    }
+
+    #[func]
+    pub fn close(&mut self) {
+        if !self.base().is_visible() {
+            return;
+        }
+
+        self.base_mut().hide();
+
+        if let Some(mut control) = self.return_focus.take()
+            && control.is_instance_valid()
+            && control.is_visible_in_tree()
+        {
+            control.grab_focus();
+        }
+
+        self.signals().closed().emit();
+    }
+
+    #[func]
+    pub fn is_open(&self) -> bool {
+        self.base().is_visible()
+    }
+
+    #[func]
+    pub fn get_reduced_motion(&self) -> bool {
+        self.settings.reduced_motion
+    }
+
+    #[func]
+    fn on_master_volume_changed(&mut self, value: f64) {
+        self.settings.master_volume = value;
+        self.settings.apply_audio();
+        self.settings.save();
+    }
+
+    #[func]
+    fn on_music_volume_changed(&mut self, value: f64) {
+        self.settings.music_volume = value;
+        self.settings.apply_audio();
+        self.settings.save();
+    }
+
+    #[func]
+    fn on_sfx_volume_changed(&mut self, value: f64) {
+        self.settings.sfx_volume = value;
+        self.settings.apply_audio();
+        self.settings.save();
+    }
+
+    #[func]
+    fn on_fullscreen_toggled(&mut self, enabled: bool) {
+        self.settings.fullscreen = enabled;
+        self.window_scale.set_disabled(enabled);
+        self.settings.apply_display();
+        self.settings.save();
+    }
+
+    #[func]
+    fn on_window_scale_selected(&mut self, index: i64) {
+        self.settings.window_scale = self
+            .window_scale
+            .get_item_metadata(index as i32)
+            .try_to()
+            .unwrap_or(1);
+        self.settings.apply_display();
+        self.settings.save();
+    }
+
+    #[func]
+    fn on_vsync_toggled(&mut self, enabled: bool) {
+        self.settings.vsync = enabled;
+        self.settings.apply_display();
+        self.settings.save();
+    }
+
+    #[func]
+    fn on_reduced_motion_toggled(&mut self, enabled: bool) {
+        self.settings.reduced_motion = enabled;
+        self.settings.save();
+        self.signals().reduced_motion_changed().emit(enabled);
+    }
+
+    #[func]
+    fn on_language_selected(&mut self, index: i64) {
+        let code = self
+            .language
+            .get_item_metadata(index as i32)
+            .to::<GString>();
+
+        if let Some(locale) = Locale::from_code(&code.to_string()) {
+            self.settings.language = Some(locale);
+            locale.apply();
+            self.settings.save();
+        }
+    }
+
+    #[func]
+    fn on_controls_pressed(&mut self) {
+        self.signals().controls_requested().emit();
+    }
+
+    fn refresh_controls(&mut self) {
+        let settings = self.settings.clone();
+
+        self.master_volume
+            .set_value_no_signal(settings.master_volume);
+        self.music_volume.set_value_no_signal(settings.music_volume);
+        self.sfx_volume.set_value_no_signal(settings.sfx_volume);
+        self.fullscreen.set_pressed_no_signal(settings.fullscreen);
+        self.window_scale.select((settings.window_scale - 1) as i32);
+        self.window_scale.set_disabled(settings.fullscreen);
+        self.vsync.set_pressed_no_signal(settings.vsync);
+        self.reduced_motion
+            .set_pressed_no_signal(settings.reduced_motion);
+
+        let current = settings.language.unwrap_or_else(Locale::current);
+        if let Some(index) = Locale::ALL.into_iter().position(|locale| locale == current) {
+            self.language.select(index as i32);
+        }
+    }
+
+    fn connect_controls(&mut self) {
+        self.master_volume
+            .signals()
+            .value_changed()
+            .connect_other(self, Self::on_master_volume_changed);
+        self.music_volume
+            .signals()
+            .value_changed()
+            .connect_other(self, Self::on_music_volume_changed);
+        self.sfx_volume
+            .signals()
+            .value_changed()
+            .connect_other(self, Self::on_sfx_volume_changed);
+        self.fullscreen
+            .signals()
+            .toggled()
+            .connect_other(self, Self::on_fullscreen_toggled);
+        self.window_scale
+            .signals()
+            .item_selected()
+            .connect_other(self, Self::on_window_scale_selected);
+        self.vsync
+            .signals()
+            .toggled()
+            .connect_other(self, Self::on_vsync_toggled);
+        self.reduced_motion
+            .signals()
+            .toggled()
+            .connect_other(self, Self::on_reduced_motion_toggled);
+        self.language
+            .signals()
+            .item_selected()
+            .connect_other(self, Self::on_language_selected);
+        self.controls_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::on_controls_pressed);
+        self.back_button
+            .signals()
+            .pressed()
+            .connect_other(self, Self::close);
+    }
+
+    /// Wraps focus between the first setting and the back button.
+    fn setup_focus_wrap(&mut self) {
+        let mut first = self.master_volume.clone().upcast::<Control>();
+        let mut last = self.back_button.clone().upcast::<Control>();
+
+        let to_last = first.get_path_to(&last);
+        let to_first = last.get_path_to(&first);
+
+        first.set_focus_neighbor(Side::TOP, &to_last);
+        last.set_focus_neighbor(Side::BOTTOM, &to_first);
+    }
+}
+
+/// Adds a label and the control made by `create` to `grid`, unless the control already exists.
+fn add_row(
+    grid: &mut Gd<GridContainer>,
+    owner: &Gd<Node>,
+    child: &SettingsPanelChild,
+    key: TextKey,
+    create: impl FnOnce() -> Gd<Control>,
+) {
+    let name = child.name();
+
+    if grid.has_node(&name) {
+        return;
+    }
+
+    let mut label = Label::new_alloc();
+    label.set_name(&format!("{name}Label"));
+    label.set_theme_type_variation(SMALL_LABEL);
+    label.set_text(&key.to_string());
+    grid.add_child(&label);
+    label.set_owner(owner);
+
+    let mut control = create();
+    control.set_name(&name);
+    control.set_h_size_flags(SizeFlags::EXPAND_FILL);
+    grid.add_child(&control);
+    control.set_owner(owner);
+}
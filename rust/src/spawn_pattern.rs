@@ -0,0 +1,779 @@
+use godot::{classes::RandomNumberGenerator, prelude::*};
+
+use crate::wave::SpawnEdge;
+
+/// Random numbers the patterns draw from: the run's `RandomNumberGenerator`, so a seed
+/// replays the same patterns.
+pub trait PatternRng {
+    /// A number from 0 to 1.
+    fn randf(&mut self) -> f32;
+    fn randf_range(&mut self, from: f32, to: f32) -> f32;
+    /// A whole number from `from` to `to`, both included.
+    fn randi_range(&mut self, from: i32, to: i32) -> i32;
+}
+
+impl PatternRng for Gd<RandomNumberGenerator> {
+    fn randf(&mut self) -> f32 {
+        RandomNumberGenerator::randf(self)
+    }
+
+    fn randf_range(&mut self, from: f32, to: f32) -> f32 {
+        RandomNumberGenerator::randf_range(self, from, to)
+    }
+
+    fn randi_range(&mut self, from: i32, to: i32) -> i32 {
+        RandomNumberGenerator::randi_range(self, from, to)
+    }
+}
+
+/// One enemy of a pattern: where it starts, how it moves, and the seconds it waits after the
+/// pattern starts.
+#[derive(Clone, Copy, Debug)]
+pub struct PatternShot {
+    pub delay: f64,
+    pub at: Vector2,
+    pub velocity: Vector2,
+}
+
+/// A way of sending several enemies at once, picked by `SpawnPatterns` with a weight that
+/// follows the difficulty.
+pub trait SpawnPattern {
+    /// Weights when the spawn rate is at its slowest and at its peak.
+    fn weights(&self) -> (f32, f32);
+
+    /// Enemies of one use of the pattern, around `bounds` and aimed at the player at
+    /// `target` where the pattern cares.
+    fn shots(&self, bounds: Rect2, target: Vector2, rng: &mut dyn PatternRng) -> Vec<PatternShot>;
+
+    /// Enemies of one use of the pattern fired from `origin`, e.g. by a boss. Patterns that
+    /// don't come from a single point ignore it.
+    fn shots_from(
+        &self,
+        _origin: Vector2,
+        bounds: Rect2,
+        target: Vector2,
+        rng: &mut dyn PatternRng,
+    ) -> Vec<PatternShot> {
+        self.shots(bounds, target, rng)
+    }
+
+    /// Weight at `difficulty`, from 0 at the start of a run to 1 at the peak spawn rate.
+    fn weight(&self, difficulty: f32) -> f32 {
+        let (start, peak) = self.weights();
+        (start + (peak - start) * difficulty.clamp(0f32, 1f32)).max(0f32)
+    }
+}
+
+/// Which way a `WallPattern` runs.
+#[derive(GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[godot(via = i64)]
+pub enum WallOrientation {
+    /// A row coming down from the top or up from the bottom.
+    Horizontal,
+    /// A column coming in from the left or the right.
+    Vertical,
+    /// Either, at random.
+    #[default]
+    Either,
+}
+
+/// A straight line of enemies across the screen with one gap to slip through.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct WallPattern {
+    #[base]
+    base: Base<Resource>,
+    #[export]
+    pub orientation: WallOrientation,
+    /// Pixels between neighbouring enemies.
+    #[export(range = (16.0, 256.0, or_greater))]
+    pub spacing: f32,
+    /// Pixels, centre to centre, between the enemies on both sides of the gap. Always leaves
+    /// out at least one enemy.
+    #[export(range = (32.0, 512.0, or_greater))]
+    pub gap_width: f32,
+    #[export(range = (0.0, 1000.0, or_greater))]
+    pub speed: f32,
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub weight_at_start: f32,
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub weight_at_peak: f32,
+}
+
+#[godot_api]
+impl IResource for WallPattern {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            orientation: WallOrientation::Either,
+            spacing: 56f32,
+            gap_width: 180f32,
+            speed: 150f32,
+            weight_at_start: 0.5f32,
+            weight_at_peak: 2f32,
+        }
+    }
+}
+
+impl SpawnPattern for WallPattern {
+    fn weights(&self) -> (f32, f32) {
+        (self.weight_at_start, self.weight_at_peak)
+    }
+
+    fn shots(&self, bounds: Rect2, _target: Vector2, rng: &mut dyn PatternRng) -> Vec<PatternShot> {
+        wall(
+            self.orientation,
+            self.spacing,
+            self.gap_width,
+            self.speed,
+            bounds,
+            rng,
+        )
+    }
+}
+
+/// Enemies of a wall running as `orientation` across `bounds`, `spacing` apart with a gap of
+/// `gap_width`, moving at `speed`.
+fn wall(
+    orientation: WallOrientation,
+    spacing: f32,
+    gap_width: f32,
+    speed: f32,
+    bounds: Rect2,
+    rng: &mut dyn PatternRng,
+) -> Vec<PatternShot> {
+    let horizontal = match orientation {
+        WallOrientation::Horizontal => true,
+        WallOrientation::Vertical => false,
+        WallOrientation::Either => rng.randf() < 0.5f32,
+    };
+    let flipped = rng.randf() < 0.5f32;
+
+    // The wall runs along `along` and moves along `heading`, from the edge it starts on.
+    let (start, along, length, heading) = match (horizontal, flipped) {
+        (true, false) => (
+            bounds.position,
+            Vector2::RIGHT,
+            bounds.size.x,
+            Vector2::DOWN,
+        ),
+        (true, true) => (
+            bounds.position + Vector2::new(0f32, bounds.size.y),
+            Vector2::RIGHT,
+            bounds.size.x,
+            Vector2::UP,
+        ),
+        (false, false) => (
+            bounds.position,
+            Vector2::DOWN,
+            bounds.size.y,
+            Vector2::RIGHT,
+        ),
+        (false, true) => (
+            bounds.position + Vector2::new(bounds.size.x, 0f32),
+            Vector2::DOWN,
+            bounds.size.y,
+            Vector2::LEFT,
+        ),
+    };
+
+    let spacing = spacing.max(1f32);
+    let half_gap = gap_width.max(spacing) / 2f32;
+    let count = (length / spacing).floor() as i32;
+
+    // The gap is centred between the first and the last enemy and clear of the ends, so the
+    // enemy closest to it, never more than half the spacing away, is always left out.
+    let low = half_gap.max(spacing / 2f32);
+    let high = (length - half_gap).min(spacing * (count as f32 - 0.5f32));
+    let gap = if low <= high {
+        rng.randf_range(low, high)
+    } else {
+        length / 2f32
+    };
+
+    (0..count)
+        .map(|index| spacing * (index as f32 + 0.5f32))
+        .filter(|offset| (offset - gap).abs() > half_gap)
+        .map(|offset| PatternShot {
+            delay: 0f64,
+            at: start + along * offset,
+            velocity: heading * speed,
+        })
+        .collect()
+}
+
+/// Enemies fired one after the other from a point on an edge, the aim sweeping across.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct SpiralPattern {
+    #[base]
+    base: Base<Resource>,
+    #[export(range = (2.0, 64.0, or_greater))]
+    pub count: i64,
+    /// Seconds between two enemies.
+    #[export(range = (0.0, 1.0, or_greater))]
+    pub interval: f64,
+    /// Degrees the aim sweeps from the first enemy to the last.
+    #[export(range = (0.0, 180.0))]
+    pub sweep: f32,
+    #[export(range = (0.0, 1000.0, or_greater))]
+    pub speed: f32,
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub weight_at_start: f32,
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub weight_at_peak: f32,
+}
+
+#[godot_api]
+impl IResource for SpiralPattern {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            count: 12,
+            interval: 0.12f64,
+            sweep: 150f32,
+            speed: 200f32,
+            weight_at_start: 0f32,
+            weight_at_peak: 1.5f32,
+        }
+    }
+}
+
+impl SpawnPattern for SpiralPattern {
+    fn weights(&self) -> (f32, f32) {
+        (self.weight_at_start, self.weight_at_peak)
+    }
+
+    fn shots(&self, bounds: Rect2, _target: Vector2, rng: &mut dyn PatternRng) -> Vec<PatternShot> {
+        // Somewhere on the middle half of an edge, so the sweep stays on screen.
+        let edge = SpawnEdge::ALL[rng.randi_range(0, SpawnEdge::ALL.len() as i32 - 1) as usize];
+        let from = edge.point(bounds, rng.randf_range(0.25f32, 0.75f32));
+        self.sweep(from, edge.inward(), rng)
+    }
+
+    fn shots_from(
+        &self,
+        origin: Vector2,
+        _bounds: Rect2,
+        target: Vector2,
+        rng: &mut dyn PatternRng,
+    ) -> Vec<PatternShot> {
+        let heading = (target - origin).try_normalized().unwrap_or(Vector2::DOWN);
+        self.sweep(origin, heading, rng)
+    }
+}
+
+impl SpiralPattern {
+    /// Enemies fired from `from`, the aim sweeping across `heading`.
+    fn sweep(&self, from: Vector2, heading: Vector2, rng: &mut dyn PatternRng) -> Vec<PatternShot> {
+        let count = self.count.max(2);
+        let sweep = self.sweep.to_radians();
+        let turn = if rng.randf() < 0.5f32 { 1f32 } else { -1f32 };
+
+        (0..count)
+            .map(|index| {
+                let progress = index as f32 / (count - 1) as f32;
+                let angle = (progress - 0.5f32) * sweep * turn;
+                PatternShot {
+                    delay: self.interval * index as f64,
+                    at: from,
+                    velocity: heading.rotated(angle) * self.speed,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Two groups closing in on the player from opposite sides.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct PincerPattern {
+    #[base]
+    base: Base<Resource>,
+    /// Enemies on each side.
+    #[export(range = (1.0, 16.0, or_greater))]
+    pub count: i64,
+    /// Pixels between the enemies of a side.
+    #[export(range = (16.0, 256.0, or_greater))]
+    pub spacing: f32,
+    #[export(range = (0.0, 1000.0, or_greater))]
+    pub speed: f32,
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub weight_at_start: f32,
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub weight_at_peak: f32,
+}
+
+#[godot_api]
+impl IResource for PincerPattern {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            count: 3,
+            spacing: 64f32,
+            speed: 220f32,
+            weight_at_start: 0.5f32,
+            weight_at_peak: 1.5f32,
+        }
+    }
+}
+
+impl SpawnPattern for PincerPattern {
+    fn weights(&self) -> (f32, f32) {
+        (self.weight_at_start, self.weight_at_peak)
+    }
+
+    fn shots(&self, bounds: Rect2, target: Vector2, rng: &mut dyn PatternRng) -> Vec<PatternShot> {
+        let horizontal = rng.randf() < 0.5f32;
+        let count = self.count.max(1);
+        let end = bounds.end();
+
+        (0..count)
+            .flat_map(|index| {
+                let offset = (index as f32 - (count - 1) as f32 / 2f32) * self.spacing;
+
+                // Sides left and right close in horizontally on the player's row; top and
+                // bottom, vertically on its column.
+                let (first, second, heading) = if horizontal {
+                    let y = (target.y + offset).clamp(bounds.position.y, end.y);
+                    (
+                        Vector2::new(bounds.position.x, y),
+                        Vector2::new(end.x, y),
+                        Vector2::RIGHT,
+                    )
+                } else {
+                    let x = (target.x + offset).clamp(bounds.position.x, end.x);
+                    (
+                        Vector2::new(x, bounds.position.y),
+                        Vector2::new(x, end.y),
+                        Vector2::DOWN,
+                    )
+                };
+
+                [(first, heading), (second, -heading)].map(|(at, heading)| PatternShot {
+                    delay: 0f64,
+                    at,
+                    velocity: heading * self.speed,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Rings of enemies fanning out from a corner.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct BurstPattern {
+    #[base]
+    base: Base<Resource>,
+    /// Enemies in each ring.
+    #[export(range = (1.0, 32.0, or_greater))]
+    pub count: i64,
+    #[export(range = (1.0, 8.0, or_greater))]
+    pub rings: i64,
+    /// Seconds between two rings.
+    #[export(range = (0.0, 2.0, or_greater))]
+    pub ring_interval: f64,
+    /// Degrees the fan covers, centred on the middle of the screen.
+    #[export(range = (0.0, 90.0))]
+    pub spread: f32,
+    #[export(range = (0.0, 1000.0, or_greater))]
+    pub speed: f32,
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub weight_at_start: f32,
+    #[export(range = (0.0, 10.0, or_greater))]
+    pub weight_at_peak: f32,
+}
+
+#[godot_api]
+impl IResource for BurstPattern {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            count: 6,
+            rings: 2,
+            ring_interval: 0.5f64,
+            spread: 80f32,
+            speed: 180f32,
+            weight_at_start: 0.2f32,
+            weight_at_peak: 1f32,
+        }
+    }
+}
+
+impl SpawnPattern for BurstPattern {
+    fn weights(&self) -> (f32, f32) {
+        (self.weight_at_start, self.weight_at_peak)
+    }
+
+    fn shots(&self, bounds: Rect2, _target: Vector2, rng: &mut dyn PatternRng) -> Vec<PatternShot> {
+        let corner = bounds.position
+            + Vector2::new(
+                if rng.randf() < 0.5f32 {
+                    0f32
+                } else {
+                    bounds.size.x
+                },
+                if rng.randf() < 0.5f32 {
+                    0f32
+                } else {
+                    bounds.size.y
+                },
+            );
+        let towards_centre = (bounds.center() - corner).normalized_or_zero();
+        self.fan(corner, towards_centre)
+    }
+
+    fn shots_from(
+        &self,
+        origin: Vector2,
+        _bounds: Rect2,
+        target: Vector2,
+        _rng: &mut dyn PatternRng,
+    ) -> Vec<PatternShot> {
+        let heading = (target - origin).try_normalized().unwrap_or(Vector2::DOWN);
+        self.fan(origin, heading)
+    }
+}
+
+impl BurstPattern {
+    /// Rings of enemies fanning out from `from` around `heading`.
+    fn fan(&self, from: Vector2, heading: Vector2) -> Vec<PatternShot> {
+        let count = self.count.max(1);
+        let spread = self.spread.to_radians();
+        let step = if count > 1 {
+            spread / (count - 1) as f32
+        } else {
+            0f32
+        };
+
+        (0..self.rings.max(1))
+            .flat_map(|ring| {
+                // Every other ring is offset by half a step, to cover the gaps of the last.
+                let shift = if ring % 2 == 1 { step / 2f32 } else { 0f32 };
+
+                (0..count).map(move |index| {
+                    let angle = -spread / 2f32 + step * index as f32 + shift;
+                    PatternShot {
+                        delay: self.ring_interval * ring as f64,
+                        at: from,
+                        velocity: heading.rotated(angle) * self.speed,
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+/// Rolls whether a spawn is a pattern, one time in `chance`, and if so which one, each as
+/// likely as its share of `weights`. Returns the index of the pattern.
+fn roll_pattern(chance: f32, weights: &[f32], rng: &mut dyn PatternRng) -> Option<usize> {
+    if rng.randf() >= chance {
+        return None;
+    }
+
+    let total = weights.iter().filter(|weight| **weight > 0f32).sum::<f32>();
+    if total <= 0f32 {
+        return None;
+    }
+
+    let mut roll = rng.randf() * total;
+    let mut picked = None;
+
+    for (index, weight) in weights.iter().enumerate() {
+        if *weight <= 0f32 {
+            continue;
+        }
+
+        picked = Some(index);
+        roll -= weight;
+
+        if roll < 0f32 {
+            break;
+        }
+    }
+
+    picked
+}
+
+/// The patterns the spawner can send instead of a single enemy, and how often it does.
+/// Patterns left empty are never picked.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct SpawnPatterns {
+    #[base]
+    base: Base<Resource>,
+    #[export]
+    pub wall: Option<Gd<WallPattern>>,
+    #[export]
+    pub spiral: Option<Gd<SpiralPattern>>,
+    #[export]
+    pub pincer: Option<Gd<PincerPattern>>,
+    #[export]
+    pub burst: Option<Gd<BurstPattern>>,
+    /// Chance that a spawn is a pattern when the spawn rate is at its slowest.
+    #[export(range = (0.0, 1.0))]
+    pub chance_at_start: f32,
+    /// Chance that a spawn is a pattern at the peak spawn rate.
+    #[export(range = (0.0, 1.0))]
+    pub chance_at_peak: f32,
+    /// Seconds after a pattern has been sent before the next one can start.
+    #[export(range = (0.0, 30.0, or_greater))]
+    pub cooldown: f64,
+}
+
+#[godot_api]
+impl IResource for SpawnPatterns {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            wall: Some(WallPattern::new_gd()),
+            spiral: Some(SpiralPattern::new_gd()),
+            pincer: Some(PincerPattern::new_gd()),
+            burst: Some(BurstPattern::new_gd()),
+            chance_at_start: 0.05f32,
+            chance_at_peak: 0.3f32,
+            cooldown: 3f64,
+        }
+    }
+}
+
+impl SpawnPatterns {
+    /// Rolls whether a spawn at `difficulty`, from 0 to 1, is a pattern and which one by
+    /// their weights. Returns its enemies, or `None` for a regular spawn.
+    pub fn pick(
+        &self,
+        difficulty: f32,
+        bounds: Rect2,
+        target: Vector2,
+        rng: &mut dyn PatternRng,
+    ) -> Option<Vec<PatternShot>> {
+        let difficulty = difficulty.clamp(0f32, 1f32);
+        let chance =
+            self.chance_at_start + (self.chance_at_peak - self.chance_at_start) * difficulty;
+
+        self.with_patterns(|patterns| {
+            let weights = patterns
+                .iter()
+                .map(|pattern| pattern.weight(difficulty))
+                .collect::<Vec<_>>();
+            let pattern = patterns.get(roll_pattern(chance, &weights, rng)?)?;
+
+            Some(pattern.shots(bounds, target, rng))
+        })
+    }
+
+    /// Enemies of the pattern at `index` in the order wall, spiral, pincer, burst, counting
+    /// only those set and wrapping around, fired from `origin`. `None` when no pattern is set.
+    pub fn nth_shots(
+        &self,
+        index: usize,
+        origin: Vector2,
+        bounds: Rect2,
+        target: Vector2,
+        rng: &mut dyn PatternRng,
+    ) -> Option<Vec<PatternShot>> {
+        self.with_patterns(|patterns| {
+            let pattern = patterns.get(index % patterns.len().max(1))?;
+            Some(pattern.shots_from(origin, bounds, target, rng))
+        })
+    }
+
+    /// Runs `f` on the patterns that are set, in the order wall, spiral, pincer, burst.
+    fn with_patterns<R>(&self, f: impl FnOnce(&[&dyn SpawnPattern]) -> R) -> R {
+        let wall = self.wall.as_ref().map(|pattern| pattern.bind());
+        let spiral = self.spiral.as_ref().map(|pattern| pattern.bind());
+        let pincer = self.pincer.as_ref().map(|pattern| pattern.bind());
+        let burst = self.burst.as_ref().map(|pattern| pattern.bind());
+
+        let patterns = [
+            wall.as_deref().map(|pattern| pattern as &dyn SpawnPattern),
+            spiral
+                .as_deref()
+                .map(|pattern| pattern as &dyn SpawnPattern),
+            pincer
+                .as_deref()
+                .map(|pattern| pattern as &dyn SpawnPattern),
+            burst.as_deref().map(|pattern| pattern as &dyn SpawnPattern),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        f(&patterns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Xorshift generator, so the tests draw the same numbers on every run.
+    struct TestRng(u64);
+
+    impl PatternRng for TestRng {
+        fn randf(&mut self) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        }
+
+        fn randf_range(&mut self, from: f32, to: f32) -> f32 {
+            from + (to - from) * self.randf()
+        }
+
+        fn randi_range(&mut self, from: i32, to: i32) -> i32 {
+            from + (self.randf() * (to - from + 1) as f32) as i32
+        }
+    }
+
+    /// Offsets of the wall's enemies along it, with both ends of the wall, sorted.
+    fn offsets(shots: &[PatternShot], bounds: Rect2) -> (Vec<f32>, f32) {
+        let horizontal = shots[0].velocity.x == 0f32;
+        let length = if horizontal {
+            bounds.size.x
+        } else {
+            bounds.size.y
+        };
+
+        let mut offsets = shots
+            .iter()
+            .map(|shot| {
+                let from = shot.at - bounds.position;
+                if horizontal { from.x } else { from.y }
+            })
+            .chain([0f32, length])
+            .collect::<Vec<_>>();
+        offsets.sort_by(f32::total_cmp);
+
+        (offsets, length)
+    }
+
+    #[test]
+    fn wall_always_leaves_a_gap() {
+        let bounds = Rect2::new(Vector2::new(-20f32, -20f32), Vector2::new(520f32, 760f32));
+        let mut rng = TestRng(0x9e37_79b9_7f4a_7c15);
+
+        for (spacing, gap_width) in [(56f32, 180f32), (40f32, 32f32), (64f32, 64f32)] {
+            for _ in 0..200 {
+                let shots = wall(
+                    WallOrientation::Either,
+                    spacing,
+                    gap_width,
+                    150f32,
+                    bounds,
+                    &mut rng,
+                );
+                let (offsets, length) = offsets(&shots, bounds);
+                let slots = (length / spacing).floor() as usize;
+                let widest = offsets
+                    .windows(2)
+                    .map(|pair| pair[1] - pair[0])
+                    .fold(0f32, f32::max);
+
+                assert!(shots.len() < slots, "{} of {slots} slots", shots.len());
+                assert!(
+                    widest >= gap_width.max(spacing) - 0.01f32,
+                    "widest opening {widest} with spacing {spacing}, gap {gap_width}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wall_wider_than_its_gap_leaves_out_the_middle() {
+        let bounds = Rect2::new(Vector2::ZERO, Vector2::new(100f32, 100f32));
+        let mut rng = TestRng(7);
+
+        for _ in 0..50 {
+            let shots = wall(
+                WallOrientation::Horizontal,
+                20f32,
+                300f32,
+                100f32,
+                bounds,
+                &mut rng,
+            );
+            assert!(shots.is_empty(), "{shots:?}");
+        }
+    }
+
+    #[test]
+    fn wall_moves_across_from_its_edge() {
+        let bounds = Rect2::new(Vector2::ZERO, Vector2::new(480f32, 720f32));
+        let mut rng = TestRng(42);
+
+        for orientation in [WallOrientation::Horizontal, WallOrientation::Vertical] {
+            for _ in 0..20 {
+                let shots = wall(orientation, 56f32, 180f32, 150f32, bounds, &mut rng);
+                let heading = shots[0].velocity;
+
+                assert_eq!(heading.length(), 150f32);
+                assert!(shots.iter().all(|shot| shot.velocity == heading));
+                assert_eq!(
+                    heading.x == 0f32,
+                    orientation == WallOrientation::Horizontal
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn patterns_are_picked_by_weight() {
+        let mut rng = TestRng(0x2545_f491_4f6c_dd1d);
+        let weights = [1f32, 3f32, 0f32, -2f32];
+        let mut picks = [0usize; 4];
+
+        for _ in 0..40_000 {
+            let index = roll_pattern(1f32, &weights, &mut rng).expect("always a pattern");
+            picks[index] += 1;
+        }
+
+        assert_eq!(picks[2], 0);
+        assert_eq!(picks[3], 0);
+        let share = picks[1] as f32 / (picks[0] + picks[1]) as f32;
+        assert!((share - 0.75f32).abs() < 0.02f32, "{picks:?}");
+    }
+
+    #[test]
+    fn pattern_chance_is_honoured() {
+        let mut rng = TestRng(99);
+        let rolls = 40_000;
+        let patterns = (0..rolls)
+            .filter(|_| roll_pattern(0.3f32, &[1f32], &mut rng).is_some())
+            .count();
+
+        let share = patterns as f32 / rolls as f32;
+        assert!((share - 0.3f32).abs() < 0.02f32, "{share}");
+        assert_eq!(roll_pattern(0f32, &[1f32], &mut rng), None);
+    }
+
+    #[test]
+    fn no_pattern_without_a_positive_weight() {
+        let mut rng = TestRng(3);
+
+        assert_eq!(roll_pattern(1f32, &[0f32, -1f32], &mut rng), None);
+        assert_eq!(roll_pattern(1f32, &[], &mut rng), None);
+    }
+
+    #[test]
+    fn weight_follows_the_difficulty() {
+        struct Weights;
+
+        impl SpawnPattern for Weights {
+            fn weights(&self) -> (f32, f32) {
+                (2f32, -2f32)
+            }
+
+            fn shots(&self, _: Rect2, _: Vector2, _: &mut dyn PatternRng) -> Vec<PatternShot> {
+                Vec::new()
+            }
+        }
+
+        assert_eq!(Weights.weight(-1f32), 2f32);
+        assert_eq!(Weights.weight(0.25f32), 1f32);
+        assert_eq!(Weights.weight(0.75f32), 0f32);
+        assert_eq!(Weights.weight(2f32), 0f32);
+    }
+}
@@ -0,0 +1,63 @@
+/// Whether a best-of-`rounds` match is decided after `round` rounds with these `wins`: a
+/// player has won more than half of the rounds, or they have all been played.
+pub fn match_over(wins: &[i64], round: i64, rounds: i64) -> bool {
+    let rounds = rounds.max(1);
+    wins.iter().any(|wins| *wins > rounds / 2) || round >= rounds
+}
+
+/// The player with the most wins, if no one else has as many.
+pub fn match_leader(wins: &[i64]) -> Option<usize> {
+    let best = wins.iter().copied().max().filter(|wins| *wins > 0)?;
+    let mut leaders = wins.iter().enumerate().filter(|(_, wins)| **wins == best);
+
+    match (leaders.next(), leaders.next()) {
+        (Some((index, _)), None) => Some(index),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn best_of_three_ends_at_two_wins() {
+        assert!(!match_over(&[1, 0], 1, 3));
+        assert!(!match_over(&[1, 1], 2, 3));
+        assert!(match_over(&[2, 0], 2, 3));
+        assert!(match_over(&[0, 2, 0], 2, 3));
+    }
+
+    #[test]
+    fn best_of_four_needs_three_wins() {
+        assert!(!match_over(&[2, 1], 3, 4));
+        assert!(match_over(&[3, 0], 3, 4));
+    }
+
+    #[test]
+    fn match_ends_once_every_round_is_played() {
+        // Draws leave no one with a majority.
+        assert!(!match_over(&[1, 1, 0], 2, 3));
+        assert!(match_over(&[1, 1, 0], 3, 3));
+        assert!(match_over(&[0, 0], 3, 3));
+    }
+
+    #[test]
+    fn fewer_than_one_round_plays_one() {
+        assert!(match_over(&[0, 0], 1, 0));
+        assert!(match_over(&[1, 0], 1, -2));
+    }
+
+    #[test]
+    fn leader_has_the_most_wins() {
+        assert_eq!(match_leader(&[1, 2, 0]), Some(1));
+        assert_eq!(match_leader(&[3]), Some(0));
+    }
+
+    #[test]
+    fn tied_or_winless_match_has_no_leader() {
+        assert_eq!(match_leader(&[1, 1, 0]), None);
+        assert_eq!(match_leader(&[0, 0]), None);
+        assert_eq!(match_leader(&[]), None);
+    }
+}
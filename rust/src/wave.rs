@@ -0,0 +1,444 @@
+use godot::{classes::RandomNumberGenerator, prelude::*};
+
+use crate::wave_json;
+
+/// Most enemies a single `SpawnEvent` sends.
+pub const MAX_EVENT_COUNT: i64 = 256;
+
+/// Side of the spawn path's bounds a `SpawnEvent` comes from. Positions along it run
+/// clockwise, like the path: left to right along the top, top to bottom on the right.
+#[derive(GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[godot(via = i64)]
+pub enum SpawnEdge {
+    #[default]
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl SpawnEdge {
+    pub const ALL: [SpawnEdge; 4] = [
+        SpawnEdge::Top,
+        SpawnEdge::Right,
+        SpawnEdge::Bottom,
+        SpawnEdge::Left,
+    ];
+
+    /// Name of the edge in wave files.
+    pub fn key(self) -> &'static str {
+        match self {
+            SpawnEdge::Top => "top",
+            SpawnEdge::Right => "right",
+            SpawnEdge::Bottom => "bottom",
+            SpawnEdge::Left => "left",
+        }
+    }
+
+    /// Point `t` of the way along the edge of `bounds`, from 0 to 1.
+    pub fn point(self, bounds: Rect2, t: f32) -> Vector2 {
+        let (from, to) = match self {
+            SpawnEdge::Top => (
+                bounds.position,
+                bounds.position + Vector2::new(bounds.size.x, 0f32),
+            ),
+            SpawnEdge::Right => (
+                bounds.position + Vector2::new(bounds.size.x, 0f32),
+                bounds.end(),
+            ),
+            SpawnEdge::Bottom => (
+                bounds.end(),
+                bounds.position + Vector2::new(0f32, bounds.size.y),
+            ),
+            SpawnEdge::Left => (
+                bounds.position + Vector2::new(0f32, bounds.size.y),
+                bounds.position,
+            ),
+        };
+
+        from.lerp(to, t)
+    }
+
+    /// Direction in which positions along the edge grow.
+    pub fn along(self) -> Vector2 {
+        match self {
+            SpawnEdge::Top => Vector2::RIGHT,
+            SpawnEdge::Right => Vector2::DOWN,
+            SpawnEdge::Bottom => Vector2::LEFT,
+            SpawnEdge::Left => Vector2::UP,
+        }
+    }
+
+    /// Direction pointing from the edge into the bounds.
+    pub fn inward(self) -> Vector2 {
+        match self {
+            SpawnEdge::Top => Vector2::DOWN,
+            SpawnEdge::Right => Vector2::LEFT,
+            SpawnEdge::Bottom => Vector2::UP,
+            SpawnEdge::Left => Vector2::RIGHT,
+        }
+    }
+}
+
+/// How the enemies of a `SpawnEvent` are laid out.
+#[derive(GodotConvert, Var, Export, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[godot(via = i64)]
+pub enum Formation {
+    /// Each enemy at a random point of the segment.
+    #[default]
+    Scatter,
+    /// Spread evenly across the segment.
+    Line,
+    /// One behind the other from the middle of the segment, `spacing` apart.
+    Column,
+    /// A V from the middle of the segment, its tip leading.
+    Wedge,
+}
+
+impl Formation {
+    pub const ALL: [Formation; 4] = [
+        Formation::Scatter,
+        Formation::Line,
+        Formation::Column,
+        Formation::Wedge,
+    ];
+
+    /// Name of the formation in wave files.
+    pub fn key(self) -> &'static str {
+        match self {
+            Formation::Scatter => "scatter",
+            Formation::Line => "line",
+            Formation::Column => "column",
+            Formation::Wedge => "wedge",
+        }
+    }
+}
+
+/// Enemies a `Wave` sends at a given time.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct SpawnEvent {
+    #[base]
+    base: Base<Resource>,
+    /// Seconds from the start of the wave.
+    #[export(range = (0.0, 600.0, or_greater))]
+    pub time: f64,
+    /// Enemies sent, at most `MAX_EVENT_COUNT`.
+    #[export(range = (1.0, 64.0, or_greater))]
+    pub count: i64,
+    #[export]
+    pub edge: SpawnEdge,
+    /// Where the segment of the edge the enemies come from starts, from 0 to 1.
+    #[export(range = (0.0, 1.0))]
+    pub segment_start: f32,
+    #[export(range = (0.0, 1.0))]
+    pub segment_end: f32,
+    /// Degrees the enemies turn clockwise away from heading straight in.
+    #[export(range = (-90.0, 90.0))]
+    pub angle: f32,
+    /// Pixels per second; 0 picks one in the enemy's own range.
+    #[export(range = (0.0, 1000.0, or_greater))]
+    pub speed: f32,
+    /// Animation name of the enemies, e.g. `flying`; empty picks one at random.
+    #[export]
+    pub archetype: StringName,
+    #[export]
+    pub formation: Formation,
+    /// Pixels between neighbours in a column or wedge.
+    #[export(range = (0.0, 256.0, or_greater))]
+    pub spacing: f32,
+}
+
+#[godot_api]
+impl IResource for SpawnEvent {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            time: 0f64,
+            count: 1,
+            edge: SpawnEdge::Top,
+            segment_start: 0f32,
+            segment_end: 1f32,
+            angle: 0f32,
+            speed: 0f32,
+            archetype: StringName::default(),
+            formation: Formation::Scatter,
+            spacing: 48f32,
+        }
+    }
+}
+
+impl SpawnEvent {
+    /// Where each enemy of the event starts, on or behind the edge of `bounds`. Scattered
+    /// enemies draw their spot from `rng`.
+    pub fn spawn_points(&self, bounds: Rect2, rng: &mut Gd<RandomNumberGenerator>) -> Vec<Vector2> {
+        let (start, end) = (self.segment_start, self.segment_end.max(self.segment_start));
+        let centre = self.edge.point(bounds, (start + end) / 2f32);
+        let (along, behind) = (self.edge.along(), -self.edge.inward());
+        let count = self.count.clamp(1, MAX_EVENT_COUNT);
+
+        (0..count)
+            .map(|index| match self.formation {
+                Formation::Scatter => {
+                    let t = rng.randf_range(start, end);
+                    self.edge.point(bounds, t)
+                }
+                Formation::Line => {
+                    let t = start + (end - start) * (index as f32 + 0.5f32) / count as f32;
+                    self.edge.point(bounds, t)
+                }
+                Formation::Column => centre + behind * self.spacing * index as f32,
+                Formation::Wedge => {
+                    let rank = ((index + 1) / 2) as f32;
+                    let side = if index % 2 == 1 { 1f32 } else { -1f32 };
+                    centre + (along * side + behind) * self.spacing * rank
+                }
+            })
+            .collect()
+    }
+
+    /// Heading of the enemies, as a unit vector.
+    pub fn direction(&self) -> Vector2 {
+        self.edge.inward().rotated(self.angle.to_radians())
+    }
+}
+
+/// Timed `SpawnEvent`s played together.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct Wave {
+    #[base]
+    base: Base<Resource>,
+    #[export]
+    pub events: Array<Gd<SpawnEvent>>,
+    /// Seconds before the next wave starts; never shorter than it takes the last event to
+    /// come.
+    #[export(range = (0.0, 600.0, or_greater))]
+    pub duration: f64,
+}
+
+#[godot_api]
+impl IResource for Wave {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            events: Array::new(),
+            duration: 0f64,
+        }
+    }
+}
+
+/// Waves a run plays in sequence instead of its random spawns. Authored in the editor, or
+/// in a JSON file read with `load_json`.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct WaveScript {
+    #[base]
+    base: Base<Resource>,
+    #[export]
+    pub waves: Array<Gd<Wave>>,
+    /// Whether the first wave follows the last; otherwise random spawns take over.
+    #[export]
+    pub looped: bool,
+}
+
+#[godot_api]
+impl IResource for WaveScript {
+    fn init(base: Base<Self::Base>) -> Self {
+        Self {
+            base,
+            waves: Array::new(),
+            looped: false,
+        }
+    }
+}
+
+#[godot_api]
+impl WaveScript {
+    /// Reads the waves of the JSON file at `path`. Every problem found is reported with its
+    /// line, and makes it return null.
+    #[func]
+    pub fn load_json(path: GString) -> Option<Gd<WaveScript>> {
+        wave_json::load(&path.to_string())
+            .inspect_err(|errors| {
+                for error in errors {
+                    godot_warn!("{path}:{error}");
+                }
+            })
+            .ok()
+    }
+}
+
+/// Plays a `WaveScript`: hands out its events as their time comes, wave after wave.
+pub struct WavePlayer<E = Gd<SpawnEvent>> {
+    /// Events of each wave sorted by time, with the seconds the wave lasts.
+    waves: Vec<(Vec<(f64, E)>, f64)>,
+    looped: bool,
+    wave: usize,
+    next_event: usize,
+    elapsed: f64,
+}
+
+impl WavePlayer {
+    pub fn new(script: &Gd<WaveScript>) -> Self {
+        let script = script.bind();
+
+        let waves = script
+            .waves
+            .iter_shared()
+            .map(|wave| {
+                let wave = wave.bind();
+                let events = wave
+                    .events
+                    .iter_shared()
+                    .map(|event| {
+                        let time = event.bind().time;
+                        (time, event)
+                    })
+                    .collect();
+
+                (events, wave.duration)
+            })
+            .collect();
+
+        Self::from_waves(waves, script.looped)
+    }
+}
+
+impl<E: Clone> WavePlayer<E> {
+    /// Plays `waves`, each a list of events with their time and the seconds the wave lasts.
+    /// A wave never ends before its last event.
+    pub fn from_waves(waves: Vec<(Vec<(f64, E)>, f64)>, looped: bool) -> Self {
+        let waves = waves
+            .into_iter()
+            .map(|(mut events, duration)| {
+                events.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+                let last = events.last().map_or(0f64, |(time, _)| *time);
+                (events, duration.max(last))
+            })
+            .collect();
+
+        Self {
+            waves,
+            looped,
+            wave: 0,
+            next_event: 0,
+            elapsed: 0f64,
+        }
+    }
+
+    /// Whether every wave has played and the script doesn't loop.
+    pub fn is_finished(&self) -> bool {
+        self.wave >= self.waves.len()
+    }
+
+    /// Moves `delta` seconds on and returns the events due by then.
+    pub fn advance(&mut self, delta: f64) -> Vec<E> {
+        let mut due = Vec::new();
+        self.elapsed += delta;
+
+        // Bounded, so a looped script of empty waves can't spin forever.
+        for _ in 0..=self.waves.len() {
+            let Some((events, duration)) = self.waves.get(self.wave) else {
+                break;
+            };
+
+            while let Some((time, event)) = events.get(self.next_event) {
+                if *time > self.elapsed {
+                    return due;
+                }
+
+                due.push(event.clone());
+                self.next_event += 1;
+            }
+
+            if self.elapsed < *duration {
+                break;
+            }
+
+            self.elapsed -= duration;
+            self.next_event = 0;
+            self.wave += 1;
+
+            if self.wave == self.waves.len() && self.looped {
+                self.wave = 0;
+            }
+        }
+
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(looped: bool) -> WavePlayer<&'static str> {
+        WavePlayer::from_waves(
+            vec![
+                (vec![(1f64, "b"), (0f64, "a"), (1f64, "c")], 2f64),
+                (vec![(0.5f64, "d")], 0f64),
+            ],
+            looped,
+        )
+    }
+
+    #[test]
+    fn events_come_in_time_order() {
+        let mut waves = player(false);
+
+        assert_eq!(waves.advance(0f64), ["a"]);
+        assert!(waves.advance(0.5f64).is_empty());
+        assert_eq!(waves.advance(0.5f64), ["b", "c"]);
+        assert!(waves.advance(0.5f64).is_empty());
+    }
+
+    #[test]
+    fn next_wave_starts_when_the_last_one_ends() {
+        let mut waves = player(false);
+
+        assert_eq!(waves.advance(2f64), ["a", "b", "c"]);
+        assert!(!waves.is_finished());
+        // The second wave lasts as long as its last event, with the time left over carried.
+        assert!(waves.advance(0.4f64).is_empty());
+        assert_eq!(waves.advance(0.1f64), ["d"]);
+        assert!(waves.is_finished());
+        assert!(waves.advance(10f64).is_empty());
+    }
+
+    #[test]
+    fn a_large_delta_catches_up_across_waves() {
+        let mut waves = player(false);
+
+        assert_eq!(waves.advance(3f64), ["a", "b", "c", "d"]);
+        assert!(waves.is_finished());
+    }
+
+    #[test]
+    fn looped_script_wraps_to_the_first_wave() {
+        let mut waves = player(true);
+
+        assert_eq!(waves.advance(2.25f64), ["a", "b", "c"]);
+        // The second wave ends with "d", and the first one starts over right away.
+        assert_eq!(waves.advance(0.25f64), ["d", "a"]);
+        assert!(!waves.is_finished());
+        assert_eq!(waves.advance(1f64), ["b", "c"]);
+    }
+
+    #[test]
+    fn looped_empty_waves_dont_spin() {
+        let mut waves = WavePlayer::<&str>::from_waves(vec![(Vec::new(), 0f64)], true);
+
+        assert!(waves.advance(1f64).is_empty());
+        assert!(!waves.is_finished());
+    }
+
+    #[test]
+    fn script_without_waves_is_finished() {
+        let mut waves = WavePlayer::<&str>::from_waves(Vec::new(), true);
+
+        assert!(waves.is_finished());
+        assert!(waves.advance(1f64).is_empty());
+    }
+}
@@ -0,0 +1,875 @@
+use core::fmt;
+use std::{iter::Peekable, ops::RangeInclusive, str::Chars};
+
+use godot::{classes::FileAccess, global::Error, prelude::*};
+
+use crate::{
+    localization::{TextKey, tr},
+    wave::{Formation, MAX_EVENT_COUNT, SpawnEdge, SpawnEvent, Wave, WaveScript},
+};
+
+/// A problem found in a wave file, at `line` (0 when the file couldn't be read).
+#[derive(Clone, PartialEq, Debug)]
+pub struct WaveError {
+    pub line: usize,
+    pub kind: WaveErrorKind,
+}
+
+/// What is wrong in a wave file. Fields are named by their key, empty for the whole file.
+#[derive(Clone, PartialEq, Debug)]
+pub enum WaveErrorKind {
+    Read,
+    /// The text ends in the middle of a value.
+    End,
+    Unexpected(char),
+    UnknownField(String),
+    MissingField(String),
+    /// The field holds another kind of value, e.g. a string instead of a number.
+    Type(String),
+    Range(String),
+    /// The field's name isn't one of the values it takes.
+    Name(String),
+}
+
+impl WaveError {
+    fn new(line: usize, kind: WaveErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+impl fmt::Display for WaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (key, detail) = match &self.kind {
+            WaveErrorKind::Read => (TextKey::ErrorWaveRead, String::new()),
+            WaveErrorKind::End => (TextKey::ErrorWaveEnd, String::new()),
+            WaveErrorKind::Unexpected(c) => (TextKey::ErrorWaveUnexpected, format!("{c:?}")),
+            WaveErrorKind::UnknownField(field) => (TextKey::ErrorWaveUnknownField, quote(field)),
+            WaveErrorKind::MissingField(field) => (TextKey::ErrorWaveMissingField, quote(field)),
+            WaveErrorKind::Type(field) => (TextKey::ErrorWaveType, quote(field)),
+            WaveErrorKind::Range(field) => (TextKey::ErrorWaveRange, quote(field)),
+            WaveErrorKind::Name(field) => (TextKey::ErrorWaveName, quote(field)),
+        };
+        let message = format!("{} {detail}", tr(key));
+
+        match self.line {
+            0 => write!(f, " {}", message.trim_end()),
+            line => write!(f, "{line}: {}", message.trim_end()),
+        }
+    }
+}
+
+/// Reads the waves of the JSON file at `path`.
+pub fn load(path: &str) -> Result<Gd<WaveScript>, Vec<WaveError>> {
+    let text = FileAccess::get_file_as_string(path);
+
+    if text.is_empty() && FileAccess::get_open_error() != Error::OK {
+        return Err(vec![WaveError::new(0, WaveErrorKind::Read)]);
+    }
+
+    parse(&text.to_string())
+}
+
+/// Builds a `WaveScript` from JSON `text`, shaped like:
+///
+/// ```json
+/// { "loop": true, "waves": [ { "duration": 6, "events": [
+///     { "time": 0, "count": 5, "edge": "top", "segment": [0.2, 0.8], "angle": 0,
+///       "speed": 220, "archetype": "walk", "formation": "line", "spacing": 48 } ] } ] }
+/// ```
+///
+/// Only `waves`, `events`, `time` and `edge` are required. Every problem is reported, not
+/// just the first one.
+pub fn parse(text: &str) -> Result<Gd<WaveScript>, Vec<WaveError>> {
+    read(text).map(|script| script.to_resource())
+}
+
+/// Checks JSON `text` and reads it into plain data, without any Godot object.
+fn read(text: &str) -> Result<ScriptData, Vec<WaveError>> {
+    let root = Parser::new(text).document().map_err(|error| vec![error])?;
+
+    let mut errors = Vec::new();
+    let script = read_script(&root, &mut errors);
+    errors.sort_by_key(|error| error.line);
+
+    if errors.is_empty() {
+        Ok(script)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Contents of a `WaveScript`, as read from a file.
+#[derive(Clone, PartialEq, Debug, Default)]
+struct ScriptData {
+    looped: bool,
+    waves: Vec<WaveData>,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+struct WaveData {
+    duration: f64,
+    events: Vec<EventData>,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+struct EventData {
+    time: f64,
+    count: i64,
+    edge: SpawnEdge,
+    segment: (f32, f32),
+    angle: f32,
+    speed: f32,
+    archetype: String,
+    formation: Formation,
+    spacing: f32,
+}
+
+impl ScriptData {
+    fn to_resource(&self) -> Gd<WaveScript> {
+        let waves = self.waves.iter().map(WaveData::to_resource).collect();
+
+        let mut script = WaveScript::new_gd();
+        {
+            let mut bound = script.bind_mut();
+            bound.looped = self.looped;
+            bound.waves = waves;
+        }
+
+        script
+    }
+}
+
+impl WaveData {
+    fn to_resource(&self) -> Gd<Wave> {
+        let events = self.events.iter().map(EventData::to_resource).collect();
+
+        let mut wave = Wave::new_gd();
+        {
+            let mut bound = wave.bind_mut();
+            bound.duration = self.duration;
+            bound.events = events;
+        }
+
+        wave
+    }
+}
+
+impl EventData {
+    fn to_resource(&self) -> Gd<SpawnEvent> {
+        let mut event = SpawnEvent::new_gd();
+        {
+            let mut bound = event.bind_mut();
+            bound.time = self.time;
+            bound.count = self.count;
+            bound.edge = self.edge;
+            bound.segment_start = self.segment.0;
+            bound.segment_end = self.segment.1;
+            bound.angle = self.angle;
+            bound.speed = self.speed;
+            bound.archetype = StringName::from(&self.archetype);
+            bound.formation = self.formation;
+            bound.spacing = self.spacing;
+        }
+
+        event
+    }
+}
+
+fn read_script(json: &Json, errors: &mut Vec<WaveError>) -> ScriptData {
+    let Some(object) = Object::new(json, "", &["loop", "waves"], errors) else {
+        return ScriptData::default();
+    };
+
+    ScriptData {
+        looped: object.bool("loop", errors),
+        waves: object
+            .array("waves", errors)
+            .iter()
+            .map(|wave| read_wave(wave, errors))
+            .collect(),
+    }
+}
+
+fn read_wave(json: &Json, errors: &mut Vec<WaveError>) -> WaveData {
+    let Some(object) = Object::new(json, "waves", &["duration", "events"], errors) else {
+        return WaveData::default();
+    };
+
+    WaveData {
+        duration: object.number("duration", Some(0f64), 0f64..=f64::MAX, errors),
+        events: object
+            .array("events", errors)
+            .iter()
+            .map(|event| read_event(event, errors))
+            .collect(),
+    }
+}
+
+fn read_event(json: &Json, errors: &mut Vec<WaveError>) -> EventData {
+    const FIELDS: [&str; 9] = [
+        "time",
+        "count",
+        "edge",
+        "segment",
+        "angle",
+        "speed",
+        "archetype",
+        "formation",
+        "spacing",
+    ];
+
+    let Some(object) = Object::new(json, "events", &FIELDS, errors) else {
+        return EventData::default();
+    };
+
+    EventData {
+        time: object.number("time", None, 0f64..=f64::MAX, errors),
+        count: object.integer("count", 1, 1..=MAX_EVENT_COUNT, errors),
+        edge: object
+            .name("edge", None, &SpawnEdge::ALL, SpawnEdge::key, errors)
+            .unwrap_or_default(),
+        segment: object.segment("segment", errors),
+        angle: object.number("angle", Some(0f64), -90f64..=90f64, errors) as f32,
+        speed: object.number("speed", Some(0f64), 0f64..=f64::MAX, errors) as f32,
+        archetype: object.string("archetype", errors),
+        formation: object
+            .name(
+                "formation",
+                Some(Formation::Scatter),
+                &Formation::ALL,
+                Formation::key,
+                errors,
+            )
+            .unwrap_or_default(),
+        spacing: object.number("spacing", Some(48f64), 0f64..=f64::MAX, errors) as f32,
+    }
+}
+
+/// Fields of a JSON object, read one by one. Problems are pushed to the caller's errors and
+/// leave the field at its default.
+struct Object<'a> {
+    line: usize,
+    entries: &'a [(String, Json)],
+}
+
+impl<'a> Object<'a> {
+    /// Reports `json` when it isn't an object, and each of its fields not in `known`. `name`
+    /// is the field holding it, for the error.
+    fn new(
+        json: &'a Json,
+        name: &str,
+        known: &[&str],
+        errors: &mut Vec<WaveError>,
+    ) -> Option<Self> {
+        let Value::Object(entries) = &json.value else {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Type(name.to_string()),
+            ));
+            return None;
+        };
+
+        for (key, value) in entries {
+            if !known.contains(&key.as_str()) {
+                errors.push(WaveError::new(
+                    value.line,
+                    WaveErrorKind::UnknownField(key.to_string()),
+                ));
+            }
+        }
+
+        Some(Self {
+            line: json.line,
+            entries,
+        })
+    }
+
+    /// The value of `key`. A missing one is reported unless `optional`.
+    fn get(&self, key: &str, optional: bool, errors: &mut Vec<WaveError>) -> Option<&'a Json> {
+        let value = self
+            .entries
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value);
+
+        if value.is_none() && !optional {
+            errors.push(WaveError::new(
+                self.line,
+                WaveErrorKind::MissingField(key.to_string()),
+            ));
+        }
+
+        value
+    }
+
+    fn number(
+        &self,
+        key: &str,
+        default: Option<f64>,
+        range: RangeInclusive<f64>,
+        errors: &mut Vec<WaveError>,
+    ) -> f64 {
+        let fallback = default.unwrap_or(*range.start());
+        let Some(json) = self.get(key, default.is_some(), errors) else {
+            return fallback;
+        };
+
+        let Value::Number(number) = json.value else {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Type(key.to_string()),
+            ));
+            return fallback;
+        };
+
+        if !range.contains(&number) {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Range(key.to_string()),
+            ));
+            return fallback;
+        }
+
+        number
+    }
+
+    fn integer(
+        &self,
+        key: &str,
+        default: i64,
+        range: RangeInclusive<i64>,
+        errors: &mut Vec<WaveError>,
+    ) -> i64 {
+        let Some(json) = self.get(key, true, errors) else {
+            return default;
+        };
+
+        let number = match json.value {
+            Value::Number(number) if number.fract() == 0f64 => number as i64,
+            _ => {
+                errors.push(WaveError::new(
+                    json.line,
+                    WaveErrorKind::Type(key.to_string()),
+                ));
+                return default;
+            }
+        };
+
+        if !range.contains(&number) {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Range(key.to_string()),
+            ));
+            return default;
+        }
+
+        number
+    }
+
+    fn bool(&self, key: &str, errors: &mut Vec<WaveError>) -> bool {
+        let Some(json) = self.get(key, true, errors) else {
+            return false;
+        };
+
+        let Value::Bool(value) = json.value else {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Type(key.to_string()),
+            ));
+            return false;
+        };
+
+        value
+    }
+
+    fn string(&self, key: &str, errors: &mut Vec<WaveError>) -> String {
+        let Some(json) = self.get(key, true, errors) else {
+            return String::new();
+        };
+
+        let Value::String(value) = &json.value else {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Type(key.to_string()),
+            ));
+            return String::new();
+        };
+
+        value.clone()
+    }
+
+    fn array(&self, key: &str, errors: &mut Vec<WaveError>) -> &'a [Json] {
+        let Some(json) = self.get(key, false, errors) else {
+            return &[];
+        };
+
+        let Value::Array(items) = &json.value else {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Type(key.to_string()),
+            ));
+            return &[];
+        };
+
+        items
+    }
+
+    /// One of `values`, written as its `name`.
+    fn name<T: Copy>(
+        &self,
+        key: &str,
+        default: Option<T>,
+        values: &[T],
+        name: fn(T) -> &'static str,
+        errors: &mut Vec<WaveError>,
+    ) -> Option<T> {
+        let Some(json) = self.get(key, default.is_some(), errors) else {
+            return default;
+        };
+
+        let Value::String(text) = &json.value else {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Type(key.to_string()),
+            ));
+            return default;
+        };
+
+        let value = values.iter().copied().find(|value| name(*value) == text);
+
+        if value.is_none() {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Name(key.to_string()),
+            ));
+        }
+
+        value.or(default)
+    }
+
+    /// A `[start, end]` pair with `0 <= start <= end <= 1`; the whole edge when missing.
+    fn segment(&self, key: &str, errors: &mut Vec<WaveError>) -> (f32, f32) {
+        let Some(json) = self.get(key, true, errors) else {
+            return (0f32, 1f32);
+        };
+
+        let bounds = match &json.value {
+            Value::Array(items) => match items.as_slice() {
+                [start, end] => match (&start.value, &end.value) {
+                    (Value::Number(start), Value::Number(end)) => Some((*start, *end)),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let Some((start, end)) = bounds else {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Type(key.to_string()),
+            ));
+            return (0f32, 1f32);
+        };
+
+        if !(0f64..=1f64).contains(&start) || !(start..=1f64).contains(&end) {
+            errors.push(WaveError::new(
+                json.line,
+                WaveErrorKind::Range(key.to_string()),
+            ));
+            return (0f32, 1f32);
+        }
+
+        (start as f32, end as f32)
+    }
+}
+
+fn quote(key: &str) -> String {
+    if key.is_empty() {
+        String::new()
+    } else {
+        format!("`{key}`")
+    }
+}
+
+/// A JSON value and the line it starts on.
+#[derive(Clone, Debug)]
+struct Json {
+    line: usize,
+    value: Value,
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Fields in the order they are written.
+    Object(Vec<(String, Json)>),
+}
+
+/// Minimal JSON reader that keeps track of lines, which Godot's `JSON` only does for syntax
+/// errors.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+            line: 1,
+        }
+    }
+
+    /// A single value, with nothing but whitespace around it.
+    fn document(&mut self) -> Result<Json, WaveError> {
+        let value = self.value()?;
+        self.skip_whitespace();
+
+        match self.chars.peek().copied() {
+            Some(c) => Err(self.unexpected(c)),
+            None => Ok(value),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, WaveError> {
+        self.skip_whitespace();
+        let line = self.line;
+
+        let value = match self.peek()? {
+            '{' => self.object()?,
+            '[' => self.array()?,
+            '"' => Value::String(self.string()?),
+            '-' | '0'..='9' => self.number()?,
+            'a'..='z' => self.literal()?,
+            c => return Err(self.unexpected(c)),
+        };
+
+        Ok(Json { line, value })
+    }
+
+    fn object(&mut self) -> Result<Value, WaveError> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+
+        if self.peek()? == '}' {
+            self.chars.next();
+            return Ok(Value::Object(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace();
+
+            match self.next()? {
+                ',' => continue,
+                '}' => return Ok(Value::Object(entries)),
+                c => return Err(self.unexpected(c)),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, WaveError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+
+        if self.peek()? == ']' {
+            self.chars.next();
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.next()? {
+                ',' => continue,
+                ']' => return Ok(Value::Array(items)),
+                c => return Err(self.unexpected(c)),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, WaveError> {
+        self.expect('"')?;
+        let mut text = String::new();
+
+        loop {
+            match self.next()? {
+                '"' => return Ok(text),
+                '\n' => return Err(self.unexpected('\n')),
+                '\\' => {
+                    let escaped = match self.next()? {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'u' => {
+                            let code =
+                                (0..4).map(|_| self.next()).collect::<Result<String, _>>()?;
+                            u32::from_str_radix(&code, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        c @ ('"' | '\\' | '/') => c,
+                        c => return Err(self.unexpected(c)),
+                    };
+                    text.push(escaped);
+                }
+                c => text.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, WaveError> {
+        let mut text = String::new();
+
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            text.push(c);
+        }
+
+        text.parse()
+            .map(Value::Number)
+            .map_err(|_| self.unexpected(text.chars().next().unwrap_or('-')))
+    }
+
+    fn literal(&mut self) -> Result<Value, WaveError> {
+        let mut word = String::new();
+
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            word.push(c);
+        }
+
+        match word.as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            "null" => Ok(Value::Null),
+            _ => Err(self.unexpected(word.chars().next().unwrap_or('?'))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.chars.next_if(|c| c.is_whitespace()) {
+            if c == '\n' {
+                self.line += 1;
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), WaveError> {
+        match self.next()? {
+            c if c == expected => Ok(()),
+            c => Err(self.unexpected(c)),
+        }
+    }
+
+    fn peek(&mut self) -> Result<char, WaveError> {
+        let line = self.line;
+        self.chars
+            .peek()
+            .copied()
+            .ok_or_else(|| WaveError::new(line, WaveErrorKind::End))
+    }
+
+    fn next(&mut self) -> Result<char, WaveError> {
+        let c = self.peek()?;
+        self.chars.next();
+        Ok(c)
+    }
+
+    fn unexpected(&self, c: char) -> WaveError {
+        WaveError::new(self.line, WaveErrorKind::Unexpected(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(text: &str) -> Vec<WaveError> {
+        read(text).expect_err("the text has problems")
+    }
+
+    fn error(line: usize, kind: WaveErrorKind) -> WaveError {
+        WaveError::new(line, kind)
+    }
+
+    #[test]
+    fn reads_every_field() {
+        let script = read(
+            r#"{ "loop": true, "waves": [ { "duration": 6, "events": [
+                { "time": 1.5, "count": 5, "edge": "left", "segment": [0.2, 0.8],
+                  "angle": -30, "speed": 220, "archetype": "walk", "formation": "wedge",
+                  "spacing": 32 } ] } ] }"#,
+        )
+        .expect("a valid script");
+
+        let event = EventData {
+            time: 1.5f64,
+            count: 5,
+            edge: SpawnEdge::Left,
+            segment: (0.2f32, 0.8f32),
+            angle: -30f32,
+            speed: 220f32,
+            archetype: "walk".to_string(),
+            formation: Formation::Wedge,
+            spacing: 32f32,
+        };
+        let expected = ScriptData {
+            looped: true,
+            waves: vec![WaveData {
+                duration: 6f64,
+                events: vec![event],
+            }],
+        };
+
+        assert_eq!(script, expected);
+    }
+
+    #[test]
+    fn optional_fields_take_their_defaults() {
+        let script = read(r#"{ "waves": [ { "events": [ { "time": 0, "edge": "top" } ] } ] }"#)
+            .expect("a valid script");
+
+        assert!(!script.looped);
+        assert_eq!(script.waves[0].duration, 0f64);
+
+        let event = &script.waves[0].events[0];
+        assert_eq!(event.count, 1);
+        assert_eq!(event.segment, (0f32, 1f32));
+        assert_eq!(event.formation, Formation::Scatter);
+        assert_eq!(event.spacing, 48f32);
+        assert!(event.archetype.is_empty());
+    }
+
+    #[test]
+    fn syntax_errors_stop_at_the_first_one() {
+        assert_eq!(
+            errors("{\n  \"waves\": [\n    1 2\n  ]\n}"),
+            [error(3, WaveErrorKind::Unexpected('2'))]
+        );
+        assert_eq!(
+            errors("{ \"waves\": tru }"),
+            [error(1, WaveErrorKind::Unexpected('t'))]
+        );
+        assert_eq!(
+            errors("{ \"waves\": [] } }"),
+            [error(1, WaveErrorKind::Unexpected('}'))]
+        );
+    }
+
+    #[test]
+    fn text_ending_early_is_reported_on_its_last_line() {
+        assert_eq!(
+            errors("{\n  \"waves\": [\n"),
+            [error(3, WaveErrorKind::End)]
+        );
+        assert_eq!(errors(""), [error(1, WaveErrorKind::End)]);
+    }
+
+    #[test]
+    fn unknown_fields_are_reported_where_their_value_is() {
+        let text = "{\n  \"waves\": [],\n  \"speed\": 3\n}";
+
+        assert_eq!(
+            errors(text),
+            [error(3, WaveErrorKind::UnknownField("speed".to_string()))]
+        );
+    }
+
+    #[test]
+    fn missing_fields_are_reported_on_their_object() {
+        let text = "{ \"waves\": [\n  { \"events\": [\n    { \"count\": 2 }\n  ] }\n] }";
+
+        assert_eq!(
+            errors(text),
+            [
+                error(3, WaveErrorKind::MissingField("time".to_string())),
+                error(3, WaveErrorKind::MissingField("edge".to_string())),
+            ]
+        );
+        assert_eq!(
+            errors("{}"),
+            [error(1, WaveErrorKind::MissingField("waves".to_string()))]
+        );
+    }
+
+    #[test]
+    fn values_out_of_range_are_reported() {
+        let event = |fields: &str| {
+            errors(&format!(
+                r#"{{ "waves": [ {{ "events": [ {{ "time": 0, "edge": "top", {fields} }} ] }} ] }}"#
+            ))
+        };
+        let range = |field: &str| [error(1, WaveErrorKind::Range(field.to_string()))];
+
+        assert_eq!(event(r#""count": 0"#), range("count"));
+        assert_eq!(event(r#""count": 257"#), range("count"));
+        assert_eq!(event(r#""count": 9223372036854775807"#), range("count"));
+        assert_eq!(event(r#""angle": 90.5"#), range("angle"));
+        assert_eq!(event(r#""speed": -1"#), range("speed"));
+        assert_eq!(event(r#""segment": [0.8, 0.2]"#), range("segment"));
+        assert_eq!(event(r#""segment": [0, 1.5]"#), range("segment"));
+        assert!(read(&format!(
+            r#"{{ "waves": [ {{ "events": [ {{ "time": 0, "edge": "top", "count": {MAX_EVENT_COUNT} }} ] }} ] }}"#
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn wrong_types_and_names_are_reported() {
+        let text = r#"{ "loop": 1, "waves": [ { "events": [
+            { "time": "soon", "edge": "up", "count": 1.5, "formation": 3 } ] } ] }"#;
+
+        assert_eq!(
+            errors(text),
+            [
+                error(1, WaveErrorKind::Type("loop".to_string())),
+                error(2, WaveErrorKind::Type("time".to_string())),
+                error(2, WaveErrorKind::Type("count".to_string())),
+                error(2, WaveErrorKind::Name("edge".to_string())),
+                error(2, WaveErrorKind::Type("formation".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_problem_is_reported_in_line_order() {
+        let text = "{\n\
+            \"waves\": [\n\
+              { \"events\": [\n\
+                { \"time\": 0, \"edge\": \"top\", \"count\": 0 },\n\
+                { \"time\": -1, \"edge\": \"top\" }\n\
+              ], \"duration\": -2 },\n\
+              4\n\
+            ],\n\
+            \"extra\": null\n\
+            }";
+
+        assert_eq!(
+            errors(text),
+            [
+                error(4, WaveErrorKind::Range("count".to_string())),
+                error(5, WaveErrorKind::Range("time".to_string())),
+                error(6, WaveErrorKind::Range("duration".to_string())),
+                error(7, WaveErrorKind::Type("waves".to_string())),
+                error(9, WaveErrorKind::UnknownField("extra".to_string())),
+            ]
+        );
+    }
+}